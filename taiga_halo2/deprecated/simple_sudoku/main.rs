@@ -1,4 +0,0 @@
-pub mod circuit;
-pub mod resource_logic;
-
-pub fn main() {}
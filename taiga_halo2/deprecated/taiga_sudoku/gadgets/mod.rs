@@ -1,3 +0,0 @@
-pub mod state_check;
-pub mod state_update;
-pub mod value_check;
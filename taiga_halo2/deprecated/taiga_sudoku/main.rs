@@ -1,4 +0,0 @@
-pub mod app_resource_logic;
-pub mod dealer_intent_app_resource_logic;
-pub mod gadgets;
-fn main() {}
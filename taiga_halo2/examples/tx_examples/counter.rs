@@ -0,0 +1,134 @@
+/// Counter app with the state transition framework
+/// Alice starts a counter and increases it by 1, then increases it by 2 in the next ptx.
+///
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::state_transition::{
+        counter::{
+            create_counter_genesis_resource, CounterResourceLogicCircuit, CounterTransition,
+        },
+        create_state_resource, StateTransition,
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::SpendingKey,
+    merkle_tree::MerklePath,
+    resource::{Resource, ResourceLogics},
+    resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+};
+
+// Consume the counter resource and create the next one. Returns the ptx and the next counter resource.
+pub fn create_counter_ptx<R: RngCore>(
+    mut rng: R,
+    counter_resource: Resource,
+    step: u64,
+    owner_sk: &SpendingKey,
+) -> (ShieldedPartialTransaction, Resource) {
+    let transition = CounterTransition::new(step);
+
+    // output resource
+    let mut next_counter_resource = create_state_resource(
+        &mut rng,
+        counter_resource.get_logic(),
+        counter_resource.get_label(),
+        transition.transition(counter_resource.value),
+        owner_sk.get_npk(),
+    );
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            counter_resource,
+            merkle_path,
+            None,
+            &mut next_counter_resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = counter_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = next_counter_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+    let input_witness = ResourceExistenceWitness::new(
+        counter_resource,
+        resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap(),
+    );
+    let output_witness = ResourceExistenceWitness::new(
+        next_counter_resource,
+        resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap(),
+    );
+
+    // Create resource logics for the counter resources
+    let input_resource_logics = {
+        let circuit = CounterResourceLogicCircuit {
+            self_resource: input_witness,
+            paired_resource: output_witness,
+            transition: transition.clone(),
+        };
+        ResourceLogics::new(Box::new(circuit), vec![])
+    };
+    let output_resource_logics = {
+        let circuit = CounterResourceLogicCircuit {
+            self_resource: output_witness,
+            paired_resource: input_witness,
+            transition,
+        };
+        ResourceLogics::new(Box::new(circuit), vec![])
+    };
+
+    // Create shielded partial tx
+    let ptx = ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![output_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap();
+
+    // The owner can spend the next counter resource with the nullifier key
+    next_counter_resource.nk_container = owner_sk.get_nk_container();
+
+    (ptx, next_counter_resource)
+}
+
+pub fn create_counter_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
+    let alice_sk = SpendingKey::random(&mut rng);
+    // The label identifies the counter
+    let label = pallas::Base::from(7u64);
+
+    // Start the counter and increase it by 1
+    let genesis_resource = create_counter_genesis_resource(label);
+    let (ptx_1, counter_resource) = create_counter_ptx(&mut rng, genesis_resource, 1, &alice_sk);
+
+    // Increase the counter by 2
+    let (ptx_2, counter_resource) = create_counter_ptx(&mut rng, counter_resource, 2, &alice_sk);
+    assert_eq!(counter_resource.value, pallas::Base::from(3u64));
+
+    // Create the final transaction
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![ptx_1, ptx_2]);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build(&mut rng, shielded_tx_bundle, transparent_ptx_bundle).unwrap()
+}
+
+#[test]
+fn test_counter_tx() {
+    use rand::rngs::OsRng;
+
+    let tx = create_counter_transaction(OsRng);
+    tx.execute().unwrap();
+}
@@ -0,0 +1,222 @@
+/// Hash time locked token transfer
+/// Alice locks 5 "BTC" to Bob with a hashlock and a deadline at height 100
+/// Claim: Bob reveals the preimage in a transaction valid from height 50 to 99
+/// Refund: Alice takes the "BTC" back in a transaction valid from height 100 to 150
+/// The lock and the spend partial transactions are in the same transaction.
+///
+use crate::token::create_token_lock_ptx;
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        htlc::{HtlcPolicy, HtlcSpend},
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{Token, TokenAuthorization, TokenResource},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::MerklePath,
+    resource_tree::ResourceMerkleTreeLeaves,
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{
+        ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle, ValidityWindow,
+    },
+};
+
+const DEADLINE: u64 = 100;
+
+// The htlc auth. The nullifier key and the encryption key are shared by the sender and the
+// receiver.
+pub fn create_htlc_auth(policy: &HtlcPolicy, htlc_sk: &SpendingKey) -> TokenAuthorization {
+    policy.to_token_authorization(htlc_sk.get_ivk().get_encryption_pk())
+}
+
+pub fn create_htlc_ptx<R: RngCore>(
+    mut rng: R,
+    input_resource: &TokenResource,
+    htlc_sk: &SpendingKey,
+    policy: HtlcPolicy,
+    spend: HtlcSpend,
+    window: ValidityWindow,
+    output_address: &PaymentAddress,
+) -> ShieldedPartialTransaction {
+    let htlc_auth = create_htlc_auth(&policy, htlc_sk);
+
+    // output resource
+    let output_auth = TokenAuthorization::from_address(output_address);
+    let output_token = Token::new(input_resource.token_name().inner(), input_resource.quantity);
+    let mut output_resource =
+        output_token.create_random_output_token_resource_to_address(&mut rng, output_address);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut output_resource.resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the locked resource
+    let input_token_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_token_resource_logics_with_htlc(
+            &mut rng,
+            htlc_auth,
+            policy,
+            spend,
+            window,
+            merkle_path,
+        )
+    };
+
+    // Create resource logics for the output resource
+    let output_token_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        output_resource.generate_output_token_resource_logics(&mut rng, output_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_token_resource_logics],
+        vec![output_token_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+// Alice locks the "BTC" to Bob, and one of them spends it in the window
+pub fn create_htlc_ptxs<R: RngCore>(
+    mut rng: R,
+    is_claim: bool,
+    window: ValidityWindow,
+) -> Vec<ShieldedPartialTransaction> {
+    let btc_token = Token::new("btc".to_string(), 5);
+
+    let alice_sk = SpendingKey::random(&mut rng);
+    let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+    // Alice creates the key of the locked resource and shares it with Bob
+    let htlc_sk = SpendingKey::random(&mut rng);
+
+    // Bob knows the preimage and shares the hashlock with Alice
+    let preimage = pallas::Base::from(2024u64);
+    let policy = HtlcPolicy::new(
+        HtlcPolicy::compute_hashlock(preimage),
+        DEADLINE,
+        bob_sk.get_auth_pk(),
+        alice_sk.get_auth_pk(),
+    );
+
+    // Alice locks the "BTC"
+    let htlc_auth = create_htlc_auth(&policy, &htlc_sk);
+    let (lock_ptx, htlc_resource) =
+        create_token_lock_ptx(&mut rng, &btc_token, &alice_sk, &htlc_sk, htlc_auth);
+
+    let spend_ptx = if is_claim {
+        let spend = HtlcSpend::Claim {
+            preimage,
+            receiver_sk: bob_sk.get_auth_sk(),
+        };
+        create_htlc_ptx(
+            &mut rng,
+            &htlc_resource,
+            &htlc_sk,
+            policy,
+            spend,
+            window,
+            &bob_address,
+        )
+    } else {
+        let spend = HtlcSpend::Refund {
+            sender_sk: alice_sk.get_auth_sk(),
+        };
+        create_htlc_ptx(
+            &mut rng,
+            &htlc_resource,
+            &htlc_sk,
+            policy,
+            spend,
+            window,
+            &alice_address,
+        )
+    };
+
+    vec![lock_ptx, spend_ptx]
+}
+
+pub fn create_htlc_transaction<R: RngCore + CryptoRng>(
+    mut rng: R,
+    is_claim: bool,
+    window: ValidityWindow,
+) -> Transaction {
+    let ptxs = create_htlc_ptxs(&mut rng, is_claim, window);
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(ptxs);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build_with_validity_window(
+        &mut rng,
+        shielded_tx_bundle,
+        transparent_ptx_bundle,
+        window,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_htlc_tx() {
+    use rand::rngs::OsRng;
+    use taiga_halo2::error::TransactionError;
+
+    let mut rng = OsRng;
+    // Bob claims before the deadline
+    let tx = create_htlc_transaction(&mut rng, true, ValidityWindow::new(50, DEADLINE - 1));
+    tx.execute().unwrap();
+
+    // Bob claims after the deadline
+    let tx = create_htlc_transaction(&mut rng, true, ValidityWindow::new(50, DEADLINE));
+    assert!(tx.execute().is_err());
+
+    // Alice takes the refund after the deadline
+    let tx = create_htlc_transaction(&mut rng, false, ValidityWindow::new(DEADLINE, 150));
+    tx.execute().unwrap();
+
+    // Alice takes the refund before the deadline
+    let tx = create_htlc_transaction(&mut rng, false, ValidityWindow::new(50, 150));
+    assert!(tx.execute().is_err());
+
+    // The claim is proved against a window that doesn't contain the transaction window
+    let ptxs = create_htlc_ptxs(&mut rng, true, ValidityWindow::new(50, DEADLINE - 1));
+    let tx = Transaction::build_with_validity_window(
+        &mut rng,
+        ShieldedPartialTxBundle::new(ptxs),
+        TransparentPartialTxBundle::default(),
+        ValidityWindow::new(50, 150),
+    )
+    .unwrap();
+    assert!(matches!(
+        tx.execute(),
+        Err(TransactionError::InvalidValidityWindow)
+    ));
+}
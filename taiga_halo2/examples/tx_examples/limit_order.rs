@@ -0,0 +1,283 @@
+/// The limit order can be partially filled at any price no worse than the limit.
+/// Alice sells up to 10 "BTC" for at least 3 "ETH" per 2 "BTC" until the height 100, and creates
+/// a limit order intent for it.
+/// Bob has 7 "ETH" and wants 4 "BTC".
+/// The Solver/Bob fills 4 "BTC" of Alice's order for 7 "ETH" and returns 6 "BTC" back to Alice.
+///
+use crate::token::create_token_swap_ptx;
+use halo2_proofs::arithmetic::Field;
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        limit_order_intent::{LimitOrder, LimitOrderIntentResourceLogicCircuit},
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{Token, TokenAuthorization, TokenName, TokenResource},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::SpendingKey,
+    merkle_tree::{Anchor, MerklePath},
+    resource::{Resource, ResourceLogics},
+    resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{
+        ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle, ValidityWindow,
+    },
+};
+
+pub fn create_limit_order_ptx<R: RngCore>(
+    mut rng: R,
+    sell: Token,
+    buy: TokenName,
+    price: (u64, u64),
+    expires_at: u64,
+    input_sk: &SpendingKey,
+) -> (ShieldedPartialTransaction, LimitOrder, Resource) {
+    let input_auth =
+        TokenAuthorization::from_address(&input_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+    let order = LimitOrder::random(
+        &mut rng, sell, buy, price.0, price.1, expires_at, input_auth,
+    );
+    let mut intent_resource = order.create_intent_resource(&mut rng);
+
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs, the intent resource is paired with the sell resource
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *order.sell.resource(),
+            merkle_path.clone(),
+            None,
+            &mut intent_resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf = order.sell.resource().get_nf().unwrap().inner();
+    let output_resource_cm = intent_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf, output_resource_cm]);
+
+    // Create input resource logics
+    let input_merkle_path = resource_merkle_tree
+        .generate_path(input_resource_nf)
+        .unwrap();
+    let input_resource_logics = order.sell.generate_input_token_resource_logics(
+        &mut rng,
+        input_auth,
+        input_sk.get_auth_sk(),
+        input_merkle_path,
+    );
+
+    // Create intent resource logics
+    let intent_resource_logics = {
+        let sell_resource_witness =
+            ResourceExistenceWitness::new(*order.sell.resource(), input_merkle_path);
+
+        let intent_resource_witness = {
+            let merkle_path = resource_merkle_tree
+                .generate_path(output_resource_cm)
+                .unwrap();
+            ResourceExistenceWitness::new(intent_resource, merkle_path)
+        };
+
+        let intent_circuit = LimitOrderIntentResourceLogicCircuit {
+            self_resource: intent_resource_witness,
+            sell_resource: sell_resource_witness,
+            offer_resource: ResourceExistenceWitness::default(), // a dummy resource
+            returned_resource: ResourceExistenceWitness::default(), // a dummy resource
+            order: order.clone(),
+            given_quantity: 0,
+            window: ValidityWindow::default(),
+        };
+
+        ResourceLogics::new(Box::new(intent_circuit), vec![])
+    };
+
+    // Create shielded partial tx
+    let ptx = ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![intent_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap();
+
+    (ptx, order, intent_resource)
+}
+
+pub fn fill_limit_order_ptx<R: RngCore>(
+    mut rng: R,
+    order: LimitOrder,
+    intent_resource: Resource,
+    given: u64,
+    received: u64,
+    window: ValidityWindow,
+) -> ShieldedPartialTransaction {
+    let (mut offer_resource, mut returned_resource) =
+        order.fill(&mut rng, given, received).unwrap();
+    let padding_input_resource = Resource::random_padding_resource(&mut rng);
+
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Fetch a valid anchor for dummy resources
+    let anchor = Anchor::from(pallas::Base::random(&mut rng));
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            intent_resource,
+            merkle_path.clone(),
+            Some(anchor),
+            &mut offer_resource,
+            &mut rng,
+        );
+
+        let compliance_2 = ComplianceInfo::new(
+            padding_input_resource,
+            merkle_path,
+            Some(anchor),
+            &mut returned_resource,
+            &mut rng,
+        );
+        vec![compliance_1, compliance_2]
+    };
+
+    let intent_nf = intent_resource.get_nf().unwrap().inner();
+    let offer_cm = offer_resource.commitment().inner();
+    let padding_nf = padding_input_resource.get_nf().unwrap().inner();
+    let returned_cm = returned_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![intent_nf, offer_cm, padding_nf, returned_cm]);
+
+    // Create resource logics
+    let (input_resource_logics, output_resource_logics) = {
+        let intent_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(intent_nf).unwrap();
+            ResourceExistenceWitness::new(intent_resource, merkle_path)
+        };
+
+        let offer_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(offer_cm).unwrap();
+            ResourceExistenceWitness::new(offer_resource, merkle_path)
+        };
+
+        let padding_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(padding_nf).unwrap();
+            ResourceExistenceWitness::new(padding_input_resource, merkle_path)
+        };
+
+        let returned_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(returned_cm).unwrap();
+            ResourceExistenceWitness::new(returned_resource, merkle_path)
+        };
+
+        // Create resource_logics for the intent
+        let intent_resource_logics = {
+            let intent_resource_logic = LimitOrderIntentResourceLogicCircuit {
+                self_resource: intent_resource_witness,
+                sell_resource: padding_resource_witness, // a dummy one
+                offer_resource: offer_resource_witness,
+                returned_resource: returned_resource_witness,
+                order: order.clone(),
+                given_quantity: given,
+                window,
+            };
+
+            ResourceLogics::new(Box::new(intent_resource_logic), vec![])
+        };
+
+        // Create resource_logics for the offer_resource
+        let offer_resource_logics = TokenResource {
+            token_name: order.buy.clone(),
+            resource: offer_resource,
+            allowlist_root: None,
+        }
+        .generate_output_token_resource_logics(
+            &mut rng,
+            order.auth,
+            offer_resource_witness.get_path(),
+        );
+
+        // Create resource_logics for the padding input
+        let padding_input_resource_logics = ResourceLogics::create_padding_resource_resource_logics(
+            padding_input_resource,
+            padding_resource_witness.get_path(),
+        );
+
+        // Create resource_logics for the returned_resource
+        let returned_resource_logics = TokenResource {
+            token_name: order.sell.token_name().clone(),
+            resource: returned_resource,
+            allowlist_root: None,
+        }
+        .generate_output_token_resource_logics(
+            &mut rng,
+            order.auth,
+            returned_resource_witness.get_path(),
+        );
+
+        (
+            vec![intent_resource_logics, padding_input_resource_logics],
+            vec![offer_resource_logics, returned_resource_logics],
+        )
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        input_resource_logics,
+        output_resource_logics,
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+pub fn create_limit_order_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
+    // Alice creates the partial transaction with:
+    // - 10 BTC sell
+    // - intent output asking at least 3 ETH per 2 BTC until the height 100
+    let alice_sk = SpendingKey::random(&mut rng);
+    let sell = Token::new("btc".to_string(), 10u64);
+    let buy = TokenName::new("eth".to_string());
+    let (alice_ptx, order, intent_resource) =
+        create_limit_order_ptx(&mut rng, sell, buy, (3, 2), 100, &alice_sk);
+
+    // Bob creates the partial transaction with 7 ETH input and 4 BTC output
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let offer = Token::new("eth".to_string(), 7);
+    let bought = Token::new("btc".to_string(), 4);
+    let bob_ptx = create_token_swap_ptx(&mut rng, offer, &bob_sk, bought, &bob_address);
+
+    // Solver/Bob fills 4 BTC of the order for 7 ETH before the expiry, and returns 6 BTC to Alice
+    let window = ValidityWindow::new(0, 100);
+    let solver_ptx = fill_limit_order_ptx(&mut rng, order, intent_resource, 4, 7, window);
+
+    // Solver creates the final transaction
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![alice_ptx, bob_ptx, solver_ptx]);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build_with_validity_window(
+        &mut rng,
+        shielded_tx_bundle,
+        transparent_ptx_bundle,
+        window,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_limit_order_partial_fill_tx() {
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    let tx = create_limit_order_transaction(&mut rng);
+    let ret = tx.execute().unwrap();
+    assert_eq!(ret.validity_window, ValidityWindow::new(0, 100));
+}
@@ -1,9 +1,18 @@
+mod counter;
+mod htlc;
+mod limit_order;
+mod multisig;
+mod nft_marketplace;
 mod partial_fulfillment_token_swap;
+mod sudoku;
+mod three_party_barter;
 mod token;
+mod token_issuance;
 mod token_swap_with_intent;
 mod token_swap_without_intent;
 fn main() {
     use rand::rngs::OsRng;
+    use taiga_halo2::transaction::ValidityWindow;
 
     let rng = OsRng;
     let tx = token_swap_without_intent::create_token_swap_transaction(rng);
@@ -14,4 +23,32 @@ fn main() {
 
     let tx = partial_fulfillment_token_swap::create_token_swap_transaction(rng);
     tx.execute().unwrap();
+
+    let tx = limit_order::create_limit_order_transaction(rng);
+    tx.execute().unwrap();
+
+    let tx = token_issuance::create_token_issuance_transaction(rng);
+    tx.execute().unwrap();
+
+    let tx = htlc::create_htlc_transaction(rng, true, ValidityWindow::new(50, 99));
+    tx.execute().unwrap();
+
+    let tx = htlc::create_htlc_transaction(rng, false, ValidityWindow::new(100, 150));
+    tx.execute().unwrap();
+
+    let tx = multisig::create_multisig_transaction(rng, &[0, 2]).unwrap();
+    tx.execute().unwrap();
+
+    let tx = nft_marketplace::create_nft_marketplace_transaction(rng);
+    tx.execute().unwrap();
+
+    let tx = three_party_barter::create_three_party_barter_transaction(rng);
+    tx.execute().unwrap();
+
+    let (start_tx, solve_tx) = sudoku::create_sudoku_transactions(rng);
+    start_tx.execute().unwrap();
+    solve_tx.execute().unwrap();
+
+    let tx = counter::create_counter_transaction(rng);
+    tx.execute().unwrap();
 }
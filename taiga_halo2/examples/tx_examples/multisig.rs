@@ -0,0 +1,153 @@
+/// Multisig token transfer
+/// Eve sends 5 "BTC" to Alice, Bob and Carol with a 2-of-3 multisig policy
+/// Alice and Carol sign to send the "BTC" to Dave
+///
+use crate::token::create_token_lock_ptx;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        multisig_verification::MultisigPolicy,
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{Token, TokenAuthorization, TokenResource},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    error::TransactionError,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::MerklePath,
+    resource_tree::ResourceMerkleTreeLeaves,
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+};
+
+// The multisig auth. The nullifier key and the encryption key are shared by the group.
+pub fn create_multisig_auth(policy: &MultisigPolicy, group_sk: &SpendingKey) -> TokenAuthorization {
+    policy.to_token_authorization(group_sk.get_ivk().get_encryption_pk())
+}
+
+pub fn create_multisig_ptx<R: RngCore>(
+    mut rng: R,
+    input_resource: &TokenResource,
+    group_sk: &SpendingKey,
+    policy: MultisigPolicy,
+    signer_sks: &[SpendingKey],
+    output_address: &PaymentAddress,
+) -> Result<ShieldedPartialTransaction, TransactionError> {
+    let multisig_auth = create_multisig_auth(&policy, group_sk);
+
+    // output resource
+    let output_auth = TokenAuthorization::from_address(output_address);
+    let output_token = Token::new(input_resource.token_name().inner(), input_resource.quantity);
+    let mut output_resource =
+        output_token.create_random_output_token_resource_to_address(&mut rng, output_address);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut output_resource.resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the multisig resource
+    let input_token_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        let signer_auth_sks: Vec<_> = signer_sks.iter().map(|sk| sk.get_auth_sk()).collect();
+        input_resource.generate_input_token_resource_logics_with_multisig(
+            &mut rng,
+            multisig_auth,
+            policy,
+            &signer_auth_sks,
+            merkle_path,
+        )?
+    };
+
+    // Create resource logics for the output resource
+    let output_token_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        output_resource.generate_output_token_resource_logics(&mut rng, output_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_token_resource_logics],
+        vec![output_token_resource_logics],
+        vec![],
+        &mut rng,
+    )
+}
+
+pub fn create_multisig_transaction<R: RngCore + CryptoRng>(
+    mut rng: R,
+    signers: &[usize],
+) -> Result<Transaction, TransactionError> {
+    let btc_token = Token::new("btc".to_string(), 5);
+
+    // Alice, Bob and Carol
+    let owner_sks: Vec<SpendingKey> = (0..3).map(|_| SpendingKey::random(&mut rng)).collect();
+    let owner_pks: Vec<_> = owner_sks.iter().map(|sk| sk.get_auth_pk()).collect();
+    let policy = MultisigPolicy::new(2, &owner_pks)?;
+    let group_sk = SpendingKey::random(&mut rng);
+
+    let dave_sk = SpendingKey::random(&mut rng);
+    let dave_address = dave_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+    // Eve sends the "BTC" to the group
+    let eve_sk = SpendingKey::random(&mut rng);
+    let multisig_auth = create_multisig_auth(&policy, &group_sk);
+    let (lock_ptx, multisig_resource) =
+        create_token_lock_ptx(&mut rng, &btc_token, &eve_sk, &group_sk, multisig_auth);
+
+    // The signers spend the multisig resource
+    let signer_sks: Vec<SpendingKey> = signers.iter().map(|i| owner_sks[*i]).collect();
+    let spend_ptx = create_multisig_ptx(
+        &mut rng,
+        &multisig_resource,
+        &group_sk,
+        policy,
+        &signer_sks,
+        &dave_address,
+    )?;
+
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![lock_ptx, spend_ptx]);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build(&mut rng, shielded_tx_bundle, transparent_ptx_bundle)
+}
+
+#[test]
+fn test_multisig_tx() {
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    // Alice and Carol sign
+    let tx = create_multisig_transaction(&mut rng, &[0, 2]).unwrap();
+    tx.execute().unwrap();
+
+    // All of them sign
+    let tx = create_multisig_transaction(&mut rng, &[0, 1, 2]).unwrap();
+    tx.execute().unwrap();
+
+    // Only Bob signs, the proof of the multisig logic is invalid
+    if let Ok(tx) = create_multisig_transaction(&mut rng, &[1]) {
+        assert!(tx.execute().is_err());
+    }
+}
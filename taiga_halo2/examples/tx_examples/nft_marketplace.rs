@@ -0,0 +1,318 @@
+/// NFT mint and marketplace swap
+/// The issuer mints the NFT "punks #7" to Alice by consuming the mint resource
+/// Alice sells the NFT to Bob for 10 "DAI", the NFT is transferred in her partial transaction
+/// Bob pays 10 "DAI"
+///
+use halo2_proofs::arithmetic::Field;
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        nft::Nft,
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{Token, TokenAuthorization},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::{Anchor, MerklePath},
+    resource::{Resource, ResourceLogics},
+    resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+};
+
+pub fn create_nft_mint_ptx<R: RngCore>(
+    mut rng: R,
+    nft: &Nft,
+    issuer_sk: &SpendingKey,
+    owner_address: &PaymentAddress,
+) -> ShieldedPartialTransaction {
+    // The mint resource
+    let input_resource = nft.create_mint_resource();
+
+    // The minted NFT
+    let output_auth = TokenAuthorization::from_address(owner_address);
+    let mut output_resource = nft.create_random_output_resource_to_address(&mut rng, owner_address);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut output_resource.resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the mint resource
+    let input_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_resource_logics(
+            &mut rng,
+            nft.issuer_auth(),
+            issuer_sk.get_auth_sk(),
+            merkle_path,
+            None,
+        )
+    };
+
+    // Create resource logics for the minted NFT
+    let output_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        output_resource.generate_output_resource_logics(&mut rng, output_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![output_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+// The seller transfers the NFT to the buyer and receives the price
+pub fn create_nft_sell_ptx<R: RngCore>(
+    mut rng: R,
+    nft: &Nft,
+    seller_sk: &SpendingKey,
+    buyer_address: &PaymentAddress,
+    price: &Token,
+) -> ShieldedPartialTransaction {
+    let seller_address = seller_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let seller_auth = TokenAuthorization::from_address(&seller_address);
+    let buyer_auth = TokenAuthorization::from_address(buyer_address);
+
+    // input resources
+    let input_resource = nft.create_random_input_resource_from_key(&mut rng, seller_sk);
+    let padding_input_resource = Resource::random_padding_resource(&mut rng);
+
+    // output resources
+    let mut transferred_resource =
+        nft.create_random_output_resource_to_address(&mut rng, buyer_address);
+    let mut price_resource =
+        price.create_random_output_token_resource_to_address(&mut rng, &seller_address);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Fetch a valid anchor for padding input resources
+    let anchor = Anchor::from(pallas::Base::random(&mut rng));
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path.clone(),
+            None,
+            &mut transferred_resource.resource,
+            &mut rng,
+        );
+
+        let compliance_2 = ComplianceInfo::new(
+            padding_input_resource,
+            merkle_path,
+            Some(anchor),
+            &mut price_resource.resource,
+            &mut rng,
+        );
+
+        vec![compliance_1, compliance_2]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = transferred_resource.commitment().inner();
+    let input_resource_nf_2 = padding_input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_2 = price_resource.commitment().inner();
+    let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![
+        input_resource_nf_1,
+        output_resource_cm_1,
+        input_resource_nf_2,
+        output_resource_cm_2,
+    ]);
+
+    let transferred_resource_witness = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        ResourceExistenceWitness::new(*transferred_resource.resource(), merkle_path)
+    };
+
+    // Create resource logics for the NFT
+    let input_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_resource_logics(
+            &mut rng,
+            seller_auth,
+            seller_sk.get_auth_sk(),
+            merkle_path,
+            Some((transferred_resource_witness, buyer_auth)),
+        )
+    };
+
+    // Create resource logics for the padding input
+    let padding_input_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_2)
+            .unwrap();
+        ResourceLogics::create_padding_resource_resource_logics(padding_input_resource, merkle_path)
+    };
+
+    // Create resource logics for the transferred NFT
+    let transferred_resource_logics = transferred_resource.generate_output_resource_logics(
+        &mut rng,
+        buyer_auth,
+        transferred_resource_witness.get_path(),
+    );
+
+    // Create resource logics for the price
+    let price_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_2)
+            .unwrap();
+        price_resource.generate_output_token_resource_logics(&mut rng, seller_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics, padding_input_resource_logics],
+        vec![transferred_resource_logics, price_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+// The buyer spends the price
+pub fn create_nft_buy_ptx<R: RngCore>(
+    mut rng: R,
+    price: &Token,
+    buyer_sk: &SpendingKey,
+) -> ShieldedPartialTransaction {
+    let buyer_address = buyer_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let buyer_auth = TokenAuthorization::from_address(&buyer_address);
+
+    // input resource
+    let input_resource = price.create_random_input_token_resource_from_key(&mut rng, buyer_sk);
+
+    // output resource
+    let mut padding_output_resource = Resource::random_padding_resource(&mut rng);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut padding_output_resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = padding_output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the price
+    let input_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_token_resource_logics(
+            &mut rng,
+            buyer_auth,
+            buyer_sk.get_auth_sk(),
+            merkle_path,
+        )
+    };
+
+    // Create resource logics for the padding output
+    let output_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        ResourceLogics::create_padding_resource_resource_logics(
+            padding_output_resource,
+            merkle_path,
+        )
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![output_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+pub fn create_nft_marketplace_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
+    let issuer_sk = SpendingKey::random(&mut rng);
+    let nft = Nft::from_issuer_key(
+        "punks".to_string(),
+        &issuer_sk,
+        pallas::Base::from(7u64),
+        Nft::hash_metadata(b"ipfs://punk-7"),
+    );
+    let dai_token = Token::new("dai".to_string(), 10);
+
+    let alice_sk = SpendingKey::random(&mut rng);
+    let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+    // The issuer mints the NFT to Alice
+    let mint_ptx = create_nft_mint_ptx(&mut rng, &nft, &issuer_sk, &alice_address);
+
+    // Alice transfers the NFT to Bob for 10 "DAI"
+    let sell_ptx = create_nft_sell_ptx(&mut rng, &nft, &alice_sk, &bob_address, &dai_token);
+
+    // Bob pays 10 "DAI"
+    let buy_ptx = create_nft_buy_ptx(&mut rng, &dai_token, &bob_sk);
+
+    // The NFT and the "DAI" are balanced across the partial transactions
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![mint_ptx, sell_ptx, buy_ptx]);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build(&mut rng, shielded_tx_bundle, transparent_ptx_bundle).unwrap()
+}
+
+#[test]
+fn test_nft_marketplace_tx() {
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    let tx = create_nft_marketplace_transaction(&mut rng);
+    tx.execute().unwrap();
+}
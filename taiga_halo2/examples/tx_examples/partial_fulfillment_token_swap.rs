@@ -4,9 +4,8 @@
 /// The Solver/Bob can partially fulfill Alice's intent and return 1 "BTC" back to Alice.
 ///
 use crate::token::create_token_swap_ptx;
-use group::Group;
 use halo2_proofs::arithmetic::Field;
-use pasta_curves::{group::Curve, pallas};
+use pasta_curves::pallas;
 use rand::{CryptoRng, RngCore};
 use taiga_halo2::{
     circuit::resource_logic_examples::{
@@ -16,8 +15,8 @@ use taiga_halo2::{
     },
     compliance::ComplianceInfo,
     constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::{PaymentAddress, SpendingKey},
     merkle_tree::{Anchor, MerklePath},
-    nullifier::NullifierKeyContainer,
     resource::{Resource, ResourceLogics},
     resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
     shielded_ptx::ShieldedPartialTransaction,
@@ -28,9 +27,10 @@ pub fn create_token_intent_ptx<R: RngCore>(
     mut rng: R,
     sell: Token,
     buy: Token,
-    input_auth_sk: pallas::Scalar,
+    input_sk: &SpendingKey,
 ) -> (ShieldedPartialTransaction, Swap, Resource) {
-    let input_auth = TokenAuthorization::from_sk_vk(&input_auth_sk, &COMPRESSED_TOKEN_AUTH_VK);
+    let input_auth =
+        TokenAuthorization::from_address(&input_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
     let swap = Swap::random(&mut rng, sell, buy, input_auth);
     let mut intent_resource = swap.create_intent_resource(&mut rng);
 
@@ -62,7 +62,7 @@ pub fn create_token_intent_ptx<R: RngCore>(
     let input_resource_logics = swap.sell.generate_input_token_resource_logics(
         &mut rng,
         input_auth,
-        input_auth_sk,
+        input_sk.get_auth_sk(),
         input_merkle_path,
     );
 
@@ -102,19 +102,18 @@ pub fn create_token_intent_ptx<R: RngCore>(
     (ptx, swap, intent_resource)
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn consume_token_intent_ptx<R: RngCore>(
     mut rng: R,
     swap: Swap,
     intent_resource: Resource,
     offer: Token,
-    output_auth_pk: pallas::Point,
+    output_address: &PaymentAddress,
 ) -> ShieldedPartialTransaction {
-    let (mut offer_resource, mut returned_resource) = swap.fill(&mut rng, offer);
+    let (mut offer_resource, mut returned_resource) = swap.fill(&mut rng, offer).unwrap();
     let padding_input_resource = Resource::random_padding_resource(&mut rng);
 
     // output resources
-    let output_auth = TokenAuthorization::new(output_auth_pk, *COMPRESSED_TOKEN_AUTH_VK);
+    let output_auth = TokenAuthorization::from_address(output_address);
     let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
 
     // Fetch a valid anchor for dummy resources
@@ -186,6 +185,7 @@ pub fn consume_token_intent_ptx<R: RngCore>(
         let bought_resource_resource_logics = TokenResource {
             token_name: swap.buy.name().clone(),
             resource: offer_resource,
+            allowlist_root: None,
         }
         .generate_output_token_resource_logics(
             &mut rng,
@@ -203,6 +203,7 @@ pub fn consume_token_intent_ptx<R: RngCore>(
         let returned_resource_resource_logics = TokenResource {
             token_name: swap.sell.token_name().clone(),
             resource: returned_resource,
+            allowlist_root: None,
         }
         .generate_output_token_resource_logics(
             &mut rng,
@@ -231,39 +232,28 @@ pub fn consume_token_intent_ptx<R: RngCore>(
 }
 
 pub fn create_token_swap_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
-    let generator = pallas::Point::generator().to_affine();
-
     // Alice creates the partial transaction with:
     // - 2 BTC sell
     // - intent output encoding 10 ETH ask
-    let alice_auth_sk = pallas::Scalar::random(&mut rng);
-    let alice_auth_pk = generator * alice_auth_sk;
+    let alice_sk = SpendingKey::random(&mut rng);
+    let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
     let sell = Token::new("btc".to_string(), 2u64);
     let buy = Token::new("eth".to_string(), 10u64);
     let (alice_ptx, swap, intent_resource) =
-        create_token_intent_ptx(&mut rng, sell.clone(), buy.clone(), alice_auth_sk);
+        create_token_intent_ptx(&mut rng, sell.clone(), buy.clone(), &alice_sk);
 
     // Bob creates the partial transaction with 1 DOLPHIN input and 5 BTC output
-    let bob_auth_sk = pallas::Scalar::random(&mut rng);
-    let bob_auth_pk = generator * bob_auth_sk;
-    let bob_nk = NullifierKeyContainer::random_key(&mut rng);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
     let offer = Token::new("eth".to_string(), 5);
     let returned = Token::new("btc".to_string(), 1);
 
-    let bob_ptx = create_token_swap_ptx(
-        &mut rng,
-        offer.clone(),
-        bob_auth_sk,
-        bob_nk.get_nk().unwrap(),
-        returned,
-        bob_auth_pk,
-        bob_nk.get_npk(),
-    );
+    let bob_ptx = create_token_swap_ptx(&mut rng, offer.clone(), &bob_sk, returned, &bob_address);
 
     // Solver/Bob creates the partial transaction to consume the intent resource
     // The bob_ptx and solver_ptx can be merged to one ptx.
     let solver_ptx =
-        consume_token_intent_ptx(&mut rng, swap, intent_resource, offer, alice_auth_pk);
+        consume_token_intent_ptx(&mut rng, swap, intent_resource, offer, &alice_address);
 
     // Solver creates the final transaction
     let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![alice_ptx, bob_ptx, solver_ptx]);
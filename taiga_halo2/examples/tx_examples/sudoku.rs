@@ -0,0 +1,421 @@
+/// Sudoku game with a dealer intent
+/// The dealer publishes a puzzle and an intent paying 10 "DAI" to whoever solves it.
+/// Alice starts the game and fills some cells in the first tx.
+/// Alice solves the puzzle and claims the reward from the dealer intent in the second tx.
+///
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        sudoku::{
+            create_sudoku_genesis_resource, create_sudoku_resource,
+            dealer_intent::{create_intent_resource, DealerIntentResourceLogicCircuit},
+            SudokuResourceLogicCircuit, SudokuState,
+        },
+        token::{Token, TokenAuthorization},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::SpendingKey,
+    merkle_tree::MerklePath,
+    resource::{Resource, ResourceLogics},
+    resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+};
+
+pub fn puzzle() -> SudokuState {
+    SudokuState::new([
+        [5, 0, 1, 6, 7, 2, 4, 3, 9],
+        [7, 0, 2, 8, 4, 3, 6, 5, 1],
+        [3, 0, 4, 5, 9, 1, 7, 8, 2],
+        [4, 0, 8, 9, 5, 7, 2, 1, 6],
+        [2, 0, 6, 1, 8, 4, 9, 7, 3],
+        [1, 0, 9, 3, 2, 6, 8, 4, 5],
+        [8, 0, 5, 2, 1, 9, 3, 6, 7],
+        [9, 0, 3, 7, 6, 8, 5, 2, 4],
+        [6, 0, 7, 4, 3, 5, 1, 9, 8],
+    ])
+}
+
+pub fn partial_solution() -> SudokuState {
+    SudokuState::new([
+        [5, 8, 1, 6, 7, 2, 4, 3, 9],
+        [7, 9, 2, 8, 4, 3, 6, 5, 1],
+        [3, 6, 4, 5, 9, 1, 7, 8, 2],
+        [4, 3, 8, 9, 5, 7, 2, 1, 6],
+        [2, 0, 6, 1, 8, 4, 9, 7, 3],
+        [1, 0, 9, 3, 2, 6, 8, 4, 5],
+        [8, 0, 5, 2, 1, 9, 3, 6, 7],
+        [9, 0, 3, 7, 6, 8, 5, 2, 4],
+        [6, 0, 7, 4, 3, 5, 1, 9, 8],
+    ])
+}
+
+pub fn solution() -> SudokuState {
+    SudokuState::new([
+        [5, 8, 1, 6, 7, 2, 4, 3, 9],
+        [7, 9, 2, 8, 4, 3, 6, 5, 1],
+        [3, 6, 4, 5, 9, 1, 7, 8, 2],
+        [4, 3, 8, 9, 5, 7, 2, 1, 6],
+        [2, 5, 6, 1, 8, 4, 9, 7, 3],
+        [1, 7, 9, 3, 2, 6, 8, 4, 5],
+        [8, 4, 5, 2, 1, 9, 3, 6, 7],
+        [9, 1, 3, 7, 6, 8, 5, 2, 4],
+        [6, 2, 7, 4, 3, 5, 1, 9, 8],
+    ])
+}
+
+// Create the resource logics of a move consuming the previous board and creating the current board
+fn create_sudoku_move_resource_logics(
+    resource_merkle_tree: &ResourceMerkleTreeLeaves,
+    puzzle: &SudokuState,
+    input_resource: Resource,
+    output_resource: Resource,
+    previous_state: &SudokuState,
+    current_state: &SudokuState,
+) -> (ResourceLogics, ResourceLogics) {
+    let input_merkle_path = resource_merkle_tree
+        .generate_path(input_resource.get_nf().unwrap().inner())
+        .unwrap();
+    let output_merkle_path = resource_merkle_tree
+        .generate_path(output_resource.commitment().inner())
+        .unwrap();
+    let input_witness = ResourceExistenceWitness::new(input_resource, input_merkle_path);
+    let output_witness = ResourceExistenceWitness::new(output_resource, output_merkle_path);
+
+    let input_resource_logics = {
+        let circuit = SudokuResourceLogicCircuit {
+            self_resource: input_witness,
+            next_resource: output_witness,
+            puzzle: puzzle.clone(),
+            previous_state: previous_state.clone(),
+            current_state: current_state.clone(),
+        };
+        ResourceLogics::new(Box::new(circuit), vec![])
+    };
+
+    let output_resource_logics = {
+        let circuit = SudokuResourceLogicCircuit {
+            self_resource: output_witness,
+            // the next resource won't be checked.
+            next_resource: output_witness,
+            puzzle: puzzle.clone(),
+            previous_state: SudokuState::default(),
+            current_state: current_state.clone(),
+        };
+        ResourceLogics::new(Box::new(circuit), vec![])
+    };
+
+    (input_resource_logics, output_resource_logics)
+}
+
+// The player consumes the sudoku resource of the previous board and creates the one of the current board.
+// The genesis resource is consumed when starting the game.
+pub fn create_sudoku_move_ptx<R: RngCore>(
+    mut rng: R,
+    puzzle: &SudokuState,
+    input_resource: Resource,
+    previous_state: &SudokuState,
+    current_state: &SudokuState,
+    player_sk: &SpendingKey,
+) -> (ShieldedPartialTransaction, Resource) {
+    // output resource
+    let mut output_resource =
+        create_sudoku_resource(&mut rng, puzzle, current_state, player_sk.get_npk());
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            input_resource,
+            merkle_path,
+            None,
+            &mut output_resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    let (input_resource_logics, output_resource_logics) = create_sudoku_move_resource_logics(
+        &resource_merkle_tree,
+        puzzle,
+        input_resource,
+        output_resource,
+        previous_state,
+        current_state,
+    );
+
+    // Create shielded partial tx
+    let ptx = ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![output_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap();
+
+    // The player can spend the created resource with the nullifier key
+    output_resource.nk_container = player_sk.get_nk_container();
+
+    (ptx, output_resource)
+}
+
+// The dealer spends the reward and creates the intent resource
+pub fn create_dealer_intent_ptx<R: RngCore>(
+    mut rng: R,
+    puzzle: &SudokuState,
+    reward: &Token,
+    dealer_sk: &SpendingKey,
+) -> ShieldedPartialTransaction {
+    let dealer_auth =
+        TokenAuthorization::from_address(&dealer_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+
+    // input resource
+    let input_resource = reward.create_random_input_token_resource_from_key(&mut rng, dealer_sk);
+
+    // output intent resource
+    let mut intent_resource = create_intent_resource(&mut rng, puzzle, dealer_sk.get_nk());
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut intent_resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = intent_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the reward
+    let input_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_token_resource_logics(
+            &mut rng,
+            dealer_auth,
+            dealer_sk.get_auth_sk(),
+            merkle_path,
+        )
+    };
+
+    // Create resource logics for the intent resource
+    let output_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        let intent_witness = ResourceExistenceWitness::new(intent_resource, merkle_path);
+        let circuit = DealerIntentResourceLogicCircuit {
+            self_resource: intent_witness,
+            // the puzzle resource won't be checked.
+            puzzle_resource: intent_witness,
+            puzzle: puzzle.clone(),
+            solution: SudokuState::default(),
+        };
+        ResourceLogics::new(Box::new(circuit), vec![])
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![output_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+// The player solves the puzzle and claims the reward by consuming the intent resource
+#[allow(clippy::too_many_arguments)]
+pub fn create_solve_and_claim_ptx<R: RngCore>(
+    mut rng: R,
+    puzzle: &SudokuState,
+    sudoku_resource: Resource,
+    previous_state: &SudokuState,
+    solution: &SudokuState,
+    player_sk: &SpendingKey,
+    dealer_nk: pallas::Base,
+    reward: &Token,
+) -> ShieldedPartialTransaction {
+    let player_address = player_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let player_auth = TokenAuthorization::from_address(&player_address);
+
+    // input resources
+    let intent_resource = create_intent_resource(&mut rng, puzzle, dealer_nk);
+
+    // output resources
+    let mut solved_resource =
+        create_sudoku_resource(&mut rng, puzzle, solution, player_sk.get_npk());
+    let mut reward_resource =
+        reward.create_random_output_token_resource_to_address(&mut rng, &player_address);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            sudoku_resource,
+            merkle_path.clone(),
+            None,
+            &mut solved_resource,
+            &mut rng,
+        );
+
+        let compliance_2 = ComplianceInfo::new(
+            intent_resource,
+            merkle_path,
+            None,
+            &mut reward_resource.resource,
+            &mut rng,
+        );
+        vec![compliance_1, compliance_2]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = sudoku_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = solved_resource.commitment().inner();
+    let input_resource_nf_2 = intent_resource.get_nf().unwrap().inner();
+    let output_resource_cm_2 = reward_resource.commitment().inner();
+    let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![
+        input_resource_nf_1,
+        output_resource_cm_1,
+        input_resource_nf_2,
+        output_resource_cm_2,
+    ]);
+
+    // Create resource logics for the sudoku resources
+    let (sudoku_input_resource_logics, sudoku_output_resource_logics) =
+        create_sudoku_move_resource_logics(
+            &resource_merkle_tree,
+            puzzle,
+            sudoku_resource,
+            solved_resource,
+            previous_state,
+            solution,
+        );
+
+    // Create resource logics for the intent resource
+    let intent_resource_logics = {
+        let intent_merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_2)
+            .unwrap();
+        let solved_merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        let circuit = DealerIntentResourceLogicCircuit {
+            self_resource: ResourceExistenceWitness::new(intent_resource, intent_merkle_path),
+            puzzle_resource: ResourceExistenceWitness::new(solved_resource, solved_merkle_path),
+            puzzle: puzzle.clone(),
+            solution: solution.clone(),
+        };
+        ResourceLogics::new(Box::new(circuit), vec![])
+    };
+
+    // Create resource logics for the reward
+    let reward_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_2)
+            .unwrap();
+        reward_resource.generate_output_token_resource_logics(&mut rng, player_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![sudoku_input_resource_logics, intent_resource_logics],
+        vec![sudoku_output_resource_logics, reward_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+// Returns the tx starting the game and the tx solving the puzzle and claiming the reward
+pub fn create_sudoku_transactions<R: RngCore + CryptoRng>(
+    mut rng: R,
+) -> (Transaction, Transaction) {
+    let dealer_sk = SpendingKey::random(&mut rng);
+    let player_sk = SpendingKey::random(&mut rng);
+    let reward = Token::new("dai".to_string(), 10u64);
+
+    // Alice starts the game and fills some cells
+    let genesis_resource = create_sudoku_genesis_resource(&mut rng, &puzzle(), player_sk.get_nk());
+    let (start_ptx, sudoku_resource) = create_sudoku_move_ptx(
+        &mut rng,
+        &puzzle(),
+        genesis_resource,
+        &puzzle(),
+        &puzzle(),
+        &player_sk,
+    );
+    let (move_ptx, sudoku_resource) = create_sudoku_move_ptx(
+        &mut rng,
+        &puzzle(),
+        sudoku_resource,
+        &puzzle(),
+        &partial_solution(),
+        &player_sk,
+    );
+    let start_tx = Transaction::build(
+        &mut rng,
+        ShieldedPartialTxBundle::new(vec![start_ptx, move_ptx]),
+        TransparentPartialTxBundle::default(),
+    )
+    .unwrap();
+
+    // The dealer publishes the intent and Alice claims the reward with the solution
+    let dealer_ptx = create_dealer_intent_ptx(&mut rng, &puzzle(), &reward, &dealer_sk);
+    let solve_ptx = create_solve_and_claim_ptx(
+        &mut rng,
+        &puzzle(),
+        sudoku_resource,
+        &partial_solution(),
+        &solution(),
+        &player_sk,
+        dealer_sk.get_nk(),
+        &reward,
+    );
+    let solve_tx = Transaction::build(
+        &mut rng,
+        ShieldedPartialTxBundle::new(vec![dealer_ptx, solve_ptx]),
+        TransparentPartialTxBundle::default(),
+    )
+    .unwrap();
+
+    (start_tx, solve_tx)
+}
+
+#[test]
+fn test_sudoku_tx() {
+    use rand::rngs::OsRng;
+
+    let (start_tx, solve_tx) = create_sudoku_transactions(OsRng);
+    start_tx.execute().unwrap();
+    solve_tx.execute().unwrap();
+}
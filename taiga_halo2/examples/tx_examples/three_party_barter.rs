@@ -0,0 +1,239 @@
+/// Three-party barter with intent resources
+/// Alice has 1 "STAR" and wants 1 "DOLPHIN".
+/// Bob has 1 "DOLPHIN" and wants 1 "TREE".
+/// Charlie has 1 "TREE" and wants 1 "STAR".
+/// None of the pairs can trade, so each user publishes an intent ptx. The solver
+/// consumes the three intent resources in one ptx and closes the cycle.
+///
+use crate::token_swap_with_intent::create_token_intent_ptx;
+use halo2_proofs::arithmetic::Field;
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        or_relation_intent::{
+            create_intent_resource, OrRelationAlternatives, OrRelationIntentResourceLogicCircuit,
+        },
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{Token, TokenAuthorization},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::{Anchor, MerklePath},
+    resource::ResourceLogics,
+    resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+};
+
+// The published intent of a barter party
+pub struct BarterIntent {
+    pub wanted: Token,
+    pub intent_nk: pallas::Base,
+    pub receiver_npk: pallas::Base,
+    pub receiver_value: pallas::Base,
+    pub receiver_address: PaymentAddress,
+}
+
+pub fn create_barter_intent_ptx<R: RngCore>(
+    mut rng: R,
+    offered: Token,
+    wanted: Token,
+    sk: &SpendingKey,
+) -> (ShieldedPartialTransaction, BarterIntent) {
+    let alternatives = OrRelationAlternatives::new(vec![wanted.clone()]);
+    let (ptx, intent_nk, receiver_npk, receiver_value) =
+        create_token_intent_ptx(&mut rng, alternatives, offered, sk);
+    let intent = BarterIntent {
+        wanted,
+        intent_nk,
+        receiver_npk,
+        receiver_value,
+        receiver_address: sk.get_address(*COMPRESSED_TOKEN_AUTH_VK),
+    };
+    (ptx, intent)
+}
+
+// The solver consumes all the intent resources and sends the wanted tokens to the parties
+pub fn create_barter_solver_ptx<R: RngCore>(
+    mut rng: R,
+    intents: &[BarterIntent],
+) -> ShieldedPartialTransaction {
+    // input intent resources
+    let intent_resources: Vec<_> = intents
+        .iter()
+        .map(|intent| {
+            create_intent_resource(
+                &mut rng,
+                &OrRelationAlternatives::new(vec![intent.wanted.clone()]),
+                intent.receiver_npk,
+                intent.receiver_value,
+                intent.intent_nk,
+            )
+        })
+        .collect();
+
+    // output resources
+    let mut output_resources: Vec<_> = intents
+        .iter()
+        .map(|intent| {
+            intent
+                .wanted
+                .create_random_output_token_resource_to_address(&mut rng, &intent.receiver_address)
+        })
+        .collect();
+
+    // Fetch a valid anchor for dummy resources
+    let anchor = Anchor::from(pallas::Base::random(&mut rng));
+
+    // Create compliance pairs
+    let compliances: Vec<_> = intent_resources
+        .iter()
+        .zip(output_resources.iter_mut())
+        .map(|(intent_resource, output_resource)| {
+            let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+            ComplianceInfo::new(
+                *intent_resource,
+                merkle_path,
+                Some(anchor),
+                &mut output_resource.resource,
+                &mut rng,
+            )
+        })
+        .collect();
+
+    // Collect resource merkle leaves
+    let mut resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![]);
+    for (intent_resource, output_resource) in intent_resources.iter().zip(output_resources.iter()) {
+        resource_merkle_tree.insert(intent_resource.get_nf().unwrap().inner());
+        resource_merkle_tree.insert(output_resource.commitment().inner());
+    }
+
+    // Create resource logics for the intent(input) resources
+    let intent_resource_logics = intent_resources
+        .iter()
+        .zip(output_resources.iter())
+        .zip(intents.iter())
+        .map(|((intent_resource, output_resource), intent)| {
+            let intent_merkle_path = resource_merkle_tree
+                .generate_path(intent_resource.get_nf().unwrap().inner())
+                .unwrap();
+            let output_merkle_path = resource_merkle_tree
+                .generate_path(output_resource.commitment().inner())
+                .unwrap();
+            let intent_resource_logic = OrRelationIntentResourceLogicCircuit {
+                self_resource: ResourceExistenceWitness::new(*intent_resource, intent_merkle_path),
+                desired_resource: ResourceExistenceWitness::new(
+                    output_resource.resource,
+                    output_merkle_path,
+                ),
+                alternatives: OrRelationAlternatives::new(vec![intent.wanted.clone()]),
+                receiver_npk: intent.receiver_npk,
+                receiver_value: intent.receiver_value,
+            };
+            ResourceLogics::new(Box::new(intent_resource_logic), vec![])
+        })
+        .collect();
+
+    // Create resource logics for the output resources
+    let output_resource_logics = output_resources
+        .iter()
+        .zip(intents.iter())
+        .map(|(output_resource, intent)| {
+            let merkle_path = resource_merkle_tree
+                .generate_path(output_resource.commitment().inner())
+                .unwrap();
+            let output_auth = TokenAuthorization::from_address(&intent.receiver_address);
+            output_resource.generate_output_token_resource_logics(
+                &mut rng,
+                output_auth,
+                merkle_path,
+            )
+        })
+        .collect();
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        intent_resource_logics,
+        output_resource_logics,
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+// Returns the intent ptxs of Alice, Bob and Charlie, and the solver ptx closing the cycle
+pub fn create_three_party_barter_ptxs<R: RngCore + CryptoRng>(
+    mut rng: R,
+) -> (Vec<ShieldedPartialTransaction>, ShieldedPartialTransaction) {
+    let star = Token::new("star".to_string(), 1u64);
+    let dolphin = Token::new("dolphin".to_string(), 1u64);
+    let tree = Token::new("tree".to_string(), 1u64);
+
+    let alice_sk = SpendingKey::random(&mut rng);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let charlie_sk = SpendingKey::random(&mut rng);
+
+    let (alice_ptx, alice_intent) =
+        create_barter_intent_ptx(&mut rng, star.clone(), dolphin.clone(), &alice_sk);
+    let (bob_ptx, bob_intent) = create_barter_intent_ptx(&mut rng, dolphin, tree.clone(), &bob_sk);
+    let (charlie_ptx, charlie_intent) = create_barter_intent_ptx(&mut rng, tree, star, &charlie_sk);
+
+    let solver_ptx =
+        create_barter_solver_ptx(&mut rng, &[alice_intent, bob_intent, charlie_intent]);
+
+    (vec![alice_ptx, bob_ptx, charlie_ptx], solver_ptx)
+}
+
+pub fn create_three_party_barter_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
+    let (mut ptxs, solver_ptx) = create_three_party_barter_ptxs(&mut rng);
+    ptxs.push(solver_ptx);
+
+    // Solver creates the final transaction
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(ptxs);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build(&mut rng, shielded_tx_bundle, transparent_ptx_bundle).unwrap()
+}
+
+#[test]
+fn test_three_party_barter_tx() {
+    use rand::rngs::OsRng;
+    use taiga_halo2::error::TransactionError;
+
+    let mut rng = OsRng;
+    let (user_ptxs, solver_ptx) = create_three_party_barter_ptxs(&mut rng);
+
+    // Any two of the intent ptxs can't balance the solver ptx
+    for skipped in 0..user_ptxs.len() {
+        let mut ptxs: Vec<_> = user_ptxs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != skipped)
+            .map(|(_, ptx)| ptx.clone())
+            .collect();
+        ptxs.push(solver_ptx.clone());
+        let tx = Transaction::build(
+            &mut rng,
+            ShieldedPartialTxBundle::new(ptxs),
+            TransparentPartialTxBundle::default(),
+        )
+        .unwrap();
+        assert!(matches!(
+            tx.execute(),
+            Err(TransactionError::InvalidBindingSignature)
+        ));
+    }
+
+    // All three intent ptxs together with the solver ptx are balanced
+    let mut ptxs = user_ptxs;
+    ptxs.push(solver_ptx);
+    let tx = Transaction::build(
+        &mut rng,
+        ShieldedPartialTxBundle::new(ptxs),
+        TransparentPartialTxBundle::default(),
+    )
+    .unwrap();
+    tx.execute().unwrap();
+}
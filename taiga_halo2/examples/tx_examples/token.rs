@@ -1,38 +1,36 @@
-use pasta_curves::pallas;
 use rand::RngCore;
 
 use taiga_halo2::{
     circuit::resource_logic_examples::{
         signature_verification::COMPRESSED_TOKEN_AUTH_VK,
-        token::{Token, TokenAuthorization},
+        token::{Token, TokenAuthorization, TokenResource},
     },
     compliance::ComplianceInfo,
     constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::{PaymentAddress, SpendingKey},
     merkle_tree::MerklePath,
     resource_tree::ResourceMerkleTreeLeaves,
     shielded_ptx::ShieldedPartialTransaction,
 };
 
-#[allow(clippy::too_many_arguments)]
 pub fn create_token_swap_ptx<R: RngCore>(
     mut rng: R,
     input_token: Token,
-    input_auth_sk: pallas::Scalar,
-    input_nk: pallas::Base,
+    input_sk: &SpendingKey,
     output_token: Token,
-    output_auth_pk: pallas::Point,
-    output_npk: pallas::Base,
+    output_address: &PaymentAddress,
 ) -> ShieldedPartialTransaction {
-    let input_auth = TokenAuthorization::from_sk_vk(&input_auth_sk, &COMPRESSED_TOKEN_AUTH_VK);
+    let input_auth =
+        TokenAuthorization::from_address(&input_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
 
     // input resource
     let input_resource =
-        input_token.create_random_input_token_resource(&mut rng, input_nk, &input_auth);
+        input_token.create_random_input_token_resource_from_key(&mut rng, input_sk);
 
     // output resource
-    let output_auth = TokenAuthorization::new(output_auth_pk, *COMPRESSED_TOKEN_AUTH_VK);
+    let output_auth = TokenAuthorization::from_address(output_address);
     let mut output_resource =
-        output_token.create_random_output_token_resource(&mut rng, output_npk, &output_auth);
+        output_token.create_random_output_token_resource_to_address(&mut rng, output_address);
 
     // Generate proving info
     let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
@@ -65,7 +63,7 @@ pub fn create_token_swap_ptx<R: RngCore>(
         input_resource.generate_input_token_resource_logics(
             &mut rng,
             input_auth,
-            input_auth_sk,
+            input_sk.get_auth_sk(),
             merkle_path,
         )
     };
@@ -88,3 +86,81 @@ pub fn create_token_swap_ptx<R: RngCore>(
     )
     .unwrap()
 }
+
+// Lock the token to the auth, e.g. a multisig policy or an htlc policy. The lock key is shared by
+// the owners of the locked resource, the locked resource is returned to be spent by them.
+pub fn create_token_lock_ptx<R: RngCore>(
+    mut rng: R,
+    token: &Token,
+    input_sk: &SpendingKey,
+    lock_sk: &SpendingKey,
+    lock_auth: TokenAuthorization,
+) -> (ShieldedPartialTransaction, TokenResource) {
+    let input_auth =
+        TokenAuthorization::from_address(&input_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+
+    // input resource
+    let input_resource = token.create_random_input_token_resource_from_key(&mut rng, input_sk);
+
+    // The locked resource
+    let mut output_resource =
+        token.create_random_output_token_resource(&mut rng, lock_sk.get_npk(), &lock_auth);
+
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut output_resource.resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the input resource
+    let input_token_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_token_resource_logics(
+            &mut rng,
+            input_auth,
+            input_sk.get_auth_sk(),
+            merkle_path,
+        )
+    };
+
+    // Create resource logics for the locked resource
+    let output_token_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        output_resource.generate_output_token_resource_logics(&mut rng, lock_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    let ptx = ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_token_resource_logics],
+        vec![output_token_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap();
+
+    // The owners can spend the locked resource with the nullifier key
+    output_resource.resource.nk_container = lock_sk.get_nk_container();
+
+    (ptx, output_resource)
+}
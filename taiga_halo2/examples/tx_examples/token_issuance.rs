@@ -0,0 +1,136 @@
+/// Token issuance, transfer and burn
+/// The issuer issues 10 "XAN" to Alice by consuming the issuance resource
+/// Alice transfers 10 "XAN" to Bob
+/// Bob burns 10 "XAN"
+///
+use pasta_curves::pallas;
+use rand::{CryptoRng, RngCore};
+use taiga_halo2::{
+    circuit::resource_logic_examples::{
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{IssuedToken, IssuedTokenResource, TokenAuthorization},
+    },
+    compliance::ComplianceInfo,
+    constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::SpendingKey,
+    merkle_tree::MerklePath,
+    resource_tree::ResourceMerkleTreeLeaves,
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+};
+
+pub fn create_issued_token_ptx<R: RngCore>(
+    mut rng: R,
+    input_resource: IssuedTokenResource,
+    input_auth: TokenAuthorization,
+    input_auth_sk: pallas::Scalar,
+    mut output_resource: IssuedTokenResource,
+    output_auth: TokenAuthorization,
+) -> ShieldedPartialTransaction {
+    // Generate proving info
+    let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+
+    // Create compliance pairs
+    let compliances = {
+        let compliance_1 = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut output_resource.resource,
+            &mut rng,
+        );
+
+        vec![compliance_1]
+    };
+
+    // Collect resource merkle leaves
+    let input_resource_nf_1 = input_resource.get_nf().unwrap().inner();
+    let output_resource_cm_1 = output_resource.commitment().inner();
+    let resource_merkle_tree =
+        ResourceMerkleTreeLeaves::new(vec![input_resource_nf_1, output_resource_cm_1]);
+
+    // Create resource logics for the input resource
+    let input_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(input_resource_nf_1)
+            .unwrap();
+        input_resource.generate_input_resource_logics(
+            &mut rng,
+            input_auth,
+            input_auth_sk,
+            merkle_path,
+        )
+    };
+
+    // Create resource logics for the output resource
+    let output_resource_logics = {
+        let merkle_path = resource_merkle_tree
+            .generate_path(output_resource_cm_1)
+            .unwrap();
+        output_resource.generate_output_resource_logics(&mut rng, output_auth, merkle_path)
+    };
+
+    // Create shielded partial tx
+    ShieldedPartialTransaction::build(
+        compliances,
+        vec![input_resource_logics],
+        vec![output_resource_logics],
+        vec![],
+        &mut rng,
+    )
+    .unwrap()
+}
+
+pub fn create_token_issuance_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
+    let issuer_sk = SpendingKey::random(&mut rng);
+    let xan_token = IssuedToken::from_issuer_key("xan".to_string(), &issuer_sk, 10);
+
+    let alice_sk = SpendingKey::random(&mut rng);
+    let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+    // The issuer mints the whole supply to Alice
+    let mint_ptx = create_issued_token_ptx(
+        &mut rng,
+        xan_token.create_issuance_resource(),
+        xan_token.issuer_auth(),
+        issuer_sk.get_auth_sk(),
+        xan_token.create_random_output_resource_to_address(&mut rng, 10, &alice_address),
+        TokenAuthorization::from_address(&alice_address),
+    );
+
+    // Alice transfers the tokens to Bob
+    let transfer_ptx = create_issued_token_ptx(
+        &mut rng,
+        xan_token.create_random_input_resource_from_key(&mut rng, 10, &alice_sk),
+        TokenAuthorization::from_address(&alice_address),
+        alice_sk.get_auth_sk(),
+        xan_token.create_random_output_resource_to_address(&mut rng, 10, &bob_address),
+        TokenAuthorization::from_address(&bob_address),
+    );
+
+    // Bob burns the tokens
+    let burn_ptx = create_issued_token_ptx(
+        &mut rng,
+        xan_token.create_random_input_resource_from_key(&mut rng, 10, &bob_sk),
+        TokenAuthorization::from_address(&bob_address),
+        bob_sk.get_auth_sk(),
+        xan_token.create_random_burn_resource(&mut rng, 10, &bob_address),
+        TokenAuthorization::from_address(&bob_address),
+    );
+
+    // The input and output quantities of every kind are balanced, so the binding signature is valid
+    let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![mint_ptx, transfer_ptx, burn_ptx]);
+    let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+    Transaction::build(&mut rng, shielded_tx_bundle, transparent_ptx_bundle).unwrap()
+}
+
+#[test]
+fn test_token_issuance_tx() {
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    let tx = create_token_issuance_transaction(&mut rng);
+    tx.execute().unwrap();
+}
@@ -1,23 +1,24 @@
 /// Token swap example with intent resource
-/// Alice has 5 "BTC" and wants 1 "DOLPHIN" or 2 "Monkeys". Then Alice creates an intent for it.
+/// Alice has 5 "BTC" and wants 1 "DOLPHIN", 2 "Monkeys" or 3 "Pandas". Then Alice creates an intent for it.
 /// Bob has 1 "DOLPHIN" and wants 5 "BTC".
 /// The Solver/Bob matches Alice's intent and creates the final tx.
 ///
 use crate::token::create_token_swap_ptx;
-use group::Group;
 use halo2_proofs::arithmetic::Field;
-use pasta_curves::{group::Curve, pallas};
+use pasta_curves::pallas;
 use rand::{CryptoRng, RngCore};
 use taiga_halo2::{
     circuit::resource_logic_examples::{
-        or_relation_intent::{create_intent_resource, OrRelationIntentResourceLogicCircuit},
+        or_relation_intent::{
+            create_intent_resource, OrRelationAlternatives, OrRelationIntentResourceLogicCircuit,
+        },
         signature_verification::COMPRESSED_TOKEN_AUTH_VK,
         token::{Token, TokenAuthorization},
     },
     compliance::ComplianceInfo,
     constant::TAIGA_COMMITMENT_TREE_DEPTH,
+    keys::{PaymentAddress, SpendingKey},
     merkle_tree::{Anchor, MerklePath},
-    nullifier::NullifierKeyContainer,
     resource::ResourceLogics,
     resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves},
     shielded_ptx::ShieldedPartialTransaction,
@@ -26,29 +27,28 @@ use taiga_halo2::{
 
 pub fn create_token_intent_ptx<R: RngCore>(
     mut rng: R,
-    token_1: Token,
-    token_2: Token,
+    alternatives: OrRelationAlternatives,
     input_token: Token,
-    input_auth_sk: pallas::Scalar,
-    input_nk: pallas::Base,
+    input_sk: &SpendingKey,
 ) -> (
     ShieldedPartialTransaction,
     pallas::Base,
     pallas::Base,
     pallas::Base,
 ) {
-    let input_auth = TokenAuthorization::from_sk_vk(&input_auth_sk, &COMPRESSED_TOKEN_AUTH_VK);
+    let input_auth =
+        TokenAuthorization::from_address(&input_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+    let input_nk = input_sk.get_nk();
 
     // input resource
     let input_resource =
-        input_token.create_random_input_token_resource(&mut rng, input_nk, &input_auth);
+        input_token.create_random_input_token_resource_from_key(&mut rng, input_sk);
 
     // output intent resource
     let input_resource_npk = input_resource.get_npk();
     let mut intent_resource = create_intent_resource(
         &mut rng,
-        &token_1,
-        &token_2,
+        &alternatives,
         input_resource_npk,
         input_resource.value,
         input_nk,
@@ -83,7 +83,7 @@ pub fn create_token_intent_ptx<R: RngCore>(
         input_resource.generate_input_token_resource_logics(
             &mut rng,
             input_auth,
-            input_auth_sk,
+            input_sk.get_auth_sk(),
             merkle_path,
         )
     };
@@ -98,8 +98,7 @@ pub fn create_token_intent_ptx<R: RngCore>(
             self_resource: intent_resource_witness,
             // the desired resource won't be checked.
             desired_resource: intent_resource_witness,
-            token_1,
-            token_2,
+            alternatives,
             receiver_npk: input_resource_npk,
             receiver_value: input_resource.value,
         };
@@ -119,32 +118,28 @@ pub fn create_token_intent_ptx<R: RngCore>(
     (ptx, input_nk, input_resource_npk, input_resource.value)
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn consume_token_intent_ptx<R: RngCore>(
     mut rng: R,
-    token_1: Token,
-    token_2: Token,
+    alternatives: OrRelationAlternatives,
     input_nk: pallas::Base,
     receiver_npk: pallas::Base,
     receiver_value: pallas::Base,
     output_token: Token,
-    output_auth_pk: pallas::Point,
+    output_address: &PaymentAddress,
 ) -> ShieldedPartialTransaction {
     // input intent resource
     let intent_resource = create_intent_resource(
         &mut rng,
-        &token_1,
-        &token_2,
+        &alternatives,
         receiver_npk,
         receiver_value,
         input_nk,
     );
 
     // output resource
-    let output_auth = TokenAuthorization::new(output_auth_pk, *COMPRESSED_TOKEN_AUTH_VK);
-    let output_npk = NullifierKeyContainer::from_key(input_nk).get_npk();
+    let output_auth = TokenAuthorization::from_address(output_address);
     let mut output_resource =
-        output_token.create_random_output_token_resource(&mut rng, output_npk, &output_auth);
+        output_token.create_random_output_token_resource_to_address(&mut rng, output_address);
 
     let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
 
@@ -180,8 +175,7 @@ pub fn consume_token_intent_ptx<R: RngCore>(
                 output_resource.resource,
                 output_merkle_path,
             ),
-            token_1,
-            token_2,
+            alternatives,
             receiver_npk,
             receiver_value,
         };
@@ -207,50 +201,36 @@ pub fn consume_token_intent_ptx<R: RngCore>(
 }
 
 pub fn create_token_swap_intent_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
-    let generator = pallas::Point::generator().to_affine();
-
     // Alice creates the partial transaction with 5 BTC input and intent output
-    let alice_auth_sk = pallas::Scalar::random(&mut rng);
-    let alice_auth_pk = generator * alice_auth_sk;
-    let alice_nk = pallas::Base::random(&mut rng);
+    let alice_sk = SpendingKey::random(&mut rng);
+    let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
     let token_1 = Token::new("dolphin".to_string(), 1u64);
-    let token_2 = Token::new("monkey".to_string(), 2u64);
-    let btc_token = Token::new("btc".to_string(), 5u64);
-    let (alice_ptx, intent_nk, receiver_npk, receiver_value) = create_token_intent_ptx(
-        &mut rng,
+    let alternatives = OrRelationAlternatives::new(vec![
         token_1.clone(),
-        token_2.clone(),
-        btc_token.clone(),
-        alice_auth_sk,
-        alice_nk,
-    );
+        Token::new("monkey".to_string(), 2u64),
+        Token::new("panda".to_string(), 3u64),
+    ]);
+    let btc_token = Token::new("btc".to_string(), 5u64);
+    let (alice_ptx, intent_nk, receiver_npk, receiver_value) =
+        create_token_intent_ptx(&mut rng, alternatives.clone(), btc_token.clone(), &alice_sk);
 
     // Bob creates the partial transaction with 1 DOLPHIN input and 5 BTC output
-    let bob_auth_sk = pallas::Scalar::random(&mut rng);
-    let bob_auth_pk = generator * bob_auth_sk;
-    let bob_nk = NullifierKeyContainer::random_key(&mut rng);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
 
-    let bob_ptx = create_token_swap_ptx(
-        &mut rng,
-        token_1.clone(),
-        bob_auth_sk,
-        bob_nk.get_nk().unwrap(),
-        btc_token,
-        bob_auth_pk,
-        bob_nk.get_npk(),
-    );
+    let bob_ptx =
+        create_token_swap_ptx(&mut rng, token_1.clone(), &bob_sk, btc_token, &bob_address);
 
     // Solver/Bob creates the partial transaction to consume the intent resource
     // The bob_ptx and solver_ptx can be merged to one ptx.
     let solver_ptx = consume_token_intent_ptx(
         &mut rng,
-        token_1.clone(),
-        token_2,
+        alternatives,
         intent_nk,
         receiver_npk,
         receiver_value,
         token_1,
-        alice_auth_pk,
+        &alice_address,
     );
 
     // Solver creates the final transaction
@@ -4,67 +4,50 @@
 /// Carol has 15 "XAN" and wants 5 BTC""
 ///
 use crate::token::create_token_swap_ptx;
-use group::Group;
-use halo2_proofs::arithmetic::Field;
-use pasta_curves::{group::Curve, pallas};
 use rand::{CryptoRng, RngCore};
 use taiga_halo2::{
-    circuit::resource_logic_examples::token::Token,
-    nullifier::NullifierKeyContainer,
+    circuit::resource_logic_examples::{
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK, token::Token,
+    },
+    keys::SpendingKey,
     transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
 };
 
 pub fn create_token_swap_transaction<R: RngCore + CryptoRng>(mut rng: R) -> Transaction {
-    let generator = pallas::Point::generator().to_affine();
-
     let btc_token = Token::new("btc".to_string(), 5);
     let eth_token = Token::new("eth".to_string(), 10);
     let xan_token = Token::new("xan".to_string(), 15);
 
     // Alice creates the partial transaction
-    let alice_auth_sk = pallas::Scalar::random(&mut rng);
-    let alice_auth_pk = generator * alice_auth_sk;
-    let alice_nk = NullifierKeyContainer::random_key(&mut rng);
+    let alice_sk = SpendingKey::random(&mut rng);
+    let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
 
     let alice_ptx = create_token_swap_ptx(
         &mut rng,
         btc_token.clone(),
-        alice_auth_sk,
-        alice_nk.get_nk().unwrap(),
+        &alice_sk,
         eth_token.clone(),
-        alice_auth_pk,
-        alice_nk.get_npk(),
+        &alice_address,
     );
 
     // Bob creates the partial transaction
-    let bob_auth_sk = pallas::Scalar::random(&mut rng);
-    let bob_auth_pk = generator * bob_auth_sk;
-    let bob_nk = NullifierKeyContainer::random_key(&mut rng);
+    let bob_sk = SpendingKey::random(&mut rng);
+    let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
 
     let bob_ptx = create_token_swap_ptx(
         &mut rng,
         eth_token,
-        bob_auth_sk,
-        bob_nk.get_nk().unwrap(),
+        &bob_sk,
         xan_token.clone(),
-        bob_auth_pk,
-        bob_nk.get_npk(),
+        &bob_address,
     );
 
     // Carol creates the partial transaction
-    let carol_auth_sk = pallas::Scalar::random(&mut rng);
-    let carol_auth_pk = generator * carol_auth_sk;
-    let carol_nk = NullifierKeyContainer::random_key(&mut rng);
+    let carol_sk = SpendingKey::random(&mut rng);
+    let carol_address = carol_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
 
-    let carol_ptx = create_token_swap_ptx(
-        &mut rng,
-        xan_token,
-        carol_auth_sk,
-        carol_nk.get_nk().unwrap(),
-        btc_token,
-        carol_auth_pk,
-        carol_nk.get_npk(),
-    );
+    let carol_ptx =
+        create_token_swap_ptx(&mut rng, xan_token, &carol_sk, btc_token, &carol_address);
 
     // Solver creates the final transaction
     let shielded_tx_bundle = ShieldedPartialTxBundle::new(vec![alice_ptx, bob_ptx, carol_ptx]);
@@ -1,6 +1,6 @@
 use crate::circuit::blake2s::{resource_logic_commitment_gadget, Blake2sChip, Blake2sConfig};
 use crate::circuit::gadgets::assign_free_advice;
-use crate::circuit::hash_to_curve::HashToCurveConfig;
+use crate::circuit::hash_to_curve::{HashToCurveChip, HashToCurveConfig};
 use crate::circuit::integrity::{
     check_input_resource, check_output_resource, compute_delta_commitment,
 };
@@ -236,8 +236,8 @@ impl Circuit<pallas::Base> for ComplianceCircuit {
         // compute and public delta commitment(input_value_commitment - output_value_commitment)
         let delta = compute_delta_commitment(
             layouter.namespace(|| "delta commitment"),
-            ecc_chip,
-            config.hash_to_curve_config.clone(),
+            ecc_chip.clone(),
+            HashToCurveChip::construct(config.hash_to_curve_config.clone(), ecc_chip),
             input_resource_variables.resource_variables.logic.clone(),
             input_resource_variables.resource_variables.label.clone(),
             input_resource_variables.resource_variables.quantity.clone(),
@@ -0,0 +1,255 @@
+/// The cost report of a circuit, e.g. a resource logic or the compliance circuit, shows how close
+/// it is to the row budget of its params size and which namespaces dominate. The circuit is
+/// synthesized without evaluating the witnesses, the same way as keygen.
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::Value,
+    dev::CircuitCost,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+use pasta_curves::{pallas, vesta};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionCost {
+    // The namespaces and the region name, joined by "/"
+    pub name: String,
+    pub rows: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitCostReport {
+    pub k: u32,
+    // The rows available for the assignments, excluding the blinding rows
+    pub usable_rows: usize,
+    // The rows used by the regions, the constants and the lookup tables
+    pub rows: usize,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub gates: usize,
+    // The polynomial constraints in the gates
+    pub constraints: usize,
+    pub lookups: usize,
+    pub degree: usize,
+    // The size in bytes of a single proof
+    pub proof_size: usize,
+    pub regions: Vec<RegionCost>,
+}
+
+impl CircuitCostReport {
+    pub fn measure<C: Circuit<pallas::Base>>(k: u32, circuit: &C) -> Result<Self, Error> {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let mut layout = CostLayout::default();
+        C::FloorPlanner::synthesize(&mut layout, circuit, config, cs.constants().clone())?;
+
+        let proof_size = CircuitCost::<vesta::Point, C>::measure(k as usize, circuit)
+            .proof_size(1)
+            .into();
+
+        Ok(Self {
+            k,
+            usable_rows: (1 << k) - (cs.blinding_factors() + 1),
+            rows: layout.rows,
+            advice_columns: cs.num_advice_columns(),
+            fixed_columns: cs.num_fixed_columns(),
+            instance_columns: cs.num_instance_columns(),
+            selectors: cs.num_selectors(),
+            gates: cs.gates().len(),
+            constraints: cs.gates().iter().map(|gate| gate.polynomials().len()).sum(),
+            lookups: cs.lookups().len(),
+            degree: cs.degree(),
+            proof_size,
+            regions: layout.regions,
+        })
+    }
+
+    /// Returns true if the circuit fits in the params size k.
+    pub fn fits(&self) -> bool {
+        self.rows <= self.usable_rows
+    }
+
+    /// Sums up the region rows by the namespaces up to depth, e.g. depth 2 separates the
+    /// gadgets in the custom constraints of a resource logic. The regions can be laid out side
+    /// by side in different columns, so the sum may exceed the rows used.
+    pub fn namespace_rows(&self, depth: usize) -> Vec<(String, usize)> {
+        let mut namespaces = BTreeMap::new();
+        for region in self.regions.iter() {
+            let name = region
+                .name
+                .split('/')
+                .take(depth)
+                .collect::<Vec<_>>()
+                .join("/");
+            *namespaces.entry(name).or_insert(0) += region.rows;
+        }
+        let mut namespaces: Vec<(String, usize)> = namespaces.into_iter().collect();
+        namespaces.sort_by(|a, b| b.1.cmp(&a.1));
+        namespaces
+    }
+}
+
+impl fmt::Display for CircuitCostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "rows: {} / {} (k = {})",
+            self.rows, self.usable_rows, self.k
+        )?;
+        writeln!(
+            f,
+            "columns: {} advice, {} fixed, {} instance, {} selectors",
+            self.advice_columns, self.fixed_columns, self.instance_columns, self.selectors
+        )?;
+        writeln!(
+            f,
+            "gates: {} with {} constraints, lookups: {}, degree: {}",
+            self.gates, self.constraints, self.lookups, self.degree
+        )?;
+        writeln!(f, "proof size: {} bytes", self.proof_size)?;
+        for (name, rows) in self.namespace_rows(2) {
+            writeln!(f, "  {}: {} rows", name, rows)?;
+        }
+        Ok(())
+    }
+}
+
+// Records the rows touched by each region and the namespaces it's in
+#[derive(Default)]
+struct CostLayout {
+    namespaces: Vec<String>,
+    current_region: Option<(String, usize, usize)>,
+    regions: Vec<RegionCost>,
+    rows: usize,
+}
+
+impl CostLayout {
+    fn touch(&mut self, row: usize) {
+        self.rows = self.rows.max(row + 1);
+        if let Some((_, start, end)) = self.current_region.as_mut() {
+            *start = (*start).min(row);
+            *end = (*end).max(row + 1);
+        }
+    }
+}
+
+impl<F: Field> Assignment<F> for CostLayout {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let mut name = self.namespaces.clone();
+        name.push(name_fn().into());
+        self.current_region = Some((name.join("/"), usize::MAX, 0));
+    }
+
+    fn exit_region(&mut self) {
+        if let Some((name, start, end)) = self.current_region.take() {
+            self.regions.push(RegionCost {
+                name,
+                rows: end.saturating_sub(start),
+            });
+        }
+    }
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespaces.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {
+        self.namespaces.pop();
+    }
+}
+
+#[test]
+fn test_compliance_circuit_cost() {
+    use crate::circuit::compliance_circuit::ComplianceCircuit;
+    use crate::constant::COMPLIANCE_CIRCUIT_PARAMS_SIZE;
+
+    let report = CircuitCostReport::measure(
+        COMPLIANCE_CIRCUIT_PARAMS_SIZE,
+        &ComplianceCircuit::default(),
+    )
+    .unwrap();
+    assert!(
+        report.fits(),
+        "the compliance circuit doesn't fit:\n{}",
+        report
+    );
+    assert_eq!(report.instance_columns, 1);
+    assert!(report
+        .regions
+        .iter()
+        .any(|region| region.name.starts_with("poseidon merkle")));
+}
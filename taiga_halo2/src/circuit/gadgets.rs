@@ -1,17 +1,24 @@
+use halo2_gadgets::utilities::lookup_range_check::LookupRangeCheckConfig;
 use halo2_proofs::{
     arithmetic,
     circuit::{AssignedCell, Layouter, Value},
     plonk::{Advice, Assigned, Column, Error, Instance},
 };
+use pasta_curves::pallas;
 
 pub mod add;
+pub mod boolean;
+pub mod comparison;
 pub mod conditional_equal;
 pub mod conditional_select;
+pub mod div_mod;
 pub mod extended_or_relation;
+pub mod issuance;
 pub mod mul;
 pub mod poseidon_hash;
 pub mod sub;
 pub mod triple_mul;
+pub mod validity_window;
 
 pub fn assign_free_advice<F: arithmetic::Field, V: Copy>(
     mut layouter: impl Layouter<F>,
@@ -54,3 +61,53 @@ where
         |mut region| region.assign_advice_from_constant(|| "load constant", column, 0, value),
     )
 }
+
+// Check the value is in [0, 2^64)
+pub fn range_check_u64(
+    layouter: impl Layouter<pallas::Base>,
+    lookup_config: &LookupRangeCheckConfig<pallas::Base, 10>,
+    value: AssignedCell<pallas::Base, pallas::Base>,
+) -> Result<(), Error> {
+    range_check_bits(layouter, lookup_config, value, 64)
+}
+
+// Check the value is in [0, 2^num_bits) with the 10-bit lookup table
+pub fn range_check_bits(
+    mut layouter: impl Layouter<pallas::Base>,
+    lookup_config: &LookupRangeCheckConfig<pallas::Base, 10>,
+    value: AssignedCell<pallas::Base, pallas::Base>,
+    num_bits: usize,
+) -> Result<(), Error> {
+    let num_words = num_bits / 10;
+    let short_bits = num_bits % 10;
+    if short_bits == 0 {
+        lookup_config.copy_check(
+            layouter.namespace(|| "K(10) bits range check"),
+            value,
+            num_words,
+            true,
+        )?;
+        return Ok(());
+    }
+
+    if num_words == 0 {
+        return lookup_config.copy_short_check(
+            layouter.namespace(|| "short bits range check"),
+            value,
+            short_bits,
+        );
+    }
+
+    let zs = lookup_config.copy_check(
+        layouter.namespace(|| "K(10) bits range check"),
+        value,
+        num_words,
+        false,
+    )?;
+
+    lookup_config.copy_short_check(
+        layouter.namespace(|| "short bits range check"),
+        zs[num_words].clone(),
+        short_bits,
+    )
+}
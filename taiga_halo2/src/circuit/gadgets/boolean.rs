@@ -0,0 +1,641 @@
+/// The boolean chip composes predicates over resource fields without custom gates. The
+/// comparisons return bits, the logical operations constrain their operands to be bits, and the
+/// assertions close a predicate. It requires a fixed column enabled for constants.
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas;
+
+#[derive(Clone, Debug)]
+pub struct BooleanChip {
+    config: BooleanConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct BooleanConfig {
+    // a, b, c, out
+    advice: [Column<Advice>; 4],
+    // a * (1 - a) = 0
+    s_bool: Selector,
+    // out = (a == b) with the inverse c of a - b
+    s_is_equal: Selector,
+    s_and: Selector,
+    s_or: Selector,
+    s_xor: Selector,
+    s_not: Selector,
+    // acc_next = acc + a * b, count_next = count + a
+    s_select: Selector,
+}
+
+impl Chip<pallas::Base> for BooleanChip {
+    type Config = BooleanConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl BooleanChip {
+    pub fn construct(config: BooleanConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<pallas::Base>,
+        advice: [Column<Advice>; 4],
+    ) -> BooleanConfig {
+        let config = BooleanConfig {
+            advice,
+            s_bool: meta.selector(),
+            s_is_equal: meta.selector(),
+            s_and: meta.selector(),
+            s_or: meta.selector(),
+            s_xor: meta.selector(),
+            s_not: meta.selector(),
+            s_select: meta.selector(),
+        };
+
+        let bool_check = |v: Expression<pallas::Base>| {
+            v.clone() * (Expression::Constant(pallas::Base::one()) - v)
+        };
+
+        meta.create_gate("bool", |meta| {
+            let s_bool = meta.query_selector(config.s_bool);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+
+            Constraints::with_selector(s_bool, [("a is a bit", bool_check(a))])
+        });
+
+        meta.create_gate("is equal", |meta| {
+            let s_is_equal = meta.query_selector(config.s_is_equal);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let inv = meta.query_advice(advice[2], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+            let one = Expression::Constant(pallas::Base::one());
+            let diff = a - b;
+
+            Constraints::with_selector(
+                s_is_equal,
+                [
+                    (
+                        "out = 1 - (a - b) * inv",
+                        out.clone() + diff.clone() * inv - one,
+                    ),
+                    ("(a - b) * out = 0", diff * out),
+                ],
+            )
+        });
+
+        meta.create_gate("and", |meta| {
+            let s_and = meta.query_selector(config.s_and);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(
+                s_and,
+                [
+                    ("a is a bit", bool_check(a.clone())),
+                    ("b is a bit", bool_check(b.clone())),
+                    ("out = a * b", a * b - out),
+                ],
+            )
+        });
+
+        meta.create_gate("or", |meta| {
+            let s_or = meta.query_selector(config.s_or);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(
+                s_or,
+                [
+                    ("a is a bit", bool_check(a.clone())),
+                    ("b is a bit", bool_check(b.clone())),
+                    ("out = a + b - a * b", a.clone() + b.clone() - a * b - out),
+                ],
+            )
+        });
+
+        meta.create_gate("xor", |meta| {
+            let s_xor = meta.query_selector(config.s_xor);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+            let two = Expression::Constant(pallas::Base::from(2));
+
+            Constraints::with_selector(
+                s_xor,
+                [
+                    ("a is a bit", bool_check(a.clone())),
+                    ("b is a bit", bool_check(b.clone())),
+                    (
+                        "out = a + b - 2 * a * b",
+                        a.clone() + b.clone() - two * a * b - out,
+                    ),
+                ],
+            )
+        });
+
+        meta.create_gate("not", |meta| {
+            let s_not = meta.query_selector(config.s_not);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+            let one = Expression::Constant(pallas::Base::one());
+
+            Constraints::with_selector(
+                s_not,
+                [
+                    ("a is a bit", bool_check(a.clone())),
+                    ("out = 1 - a", one - a - out),
+                ],
+            )
+        });
+
+        meta.create_gate("select", |meta| {
+            let s_select = meta.query_selector(config.s_select);
+            let flag = meta.query_advice(advice[0], Rotation::cur());
+            let value = meta.query_advice(advice[1], Rotation::cur());
+            let acc = meta.query_advice(advice[2], Rotation::cur());
+            let count = meta.query_advice(advice[3], Rotation::cur());
+            let acc_next = meta.query_advice(advice[2], Rotation::next());
+            let count_next = meta.query_advice(advice[3], Rotation::next());
+
+            Constraints::with_selector(
+                s_select,
+                [
+                    (
+                        "acc_next = acc + flag * value",
+                        acc + flag.clone() * value - acc_next,
+                    ),
+                    ("count_next = count + flag", count + flag - count_next),
+                ],
+            )
+        });
+
+        config
+    }
+
+    fn is_equal_region(
+        &self,
+        region: &mut Region<'_, pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let config = self.config();
+        config.s_is_equal.enable(region, 0)?;
+        a.copy_advice(|| "a", region, config.advice[0], 0)?;
+        b.copy_advice(|| "b", region, config.advice[1], 0)?;
+        let diff = a.value().copied() - b.value();
+        let inv = diff.map(|diff| diff.invert().unwrap_or(pallas::Base::zero()));
+        region.assign_advice(|| "inv", config.advice[2], 0, || inv)?;
+        let out = diff.map(|diff| {
+            if diff == pallas::Base::zero() {
+                pallas::Base::one()
+            } else {
+                pallas::Base::zero()
+            }
+        });
+        region.assign_advice(|| "out", config.advice[3], 0, || out)
+    }
+
+    fn is_equal_constant(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        constant: pallas::Base,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        layouter.assign_region(
+            || "is equal to constant",
+            |mut region| {
+                let b = region.assign_advice_from_constant(
+                    || "constant",
+                    self.config().advice[1],
+                    1,
+                    constant,
+                )?;
+                self.is_equal_region(&mut region, a, &b)
+            },
+        )
+    }
+
+    fn operation(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        selector: Selector,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: Option<&AssignedCell<pallas::Base, pallas::Base>>,
+        op: impl Fn(pallas::Base, pallas::Base) -> pallas::Base,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "boolean operation",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                let b_value = match b {
+                    Some(b) => b
+                        .copy_advice(|| "b", &mut region, config.advice[1], 0)?
+                        .value()
+                        .copied(),
+                    // The unused operand of not
+                    None => region
+                        .assign_advice(
+                            || "b",
+                            config.advice[1],
+                            0,
+                            || Value::known(pallas::Base::zero()),
+                        )?
+                        .value()
+                        .copied(),
+                };
+                let out = a.value().copied().zip(b_value).map(|(a, b)| op(a, b));
+                region.assign_advice(|| "out", config.advice[3], 0, || out)
+            },
+        )
+    }
+
+    fn assert_constant(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        bit: &AssignedCell<pallas::Base, pallas::Base>,
+        constant: pallas::Base,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assert constant",
+            |mut region| region.constrain_constant(bit.cell(), constant),
+        )
+    }
+}
+
+pub trait BooleanInstructions: Chip<pallas::Base> {
+    /// Constrains `a` to be 0 or 1.
+    fn assert_bit(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+
+    /// Returns 1 if `a == 0`, otherwise 0.
+    fn is_zero(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    /// Returns 1 if `a == b`, otherwise 0.
+    fn is_equal(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    fn and(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    fn or(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    fn xor(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    fn not(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    /// Returns `values[index]`, and constrains `index < values.len()`.
+    fn select(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        index: &AssignedCell<pallas::Base, pallas::Base>,
+        values: &[AssignedCell<pallas::Base, pallas::Base>],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+
+    /// Constrains the bit to be 1.
+    fn assert_true(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        bit: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+
+    /// Constrains the bit to be 0.
+    fn assert_false(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        bit: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+}
+
+impl BooleanInstructions for BooleanChip {
+    fn assert_bit(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "assert bit",
+            |mut region| {
+                config.s_bool.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                Ok(())
+            },
+        )
+    }
+
+    fn is_zero(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.is_equal_constant(layouter, a, pallas::Base::zero())
+    }
+
+    fn is_equal(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        layouter.assign_region(
+            || "is equal",
+            |mut region| self.is_equal_region(&mut region, a, b),
+        )
+    }
+
+    fn and(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.operation(layouter, self.config().s_and, a, Some(b), |a, b| a * b)
+    }
+
+    fn or(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.operation(layouter, self.config().s_or, a, Some(b), |a, b| {
+            a + b - a * b
+        })
+    }
+
+    fn xor(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.operation(layouter, self.config().s_xor, a, Some(b), |a, b| {
+            a + b - a * b.double()
+        })
+    }
+
+    fn not(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        self.operation(layouter, self.config().s_not, a, None, |a, _| {
+            pallas::Base::one() - a
+        })
+    }
+
+    fn select(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        index: &AssignedCell<pallas::Base, pallas::Base>,
+        values: &[AssignedCell<pallas::Base, pallas::Base>],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        // The flags are one-hot iff the index is in range
+        let flags = (0..values.len())
+            .map(|i| {
+                self.is_equal_constant(
+                    layouter.namespace(|| "index == i"),
+                    index,
+                    pallas::Base::from(i as u64),
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let config = self.config();
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                let mut acc = region.assign_advice_from_constant(
+                    || "initial acc",
+                    config.advice[2],
+                    0,
+                    pallas::Base::zero(),
+                )?;
+                let mut count = region.assign_advice_from_constant(
+                    || "initial count",
+                    config.advice[3],
+                    0,
+                    pallas::Base::zero(),
+                )?;
+                for (i, (flag, value)) in flags.iter().zip(values.iter()).enumerate() {
+                    config.s_select.enable(&mut region, i)?;
+                    flag.copy_advice(|| "flag", &mut region, config.advice[0], i)?;
+                    value.copy_advice(|| "value", &mut region, config.advice[1], i)?;
+                    let acc_value = acc.value().copied() + flag.value().copied() * value.value();
+                    acc = region.assign_advice(|| "acc", config.advice[2], i + 1, || acc_value)?;
+                    let count_value = count.value().copied() + flag.value();
+                    count = region.assign_advice(
+                        || "count",
+                        config.advice[3],
+                        i + 1,
+                        || count_value,
+                    )?;
+                }
+                region.constrain_constant(count.cell(), pallas::Base::one())?;
+                Ok(acc)
+            },
+        )
+    }
+
+    fn assert_true(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        bit: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        self.assert_constant(layouter, bit, pallas::Base::one())
+    }
+
+    fn assert_false(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        bit: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        self.assert_constant(layouter, bit, pallas::Base::zero())
+    }
+}
+
+#[test]
+fn test_halo2_boolean_chip() {
+    use crate::circuit::gadgets::assign_free_advice;
+    use halo2_proofs::{circuit::floor_planner, dev::MockProver, plonk::Circuit};
+
+    #[derive(Default)]
+    struct MyCircuit {
+        a: pallas::Base,
+        b: pallas::Base,
+        index: u64,
+        values: Vec<pallas::Base>,
+        expected: [bool; 6],
+    }
+
+    #[derive(Clone, Debug)]
+    struct MyConfig {
+        advices: [Column<Advice>; 4],
+        boolean_config: BooleanConfig,
+    }
+
+    // Check is_zero(a), is_equal(a, b), and/or/xor/not over them, and values[index]
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = MyConfig;
+        type FloorPlanner = floor_planner::V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![pallas::Base::zero(); self.values.len()],
+                ..Default::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            let advices = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            for advice in advices.iter() {
+                meta.enable_equality(*advice);
+            }
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+            let boolean_config = BooleanChip::configure(meta, advices);
+            MyConfig {
+                advices,
+                boolean_config,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            let chip = BooleanChip::construct(config.boolean_config);
+            let [a, b, index] = [self.a, self.b, pallas::Base::from(self.index)].map(|value| {
+                assign_free_advice(
+                    layouter.namespace(|| "witness"),
+                    config.advices[0],
+                    Value::known(value),
+                )
+                .unwrap()
+            });
+            let values = self
+                .values
+                .iter()
+                .map(|value| {
+                    assign_free_advice(
+                        layouter.namespace(|| "witness value"),
+                        config.advices[0],
+                        Value::known(*value),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            let is_zero = chip.is_zero(layouter.namespace(|| "a == 0"), &a)?;
+            let is_equal = chip.is_equal(layouter.namespace(|| "a == b"), &a, &b)?;
+            chip.assert_bit(layouter.namespace(|| "is_zero bit"), &is_zero)?;
+            let results = [
+                is_zero.clone(),
+                is_equal.clone(),
+                chip.and(layouter.namespace(|| "and"), &is_zero, &is_equal)?,
+                chip.or(layouter.namespace(|| "or"), &is_zero, &is_equal)?,
+                chip.xor(layouter.namespace(|| "xor"), &is_zero, &is_equal)?,
+                chip.not(layouter.namespace(|| "not"), &is_zero)?,
+            ];
+            for (result, expected) in results.iter().zip(self.expected) {
+                if expected {
+                    chip.assert_true(layouter.namespace(|| "check"), result)?;
+                } else {
+                    chip.assert_false(layouter.namespace(|| "check"), result)?;
+                }
+            }
+
+            let selected = chip.select(layouter.namespace(|| "select"), &index, &values)?;
+            let expected = assign_free_advice(
+                layouter.namespace(|| "witness expected"),
+                config.advices[0],
+                Value::known(
+                    self.values
+                        .get(self.index as usize)
+                        .copied()
+                        .unwrap_or_default(),
+                ),
+            )?;
+            layouter.assign_region(
+                || "check selected",
+                |mut region| region.constrain_equal(selected.cell(), expected.cell()),
+            )
+        }
+    }
+
+    let values: Vec<pallas::Base> = (0..5).map(|i| pallas::Base::from(i * 7 + 3)).collect();
+    let verify = |a: u64, b: u64, index: u64, expected: [bool; 6]| {
+        let circuit = MyCircuit {
+            a: pallas::Base::from(a),
+            b: pallas::Base::from(b),
+            index,
+            values: values.clone(),
+            expected,
+        };
+        MockProver::run(8, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+
+    // [is_zero, is_equal, and, or, xor, not]
+    assert!(verify(0, 0, 0, [true, true, true, true, false, false]));
+    assert!(verify(0, 5, 4, [true, false, false, true, true, false]));
+    assert!(verify(5, 5, 2, [false, true, false, true, true, true]));
+    assert!(verify(5, 6, 1, [false, false, false, false, false, true]));
+    // Wrong results
+    assert!(!verify(0, 5, 0, [false, false, false, true, true, false]));
+    assert!(!verify(5, 6, 0, [false, false, false, true, false, true]));
+    // The index is out of range
+    assert!(!verify(5, 6, 5, [false, false, false, false, false, true]));
+}
@@ -0,0 +1,301 @@
+/// The comparison chip constrains the order of u64 values, e.g. quantities, prices and heights.
+/// The operands are range checked to [0, 2^64) and the difference is range checked with the
+/// 10-bit lookup table, so a wrapped-around difference can't pass.
+use crate::circuit::gadgets::range_check_u64;
+use halo2_gadgets::utilities::lookup_range_check::LookupRangeCheckConfig;
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas;
+
+#[derive(Clone, Debug)]
+pub struct U64ComparisonChip {
+    config: U64ComparisonConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct U64ComparisonConfig {
+    advice: [Column<Advice>; 2],
+    // diff = rhs - lhs
+    s_le: Selector,
+    // diff = rhs - lhs - 1
+    s_lt: Selector,
+    lookup_config: LookupRangeCheckConfig<pallas::Base, 10>,
+}
+
+impl Chip<pallas::Base> for U64ComparisonChip {
+    type Config = U64ComparisonConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl U64ComparisonChip {
+    pub fn construct(config: U64ComparisonConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<pallas::Base>,
+        advice: [Column<Advice>; 2],
+        lookup_config: LookupRangeCheckConfig<pallas::Base, 10>,
+    ) -> U64ComparisonConfig {
+        let s_le = meta.selector();
+        let s_lt = meta.selector();
+
+        meta.create_gate("u64 comparison", |meta| {
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let diff = meta.query_advice(advice[0], Rotation::next());
+            let s_le = meta.query_selector(s_le);
+            let s_lt = meta.query_selector(s_lt);
+            let one = Expression::Constant(pallas::Base::one());
+
+            Constraints::without_selector([
+                (
+                    "lhs <= rhs",
+                    s_le * (rhs.clone() - lhs.clone() - diff.clone()),
+                ),
+                ("lhs < rhs", s_lt * (rhs - lhs - one - diff)),
+            ])
+        });
+
+        U64ComparisonConfig {
+            advice,
+            s_le,
+            s_lt,
+            lookup_config,
+        }
+    }
+
+    pub fn get_lookup_config(&self) -> &LookupRangeCheckConfig<pallas::Base, 10> {
+        &self.config.lookup_config
+    }
+
+    // Assign the difference of the operands, which is in [0, 2^64) iff the relation holds
+    fn assign_diff(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        lhs: &AssignedCell<pallas::Base, pallas::Base>,
+        rhs: &AssignedCell<pallas::Base, pallas::Base>,
+        strict: bool,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "u64 comparison",
+            |mut region: Region<'_, pallas::Base>| {
+                let offset = if strict {
+                    config.s_lt.enable(&mut region, 0)?;
+                    pallas::Base::one()
+                } else {
+                    config.s_le.enable(&mut region, 0)?;
+                    pallas::Base::zero()
+                };
+                lhs.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                rhs.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+                let diff = rhs.value().copied() - lhs.value() - offset;
+                region.assign_advice(|| "diff", config.advice[0], 1, || diff)
+            },
+        )
+    }
+
+    fn compare(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        lhs: &AssignedCell<pallas::Base, pallas::Base>,
+        rhs: &AssignedCell<pallas::Base, pallas::Base>,
+        strict: bool,
+    ) -> Result<(), Error> {
+        self.range_check(layouter.namespace(|| "lhs range check"), lhs)?;
+        self.range_check(layouter.namespace(|| "rhs range check"), rhs)?;
+        let diff = self.assign_diff(layouter.namespace(|| "diff"), lhs, rhs, strict)?;
+        self.range_check(layouter.namespace(|| "diff range check"), &diff)
+    }
+}
+
+pub trait U64ComparisonInstructions: Chip<pallas::Base> {
+    /// Constrains `value` to be in [0, 2^64).
+    fn range_check(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        value: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+
+    /// Constrains `a < b`.
+    fn less_than(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+
+    /// Constrains `a <= b`.
+    fn less_than_or_equal(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+
+    /// Constrains `lo <= value <= hi`.
+    fn in_range(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        value: &AssignedCell<pallas::Base, pallas::Base>,
+        lo: &AssignedCell<pallas::Base, pallas::Base>,
+        hi: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error>;
+}
+
+impl U64ComparisonInstructions for U64ComparisonChip {
+    fn range_check(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        value: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        range_check_u64(layouter, self.get_lookup_config(), value.clone())
+    }
+
+    fn less_than(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        self.compare(layouter, a, b, true)
+    }
+
+    fn less_than_or_equal(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        self.compare(layouter, a, b, false)
+    }
+
+    fn in_range(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        value: &AssignedCell<pallas::Base, pallas::Base>,
+        lo: &AssignedCell<pallas::Base, pallas::Base>,
+        hi: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        self.range_check(layouter.namespace(|| "value range check"), value)?;
+        self.range_check(layouter.namespace(|| "lo range check"), lo)?;
+        self.range_check(layouter.namespace(|| "hi range check"), hi)?;
+        let lower_diff = self.assign_diff(layouter.namespace(|| "value - lo"), lo, value, false)?;
+        self.range_check(layouter.namespace(|| "value - lo range check"), &lower_diff)?;
+        let upper_diff = self.assign_diff(layouter.namespace(|| "hi - value"), value, hi, false)?;
+        self.range_check(layouter.namespace(|| "hi - value range check"), &upper_diff)
+    }
+}
+
+#[test]
+fn test_halo2_u64_comparison_chip() {
+    use crate::circuit::gadgets::assign_free_advice;
+    use halo2_proofs::{
+        circuit::{floor_planner, Value},
+        dev::MockProver,
+        plonk::{Circuit, TableColumn},
+    };
+
+    #[derive(Default)]
+    struct MyCircuit {
+        a: u64,
+        b: u64,
+        c: u64,
+    }
+
+    #[derive(Clone, Debug)]
+    struct MyConfig {
+        advices: [Column<Advice>; 2],
+        table_idx: TableColumn,
+        comparison_config: U64ComparisonConfig,
+    }
+
+    // Constrain a < b, a <= b, and b in [a, c]
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = MyConfig;
+        type FloorPlanner = floor_planner::V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            let advices = [meta.advice_column(), meta.advice_column()];
+            for advice in advices.iter() {
+                meta.enable_equality(*advice);
+            }
+            let table_idx = meta.lookup_table_column();
+            let lookup_config = LookupRangeCheckConfig::configure(meta, advices[1], table_idx);
+            let comparison_config = U64ComparisonChip::configure(meta, advices, lookup_config);
+            MyConfig {
+                advices,
+                table_idx,
+                comparison_config,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            layouter.assign_table(
+                || "table_idx",
+                |mut table| {
+                    for index in 0..(1 << 10) {
+                        table.assign_cell(
+                            || "table_idx",
+                            config.table_idx,
+                            index,
+                            || Value::known(pallas::Base::from(index as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+            let chip = U64ComparisonChip::construct(config.comparison_config);
+            let [a, b, c] = [self.a, self.b, self.c].map(|v| {
+                assign_free_advice(
+                    layouter.namespace(|| "witness"),
+                    config.advices[0],
+                    Value::known(pallas::Base::from(v)),
+                )
+                .unwrap()
+            });
+            chip.less_than(layouter.namespace(|| "a < b"), &a, &b)?;
+            chip.less_than_or_equal(layouter.namespace(|| "a <= b"), &a, &b)?;
+            chip.in_range(layouter.namespace(|| "a <= b <= c"), &b, &a, &c)
+        }
+    }
+
+    let verify = |a, b, c| {
+        let circuit = MyCircuit { a, b, c };
+        MockProver::run(11, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+
+    assert!(verify(1, 2, 3));
+    assert!(verify(0, u64::MAX, u64::MAX));
+    assert!(verify(u64::MAX - 1, u64::MAX, u64::MAX));
+    // a == b
+    assert!(!verify(2, 2, 3));
+    // a > b
+    assert!(!verify(3, 2, 3));
+    assert!(!verify(u64::MAX, 0, u64::MAX));
+    // b > c
+    assert!(!verify(1, 4, 3));
+}
@@ -1,6 +1,6 @@
 /// Constrain flag * (lhs - rhs) = 0
 use halo2_proofs::{
-    circuit::{AssignedCell, Region},
+    circuit::{AssignedCell, Layouter, Region},
     plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Selector},
     poly::Rotation,
 };
@@ -60,3 +60,18 @@ impl ConditionalEqualConfig {
         Ok(())
     }
 }
+
+// Constrain lhs = rhs if the flag is set
+pub fn conditional_equal(
+    layouter: &mut impl Layouter<pallas::Base>,
+    config: &ConditionalEqualConfig,
+    name: &str,
+    flag: &AssignedCell<pallas::Base, pallas::Base>,
+    lhs: &AssignedCell<pallas::Base, pallas::Base>,
+    rhs: &AssignedCell<pallas::Base, pallas::Base>,
+) -> Result<(), Error> {
+    layouter.assign_region(
+        || format!("conditional equal: {}", name),
+        |mut region| config.assign_region(flag, lhs, rhs, 0, &mut region),
+    )
+}
@@ -0,0 +1,267 @@
+/// The division chip witnesses the quotient and remainder of u64 operands and proves
+/// `a = q * b + r` with `r < b`. The operands, the quotient and the remainder are range checked
+/// to [0, 2^64), so `q * b + r` can't wrap around the field.
+use crate::circuit::gadgets::comparison::{U64ComparisonChip, U64ComparisonInstructions};
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas;
+
+#[derive(Clone, Debug)]
+pub struct DivModChip {
+    config: DivModConfig,
+    comparison_chip: U64ComparisonChip,
+}
+
+#[derive(Clone, Debug)]
+pub struct DivModConfig {
+    advice: [Column<Advice>; 2],
+    s_div_mod: Selector,
+}
+
+impl Chip<pallas::Base> for DivModChip {
+    type Config = DivModConfig;
+    type Loaded = U64ComparisonChip;
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &self.comparison_chip
+    }
+}
+
+impl DivModChip {
+    pub fn construct(config: DivModConfig, comparison_chip: U64ComparisonChip) -> Self {
+        Self {
+            config,
+            comparison_chip,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<pallas::Base>,
+        advice: [Column<Advice>; 2],
+    ) -> DivModConfig {
+        let s_div_mod = meta.selector();
+
+        meta.create_gate("div mod", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let q = meta.query_advice(advice[0], Rotation::next());
+            let r = meta.query_advice(advice[1], Rotation::next());
+
+            Constraints::with_selector(s_div_mod, [("a = q * b + r", q * b + r - a)])
+        });
+
+        DivModConfig { advice, s_div_mod }
+    }
+}
+
+pub trait DivModInstructions: Chip<pallas::Base> {
+    /// Returns `(q, r)` such that `a = q * b + r` and `r < b`.
+    #[allow(clippy::type_complexity)]
+    fn div_mod(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<
+        (
+            AssignedCell<pallas::Base, pallas::Base>,
+            AssignedCell<pallas::Base, pallas::Base>,
+        ),
+        Error,
+    >;
+}
+
+impl DivModInstructions for DivModChip {
+    fn div_mod(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &AssignedCell<pallas::Base, pallas::Base>,
+        b: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<
+        (
+            AssignedCell<pallas::Base, pallas::Base>,
+            AssignedCell<pallas::Base, pallas::Base>,
+        ),
+        Error,
+    > {
+        let config = self.config();
+        let (q, r) = layouter.assign_region(
+            || "div mod",
+            |mut region: Region<'_, pallas::Base>| {
+                config.s_div_mod.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                // The quotient and remainder of a dividing by zero are not defined, and the
+                // constraint r < b fails anyway.
+                let q_r = a.value().zip(b.value()).map(|(a, b)| {
+                    let a = u64_from_base(a);
+                    let b = u64_from_base(b);
+                    if b == 0 {
+                        (0, a)
+                    } else {
+                        (a / b, a % b)
+                    }
+                });
+                let q = region.assign_advice(
+                    || "q",
+                    config.advice[0],
+                    1,
+                    || q_r.map(|(q, _)| pallas::Base::from(q)),
+                )?;
+                let r = region.assign_advice(
+                    || "r",
+                    config.advice[1],
+                    1,
+                    || q_r.map(|(_, r)| pallas::Base::from(r)),
+                )?;
+                Ok((q, r))
+            },
+        )?;
+
+        // r and b are range checked in the comparison
+        self.comparison_chip
+            .range_check(layouter.namespace(|| "a range check"), a)?;
+        self.comparison_chip
+            .range_check(layouter.namespace(|| "q range check"), &q)?;
+        self.comparison_chip
+            .less_than(layouter.namespace(|| "r < b"), &r, b)?;
+
+        Ok((q, r))
+    }
+}
+
+// The witness of a value out of the u64 range is truncated, and the range check fails anyway.
+fn u64_from_base(value: &pallas::Base) -> u64 {
+    use ff::PrimeField;
+    let repr = value.to_repr();
+    u64::from_le_bytes(repr[..8].try_into().unwrap())
+}
+
+#[test]
+fn test_halo2_div_mod_chip() {
+    use crate::circuit::gadgets::{
+        assign_free_advice,
+        comparison::{U64ComparisonChip, U64ComparisonConfig},
+    };
+    use halo2_gadgets::utilities::lookup_range_check::LookupRangeCheckConfig;
+    use halo2_proofs::{
+        circuit::{floor_planner, Value},
+        dev::MockProver,
+        plonk::{Circuit, TableColumn},
+    };
+
+    #[derive(Default)]
+    struct MyCircuit {
+        a: u64,
+        b: u64,
+        expected_q: u64,
+        expected_r: u64,
+    }
+
+    #[derive(Clone, Debug)]
+    struct MyConfig {
+        advices: [Column<Advice>; 2],
+        table_idx: TableColumn,
+        comparison_config: U64ComparisonConfig,
+        div_mod_config: DivModConfig,
+    }
+
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = MyConfig;
+        type FloorPlanner = floor_planner::V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            let advices = [meta.advice_column(), meta.advice_column()];
+            for advice in advices.iter() {
+                meta.enable_equality(*advice);
+            }
+            let table_idx = meta.lookup_table_column();
+            let lookup_config = LookupRangeCheckConfig::configure(meta, advices[1], table_idx);
+            let comparison_config = U64ComparisonChip::configure(meta, advices, lookup_config);
+            let div_mod_config = DivModChip::configure(meta, advices);
+            MyConfig {
+                advices,
+                table_idx,
+                comparison_config,
+                div_mod_config,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            layouter.assign_table(
+                || "table_idx",
+                |mut table| {
+                    for index in 0..(1 << 10) {
+                        table.assign_cell(
+                            || "table_idx",
+                            config.table_idx,
+                            index,
+                            || Value::known(pallas::Base::from(index as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+            let chip = DivModChip::construct(
+                config.div_mod_config,
+                U64ComparisonChip::construct(config.comparison_config),
+            );
+            let [a, b, expected_q, expected_r] = [self.a, self.b, self.expected_q, self.expected_r]
+                .map(|v| {
+                    assign_free_advice(
+                        layouter.namespace(|| "witness"),
+                        config.advices[0],
+                        Value::known(pallas::Base::from(v)),
+                    )
+                    .unwrap()
+                });
+            let (q, r) = chip.div_mod(layouter.namespace(|| "a / b"), &a, &b)?;
+            layouter.assign_region(
+                || "check q and r",
+                |mut region| {
+                    region.constrain_equal(q.cell(), expected_q.cell())?;
+                    region.constrain_equal(r.cell(), expected_r.cell())
+                },
+            )
+        }
+    }
+
+    let verify = |a, b, expected_q, expected_r| {
+        let circuit = MyCircuit {
+            a,
+            b,
+            expected_q,
+            expected_r,
+        };
+        MockProver::run(11, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+
+    assert!(verify(15, 10, 1, 5));
+    assert!(verify(20, 10, 2, 0));
+    assert!(verify(3, 10, 0, 3));
+    assert!(verify(u64::MAX, 7, u64::MAX / 7, u64::MAX % 7));
+    // The quotient and remainder are unique
+    assert!(!verify(15, 10, 0, 15));
+    // Division by zero
+    assert!(!verify(15, 0, 0, 15));
+}
@@ -0,0 +1,210 @@
+/// The issuance gadgets constrain the resources that can be created only once, e.g. the issuance
+/// resource of an issued token or the mint resource of an NFT. Such a resource is authorized by
+/// the issuer, and all fields other than the label, value and quantity are determined by the
+/// label, so its nullifier can only be revealed once.
+use crate::{
+    circuit::{
+        gadgets::{
+            assign_free_constant, conditional_equal::conditional_equal,
+            poseidon_hash::poseidon_hash_gadget,
+        },
+        resource_logic_circuit::{ResourceLogicConfig, ResourceStatus},
+    },
+    constant::{
+        TaigaFixedBases, PRF_EXPAND_PERSONALIZATION_TO_FIELD, PRF_EXPAND_PSI, PRF_EXPAND_RCM,
+    },
+    nullifier::NullifierKeyContainer,
+};
+use halo2_gadgets::ecc::{chip::EccChip, NonIdentityPoint};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    plonk::Error,
+};
+use lazy_static::lazy_static;
+use pasta_curves::pallas;
+
+lazy_static! {
+    // The label-determined resources use the zero nullifier key, so the nullifier of such a
+    // resource is determined by the label.
+    pub static ref ISSUANCE_NPK: pallas::Base =
+        NullifierKeyContainer::from_key(pallas::Base::zero()).get_npk();
+}
+
+// Constrain the resource to be authorized by the issuer if the flag is set: the pk and rcv_pk
+// are the issuer pk, and the auth and receiver resource logics are the given ones.
+#[allow(clippy::too_many_arguments)]
+pub fn constrain_issuer_authorization(
+    layouter: &mut impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    flag: &AssignedCell<pallas::Base, pallas::Base>,
+    issuer_pk: &NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>,
+    pk: &NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>,
+    rcv_pk: &NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>,
+    auth_resource_logic_vk: &AssignedCell<pallas::Base, pallas::Base>,
+    receiver_resource_logic_vk: &AssignedCell<pallas::Base, pallas::Base>,
+    issuer_auth_vk: pallas::Base,
+    issuer_receiver_vk: pallas::Base,
+) -> Result<(), Error> {
+    let conditional_equal_config = &config.conditional_equal_config;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance pk x",
+        flag,
+        &pk.inner().x(),
+        &issuer_pk.inner().x(),
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance pk y",
+        flag,
+        &pk.inner().y(),
+        &issuer_pk.inner().y(),
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance rcv_pk x",
+        flag,
+        &rcv_pk.inner().x(),
+        &issuer_pk.inner().x(),
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance rcv_pk y",
+        flag,
+        &rcv_pk.inner().y(),
+        &issuer_pk.inner().y(),
+    )?;
+    let issuer_auth_vk = assign_free_constant(
+        layouter.namespace(|| "constant issuer auth vk"),
+        config.advices[0],
+        issuer_auth_vk,
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance auth vk",
+        flag,
+        auth_resource_logic_vk,
+        &issuer_auth_vk,
+    )?;
+    let issuer_receiver_vk = assign_free_constant(
+        layouter.namespace(|| "constant issuer receiver vk"),
+        config.advices[0],
+        issuer_receiver_vk,
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance receiver vk",
+        flag,
+        receiver_resource_logic_vk,
+        &issuer_receiver_vk,
+    )?;
+
+    Ok(())
+}
+
+// Constrain the resource fields other than the label, value and quantity to be determined by the
+// label if the flag is set. The nullifier of such a resource is unique for the label.
+pub fn constrain_label_determined_resource(
+    layouter: &mut impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    flag: &AssignedCell<pallas::Base, pallas::Base>,
+    self_resource: &ResourceStatus,
+) -> Result<(), Error> {
+    let conditional_equal_config = &config.conditional_equal_config;
+    let issuance_npk = assign_free_constant(
+        layouter.namespace(|| "constant issuance npk"),
+        config.advices[0],
+        *ISSUANCE_NPK,
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance npk",
+        flag,
+        &self_resource.resource.npk,
+        &issuance_npk,
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance nonce",
+        flag,
+        &self_resource.resource.nonce,
+        &self_resource.resource.label,
+    )?;
+    let constant_zero = assign_free_constant(
+        layouter.namespace(|| "zero"),
+        config.advices[0],
+        pallas::Base::zero(),
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance rseed",
+        flag,
+        &self_resource.resource.rseed,
+        &constant_zero,
+    )?;
+
+    // The psi and rcm of input resources are not derived in the circuit, derive them
+    // for the label-determined resource.
+    let prf_expand_personalization = assign_free_constant(
+        layouter.namespace(|| "constant PRF_EXPAND_PERSONALIZATION_TO_FIELD"),
+        config.advices[0],
+        *PRF_EXPAND_PERSONALIZATION_TO_FIELD,
+    )?;
+    let prf_expand_psi = assign_free_constant(
+        layouter.namespace(|| "constant PRF_EXPAND_PSI"),
+        config.advices[0],
+        pallas::Base::from(PRF_EXPAND_PSI as u64),
+    )?;
+    let psi = poseidon_hash_gadget(
+        config.poseidon_config.clone(),
+        layouter.namespace(|| "derive the psi"),
+        [
+            prf_expand_personalization.clone(),
+            prf_expand_psi,
+            self_resource.resource.rseed.clone(),
+            self_resource.resource.nonce.clone(),
+        ],
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance psi",
+        flag,
+        &self_resource.resource.psi,
+        &psi,
+    )?;
+    let prf_expand_rcm = assign_free_constant(
+        layouter.namespace(|| "constant PRF_EXPAND_RCM"),
+        config.advices[0],
+        pallas::Base::from(PRF_EXPAND_RCM as u64),
+    )?;
+    let rcm = poseidon_hash_gadget(
+        config.poseidon_config.clone(),
+        layouter.namespace(|| "derive the rcm"),
+        [
+            prf_expand_personalization,
+            prf_expand_rcm,
+            self_resource.resource.rseed.clone(),
+            self_resource.resource.nonce.clone(),
+        ],
+    )?;
+    conditional_equal(
+        layouter,
+        conditional_equal_config,
+        "issuance rcm",
+        flag,
+        &self_resource.resource.rcm,
+        &rcm,
+    )?;
+
+    Ok(())
+}
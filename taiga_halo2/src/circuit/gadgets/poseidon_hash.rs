@@ -1,10 +1,17 @@
+use crate::{
+    circuit::gadgets::assign_free_constant,
+    constant::{POSEIDON_RATE, POSEIDON_WIDTH},
+    utils::{poseidon_var_len_capacity, poseidon_var_len_padded_len},
+};
 use halo2_gadgets::poseidon::{
-    primitives as poseidon, primitives::ConstantLength, Hash as PoseidonHash,
-    Pow5Chip as PoseidonChip, Pow5Config as PoseidonConfig,
+    primitives as poseidon,
+    primitives::{Absorbing, ConstantLength},
+    Hash as PoseidonHash, PaddedWord, PoseidonInstructions, PoseidonSpongeInstructions,
+    Pow5Chip as PoseidonChip, Pow5Config as PoseidonConfig, StateWord,
 };
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter},
-    plonk::Error,
+    plonk::{Advice, Column, Error},
 };
 use pasta_curves::pallas;
 
@@ -22,3 +29,202 @@ pub fn poseidon_hash_gadget<const L: usize>(
 
     poseidon_hasher.hash(layouter.namespace(|| "poseidon hash"), messages)
 }
+
+/// The in-circuit `poseidon_hash_var_len`. The domain tag and the message length are fixed by
+/// the circuit.
+pub fn poseidon_hash_var_len_gadget(
+    config: PoseidonConfig<pallas::Base, POSEIDON_WIDTH, POSEIDON_RATE>,
+    mut layouter: impl Layouter<pallas::Base>,
+    advice: Column<Advice>,
+    domain_tag: u64,
+    messages: &[AssignedCell<pallas::Base, pallas::Base>],
+) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+    let poseidon_chip = PoseidonChip::construct(config);
+
+    // Init poseidon sponge state
+    let zero = assign_free_constant(layouter.namespace(|| "zero"), advice, pallas::Base::zero())?;
+    let capacity = assign_free_constant(
+        layouter.namespace(|| "length || domain_tag"),
+        advice,
+        poseidon_var_len_capacity(domain_tag, messages.len()),
+    )?;
+    let init_state = vec![
+        StateWord::from(zero.clone()),
+        StateWord::from(zero),
+        StateWord::from(capacity),
+    ];
+    let mut state = init_state.try_into().unwrap();
+
+    // Absorb the messages with the zero padding
+    let padded_word = |idx: usize| {
+        messages
+            .get(idx)
+            .map_or(PaddedWord::Padding(pallas::Base::zero()), |m| {
+                PaddedWord::Message(m.clone())
+            })
+    };
+    for chunk_start in (0..poseidon_var_len_padded_len(messages.len())).step_by(POSEIDON_RATE) {
+        let mut input: Absorbing<_, POSEIDON_RATE> = Absorbing::init_with(padded_word(chunk_start));
+        for idx in 1..POSEIDON_RATE {
+            input.0[idx] = Some(padded_word(chunk_start + idx));
+        }
+        state = <PoseidonChip<_, POSEIDON_WIDTH, POSEIDON_RATE> as PoseidonSpongeInstructions<
+            pallas::Base,
+            poseidon::P128Pow5T3,
+            ConstantLength<2>, // ConstantLength<2> is not used
+            POSEIDON_WIDTH,
+            POSEIDON_RATE,
+        >>::add_input(&poseidon_chip, &mut layouter, &state, &input)?;
+        state = <PoseidonChip<_, POSEIDON_WIDTH, POSEIDON_RATE> as PoseidonInstructions<
+            pallas::Base,
+            poseidon::P128Pow5T3,
+            POSEIDON_WIDTH,
+            POSEIDON_RATE,
+        >>::permute(&poseidon_chip, &mut layouter, &state)?;
+    }
+
+    Ok(state[0].clone().into())
+}
+
+#[test]
+fn test_halo2_poseidon_hash_var_len_gadget() {
+    use crate::circuit::gadgets::assign_free_advice;
+    use crate::utils::{poseidon_hash, poseidon_hash_var_len};
+    use halo2_proofs::{
+        arithmetic::Field,
+        circuit::{floor_planner, Value},
+        dev::MockProver,
+        plonk::{Circuit, ConstraintSystem},
+    };
+    use rand::rngs::OsRng;
+
+    #[derive(Default)]
+    struct MyCircuit {
+        domain_tag: u64,
+        messages: Vec<pallas::Base>,
+        expected_hash: pallas::Base,
+    }
+
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = (
+            [Column<Advice>; 4],
+            PoseidonConfig<pallas::Base, POSEIDON_WIDTH, POSEIDON_RATE>,
+        );
+        type FloorPlanner = floor_planner::V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                domain_tag: self.domain_tag,
+                messages: vec![pallas::Base::zero(); self.messages.len()],
+                expected_hash: pallas::Base::zero(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            let advices = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            for advice in advices.iter() {
+                meta.enable_equality(*advice);
+            }
+            let lagrange_coeffs = [
+                meta.fixed_column(),
+                meta.fixed_column(),
+                meta.fixed_column(),
+                meta.fixed_column(),
+                meta.fixed_column(),
+                meta.fixed_column(),
+            ];
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+
+            let poseidon_config = PoseidonChip::configure::<poseidon::P128Pow5T3>(
+                meta,
+                advices[0..3].try_into().unwrap(),
+                advices[3],
+                lagrange_coeffs[0..3].try_into().unwrap(),
+                lagrange_coeffs[3..6].try_into().unwrap(),
+            );
+            (advices, poseidon_config)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            let (advices, poseidon_config) = config;
+            let messages = self
+                .messages
+                .iter()
+                .map(|&v| {
+                    assign_free_advice(
+                        layouter.namespace(|| "message"),
+                        advices[0],
+                        Value::known(v),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let hash = poseidon_hash_var_len_gadget(
+                poseidon_config,
+                layouter.namespace(|| "poseidon hash var len"),
+                advices[0],
+                self.domain_tag,
+                &messages,
+            )?;
+            let expected_hash = assign_free_advice(
+                layouter.namespace(|| "expected hash"),
+                advices[0],
+                Value::known(self.expected_hash),
+            )?;
+            layouter.assign_region(
+                || "check hash",
+                |mut region| region.constrain_equal(hash.cell(), expected_hash.cell()),
+            )
+        }
+    }
+
+    let verify = |domain_tag: u64, messages: Vec<pallas::Base>, expected_hash: pallas::Base| {
+        let circuit = MyCircuit {
+            domain_tag,
+            messages,
+            expected_hash,
+        };
+        MockProver::run(10, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+
+    let mut rng = OsRng;
+    let domain_tag = 7;
+    for len in [0, 1, 2, 3, 5] {
+        let messages = (0..len)
+            .map(|_| pallas::Base::random(&mut rng))
+            .collect::<Vec<_>>();
+        let expected_hash = poseidon_hash_var_len(domain_tag, &messages);
+        assert!(verify(domain_tag, messages.clone(), expected_hash));
+
+        // The domain tag and the length separate the hashes
+        assert!(!verify(domain_tag + 1, messages.clone(), expected_hash));
+        let mut extended_messages = messages;
+        extended_messages.push(pallas::Base::zero());
+        assert_ne!(
+            poseidon_hash_var_len(domain_tag, &extended_messages),
+            expected_hash
+        );
+    }
+
+    // It equals the constant length hash with the zero domain tag
+    let (left, right) = (
+        pallas::Base::random(&mut rng),
+        pallas::Base::random(&mut rng),
+    );
+    assert_eq!(
+        poseidon_hash_var_len(0, &[left, right]),
+        poseidon_hash(left, right)
+    );
+}
@@ -0,0 +1,217 @@
+/// The validity window gadget constrains a resource logic against the transaction validity window.
+/// The window is a free public input of every resource logic, and the executor checks it contains
+/// the window of the transaction. The heights compared with the window must be u64.
+use crate::{
+    circuit::gadgets::{
+        assign_free_instance,
+        comparison::{U64ComparisonChip, U64ComparisonInstructions},
+    },
+    constant::{RESOURCE_LOGIC_CIRCUIT_EXPIRES_AT_IDX, RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX},
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, Error, Instance},
+};
+use pasta_curves::pallas;
+
+#[derive(Clone, Debug)]
+pub struct ValidityWindowVariables {
+    pub not_before: AssignedCell<pallas::Base, pallas::Base>,
+    pub expires_at: AssignedCell<pallas::Base, pallas::Base>,
+}
+
+// Load the validity window from the resource logic public inputs
+pub fn load_validity_window(
+    mut layouter: impl Layouter<pallas::Base>,
+    instances: Column<Instance>,
+    advice: Column<Advice>,
+) -> Result<ValidityWindowVariables, Error> {
+    let not_before = assign_free_instance(
+        layouter.namespace(|| "load not_before"),
+        instances,
+        RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX,
+        advice,
+    )?;
+    let expires_at = assign_free_instance(
+        layouter.namespace(|| "load expires_at"),
+        instances,
+        RESOURCE_LOGIC_CIRCUIT_EXPIRES_AT_IDX,
+        advice,
+    )?;
+    Ok(ValidityWindowVariables {
+        not_before,
+        expires_at,
+    })
+}
+
+impl ValidityWindowVariables {
+    /// Constrain the transaction to expire at or before the deadline, e.g. an intent expiry.
+    pub fn constrain_expires_at_or_before(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        comparison_chip: &U64ComparisonChip,
+        deadline: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        comparison_chip.less_than_or_equal(layouter, &self.expires_at, deadline)
+    }
+
+    /// Constrain the transaction to be valid no earlier than the unlock height, e.g. a vesting resource.
+    pub fn constrain_not_before_at_or_after(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        comparison_chip: &U64ComparisonChip,
+        unlock_height: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<(), Error> {
+        comparison_chip.less_than_or_equal(layouter, unlock_height, &self.not_before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::load_validity_window;
+    use crate::{
+        circuit::{
+            blake2s::publicize_default_dynamic_resource_logic_commitments,
+            gadgets::{assign_free_advice, comparison::U64ComparisonChip},
+            resource_logic_circuit::{
+                ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+                ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+            },
+        },
+        constant::SETUP_PARAMS_MAP,
+        error::TransactionError,
+        proof::Proof,
+        resource::RandomSeed,
+        resource_logic_commitment::ResourceLogicCommitment,
+        resource_logic_vk::ResourceLogicVerifyingKey,
+        resource_tree::ResourceExistenceWitness,
+        transaction::ValidityWindow,
+    };
+    use halo2_proofs::{
+        circuit::{floor_planner, Layouter, Value},
+        plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+    };
+    use pasta_curves::pallas;
+    use rand::{rngs::OsRng, RngCore};
+
+    // A resource that expires at the deadline and unlocks at the unlock height
+    #[derive(Clone, Debug, Default)]
+    struct ValidityWindowTestCircuit {
+        self_resource: ResourceExistenceWitness,
+        window: ValidityWindow,
+        unlock_height: u64,
+        deadline: u64,
+    }
+
+    impl ResourceLogicCircuit for ValidityWindowTestCircuit {
+        fn custom_constraints(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+            _self_resource: ResourceStatus,
+        ) -> Result<(), Error> {
+            let comparison_chip = U64ComparisonChip::construct(config.comparison_config.clone());
+            let window = load_validity_window(
+                layouter.namespace(|| "load validity window"),
+                config.instances,
+                config.advices[0],
+            )?;
+
+            let deadline = assign_free_advice(
+                layouter.namespace(|| "witness deadline"),
+                config.advices[0],
+                Value::known(pallas::Base::from(self.deadline)),
+            )?;
+            window.constrain_expires_at_or_before(
+                layouter.namespace(|| "check expiry"),
+                &comparison_chip,
+                &deadline,
+            )?;
+
+            let unlock_height = assign_free_advice(
+                layouter.namespace(|| "witness unlock height"),
+                config.advices[0],
+                Value::known(pallas::Base::from(self.unlock_height)),
+            )?;
+            window.constrain_not_before_at_or_after(
+                layouter.namespace(|| "check unlock"),
+                &comparison_chip,
+                &unlock_height,
+            )?;
+
+            publicize_default_dynamic_resource_logic_commitments(
+                &mut layouter,
+                config.advices[0],
+                config.instances,
+            )?;
+
+            Ok(())
+        }
+
+        fn get_validity_window(&self) -> ValidityWindow {
+            self.window
+        }
+
+        fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+            let mut public_inputs = self.get_mandatory_public_inputs();
+            let default_resource_logic_cm: [pallas::Base; 2] =
+                ResourceLogicCommitment::default().to_public_inputs();
+            public_inputs.extend(default_resource_logic_cm);
+            public_inputs.extend(default_resource_logic_cm);
+            public_inputs.extend(self.get_validity_window_public_inputs());
+            let padding = ResourceLogicPublicInputs::get_public_input_padding(
+                public_inputs.len(),
+                &RandomSeed::random(&mut rng),
+            );
+            public_inputs.extend(padding);
+            public_inputs.into()
+        }
+
+        fn get_self_resource(&self) -> ResourceExistenceWitness {
+            self.self_resource
+        }
+    }
+
+    crate::resource_logic_circuit_impl!(ValidityWindowTestCircuit);
+    crate::resource_logic_verifying_info_impl!(ValidityWindowTestCircuit);
+
+    #[test]
+    fn test_halo2_validity_window_gadget() {
+        use crate::constant::TAIGA_RESOURCE_TREE_DEPTH;
+        use crate::merkle_tree::LR;
+        use crate::resource::tests::random_resource;
+        use ff::Field;
+        use halo2_proofs::dev::MockProver;
+
+        let mut rng = OsRng;
+        let merkle_path = [(pallas::Base::zero(), LR::R); TAIGA_RESOURCE_TREE_DEPTH];
+        let self_resource = ResourceExistenceWitness::new(random_resource(&mut rng), merkle_path);
+        let mut circuit = ValidityWindowTestCircuit {
+            self_resource,
+            window: ValidityWindow::new(20, 50),
+            unlock_height: 10,
+            deadline: 100,
+        };
+
+        // The window is within [unlock_height, deadline]
+        let public_inputs = circuit.get_public_inputs(&mut rng);
+        assert_eq!(public_inputs.get_validity_window(), Some(circuit.window));
+        let prover =
+            MockProver::<pallas::Base>::run(15, &circuit, vec![public_inputs.to_vec()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The transaction may expire after the deadline
+        circuit.window = ValidityWindow::new(20, 101);
+        let public_inputs = circuit.get_public_inputs(&mut rng);
+        let prover =
+            MockProver::<pallas::Base>::run(15, &circuit, vec![public_inputs.to_vec()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // The transaction may be valid before the unlock height
+        circuit.window = ValidityWindow::new(9, 50);
+        let public_inputs = circuit.get_public_inputs(&mut rng);
+        let prover =
+            MockProver::<pallas::Base>::run(15, &circuit, vec![public_inputs.to_vec()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1,13 +1,12 @@
 use crate::constant::{
-    TaigaFixedBases, POSEIDON_TO_CURVE_INPUT_LEN, POSEIDON_TO_FIELD_U_0_POSTFIX,
-    POSEIDON_TO_FIELD_U_1_POSTFIX,
+    TaigaFixedBases, POSEIDON_TO_FIELD_U_0_POSTFIX, POSEIDON_TO_FIELD_U_1_POSTFIX,
 };
 use halo2_gadgets::{
     ecc::{chip::EccChip, Point},
     poseidon::Pow5Config as PoseidonConfig,
 };
 use halo2_proofs::{
-    circuit::{AssignedCell, Layouter},
+    circuit::{AssignedCell, Chip, Layouter},
     plonk::{Advice, Column, ConstraintSystem, Error},
 };
 use pasta_curves::pallas;
@@ -15,184 +14,148 @@ use pasta_curves::pallas;
 use super::curve::{
     iso_map::MapToCurveConfig, map_to_curve::IsoMapConfig, to_affine::ToAffineConfig,
 };
-use crate::circuit::gadgets::poseidon_hash::poseidon_hash_gadget;
-
-// TODO: make HashToCurve a chip
-// pub trait HashToCurveInstructions<F: FieldExt>: Chip<F> {
-//     type Var;
-//     fn hash_to_curve(
-//         &self,
-//         layouter: impl Layouter<F>,
-//         messages: &[Self::Var],
-//     ) -> Result<(), Error>;
-// }
-
-// #[derive(Clone, Debug)]
-// pub struct HashToCurveChip<F: FieldExt> {
-//     config: HashToCurveConfig,
-//     _marker: PhantomData<F>,
-// }
-
-// impl<F: FieldExt> Chip<F> for HashToCurveChip<F> {
-//     type Config = HashToCurveConfig;
-//     type Loaded = ();
-
-//     fn config(&self) -> &Self::Config {
-//         &self.config
-//     }
-
-//     fn loaded(&self) -> &Self::Loaded {
-//         &()
-//     }
-// }
-
-// impl<F: FieldExt> HashToCurveInstructions<F> for HashToCurveChip<F> {
-//     type Var = AssignedCell<F, F>;
-//     fn hash_to_curve(
-//         &self,
-//         mut layouter: impl Layouter<F>,
-//         messages: &[Self::Var],
-//     ) -> Result<(), Error> {
-//         Ok(())
-//     }
-// }
-
-pub fn hash_to_curve_circuit(
-    mut layouter: impl Layouter<pallas::Base>,
+use crate::circuit::gadgets::{assign_free_constant, poseidon_hash::poseidon_hash_gadget};
+
+pub trait HashToCurveInstructions: Chip<pallas::Base> {
+    type Var;
+    type Point;
+
+    /// Hashes the messages to a curve point, matching `poseidon_to_curve::<L>`. `L` is the
+    /// length of the messages plus the length of the domain postfix, so any message length is
+    /// supported as long as `L` is set accordingly.
+    fn hash_to_curve<const L: usize>(
+        &self,
+        layouter: impl Layouter<pallas::Base>,
+        messages: &[Self::Var],
+    ) -> Result<Self::Point, Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct HashToCurveChip {
     config: HashToCurveConfig,
     ecc_chip: EccChip<TaigaFixedBases>,
-    messages: &[AssignedCell<pallas::Base, pallas::Base>],
-) -> Result<Point<pallas::Affine, EccChip<TaigaFixedBases>>, Error> {
-    // hash to u_0
-    let u_0 = {
-        let u_0_postfix: Vec<AssignedCell<pallas::Base, pallas::Base>> =
-            POSEIDON_TO_FIELD_U_0_POSTFIX
-                .iter()
-                .map(|&v| {
-                    layouter
-                        .assign_region(
-                            || "load constant",
-                            |mut region| {
-                                region.assign_advice_from_constant(
-                                    || "constant value",
-                                    config.advices[0],
-                                    0,
-                                    v,
-                                )
-                            },
-                        )
-                        .unwrap()
-                })
-                .collect();
-        let poseidon_msg = [messages, &u_0_postfix]
+}
+
+impl Chip<pallas::Base> for HashToCurveChip {
+    type Config = HashToCurveConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl HashToCurveChip {
+    pub fn construct(config: HashToCurveConfig, ecc_chip: EccChip<TaigaFixedBases>) -> Self {
+        Self { config, ecc_chip }
+    }
+
+    pub fn ecc_chip(&self) -> EccChip<TaigaFixedBases> {
+        self.ecc_chip.clone()
+    }
+
+    // Hash the messages with the domain postfix to a field element
+    fn hash_to_field<const L: usize>(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        messages: &[AssignedCell<pallas::Base, pallas::Base>],
+        postfix: &[pallas::Base],
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        assert_eq!(
+            messages.len() + postfix.len(),
+            L,
+            "the hash to curve input length must be L"
+        );
+        let postfix = postfix
+            .iter()
+            .map(|&v| {
+                assign_free_constant(
+                    layouter.namespace(|| "load postfix"),
+                    self.config.advices[0],
+                    v,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let poseidon_msg: [AssignedCell<pallas::Base, pallas::Base>; L] = [messages, &postfix]
             .concat()
             .try_into()
             .expect("slice with incorrect length");
-        poseidon_hash_gadget::<POSEIDON_TO_CURVE_INPUT_LEN>(
-            config.poseidon_config.clone(),
-            layouter.namespace(|| "compute u_0"),
+        poseidon_hash_gadget(
+            self.config.poseidon_config.clone(),
+            layouter.namespace(|| "poseidon hash"),
             poseidon_msg,
-        )?
-    };
+        )
+    }
 
-    // hash to u_1
-    let u_1 = {
-        let u_1_postfix: Vec<AssignedCell<pallas::Base, pallas::Base>> =
-            POSEIDON_TO_FIELD_U_1_POSTFIX
-                .iter()
-                .map(|&v| {
-                    layouter
-                        .assign_region(
-                            || "load constant",
-                            |mut region| {
-                                region.assign_advice_from_constant(
-                                    || "constant value",
-                                    config.advices[1],
-                                    0,
-                                    v,
-                                )
-                            },
-                        )
-                        .unwrap()
-                })
-                .collect();
-        let poseidon_msg = [messages, &u_1_postfix]
-            .concat()
-            .try_into()
-            .expect("slice with incorrect length");
-        poseidon_hash_gadget::<POSEIDON_TO_CURVE_INPUT_LEN>(
-            config.poseidon_config.clone(),
+    // Map the field element to an affine point
+    fn map_to_affine(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        u: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<Point<pallas::Affine, EccChip<TaigaFixedBases>>, Error> {
+        let q = layouter.assign_region(
+            || "map_to_curve",
+            |mut region| {
+                self.config
+                    .map_to_curve_config
+                    .assign_region(u, 0, &mut region)
+            },
+        )?;
+
+        let r = layouter.assign_region(
+            || "isogeny map",
+            |mut region| {
+                self.config
+                    .iso_map_config
+                    .assign_region(&q.0, &q.1, &q.2, 0, &mut region)
+            },
+        )?;
+
+        layouter.assign_region(
+            || "to affine",
+            |mut region| {
+                self.config.to_affine_config.assign_region(
+                    self.ecc_chip.clone(),
+                    &r.0,
+                    &r.1,
+                    &r.2,
+                    0,
+                    &mut region,
+                )
+            },
+        )
+    }
+}
+
+impl HashToCurveInstructions for HashToCurveChip {
+    type Var = AssignedCell<pallas::Base, pallas::Base>;
+    type Point = Point<pallas::Affine, EccChip<TaigaFixedBases>>;
+
+    fn hash_to_curve<const L: usize>(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        messages: &[Self::Var],
+    ) -> Result<Self::Point, Error> {
+        let u_0 = self.hash_to_field::<L>(
+            layouter.namespace(|| "compute u_0"),
+            messages,
+            &POSEIDON_TO_FIELD_U_0_POSTFIX,
+        )?;
+        let u_1 = self.hash_to_field::<L>(
             layouter.namespace(|| "compute u_1"),
-            poseidon_msg,
-        )?
-    };
+            messages,
+            &POSEIDON_TO_FIELD_U_1_POSTFIX,
+        )?;
 
-    // Use messages as u_0 and u_1
-    let q_0 = layouter.assign_region(
-        || "u_0 map_to_curve",
-        |mut region| {
-            config
-                .map_to_curve_config
-                .assign_region(&u_0, 0, &mut region)
-        },
-    )?;
-
-    let r_0 = layouter.assign_region(
-        || "q_0 isogeny map",
-        |mut region| {
-            config
-                .iso_map_config
-                .assign_region(&q_0.0, &q_0.1, &q_0.2, 0, &mut region)
-        },
-    )?;
-
-    let k_0 = layouter.assign_region(
-        || "r_0 to affine",
-        |mut region| {
-            config.to_affine_config.assign_region(
-                ecc_chip.clone(),
-                &r_0.0,
-                &r_0.1,
-                &r_0.2,
-                0,
-                &mut region,
-            )
-        },
-    )?;
-
-    let q_1 = layouter.assign_region(
-        || "u_1 map_to_curve",
-        |mut region| {
-            config
-                .map_to_curve_config
-                .assign_region(&u_1, 0, &mut region)
-        },
-    )?;
-
-    let r_1 = layouter.assign_region(
-        || "q_1 isogeny map",
-        |mut region| {
-            config
-                .iso_map_config
-                .assign_region(&q_1.0, &q_1.1, &q_1.2, 0, &mut region)
-        },
-    )?;
-
-    let k_1 = layouter.assign_region(
-        || "r_1 to affine",
-        |mut region| {
-            config.to_affine_config.assign_region(
-                ecc_chip.clone(),
-                &r_1.0,
-                &r_1.1,
-                &r_1.2,
-                0,
-                &mut region,
-            )
-        },
-    )?;
+        let k_0 = self.map_to_affine(layouter.namespace(|| "u_0 to curve"), &u_0)?;
+        let k_1 = self.map_to_affine(layouter.namespace(|| "u_1 to curve"), &u_1)?;
 
-    k_0.add(layouter.namespace(|| "k_0 + k_1"), &k_1)
+        k_0.add(layouter.namespace(|| "k_0 + k_1"), &k_1)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -227,26 +190,46 @@ impl HashToCurveConfig {
     }
 }
 
-#[test]
-fn test_hash_to_curve_circuit() {
+#[cfg(test)]
+mod tests {
+    use super::{HashToCurveChip, HashToCurveConfig, HashToCurveInstructions};
+    use crate::circuit::gadgets::assign_free_advice;
+    use crate::constant::{TaigaFixedBases, POSEIDON_TO_CURVE_INPUT_LEN};
+    use crate::utils::poseidon_to_curve;
     use halo2_gadgets::{
-        ecc::chip::EccConfig,
+        ecc::{
+            chip::{EccChip, EccConfig},
+            Point,
+        },
         poseidon::{primitives as poseidon, Pow5Chip as PoseidonChip},
         utilities::lookup_range_check::LookupRangeCheckConfig,
     };
-    use pasta_curves::group::Curve;
-
-    use crate::circuit::gadgets::assign_free_advice;
-    use crate::utils::poseidon_to_curve;
     use halo2_proofs::{
+        arithmetic::Field,
         circuit::{Layouter, SimpleFloorPlanner, Value},
         dev::MockProver,
         plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
     };
-    #[derive(Default)]
-    struct MyCircuit {}
+    use pasta_curves::{group::Curve, pallas};
+    use rand::rngs::OsRng;
+
+    // Hash the messages in circuit and compare with the expected point
+    struct MyCircuit<const L: usize> {
+        messages: Vec<pallas::Base>,
+        expected_point: pallas::Affine,
+    }
 
-    impl Circuit<pallas::Base> for MyCircuit {
+    impl<const L: usize> MyCircuit<L> {
+        fn new(messages: Vec<pallas::Base>) -> Self {
+            let expected_point = poseidon_to_curve::<L>(&messages).to_affine();
+            Self {
+                messages,
+                expected_point,
+            }
+        }
+    }
+
+    impl<const L: usize> Circuit<pallas::Base> for MyCircuit<L> {
         type Config = (
             [Column<Advice>; 10],
             HashToCurveConfig,
@@ -255,7 +238,10 @@ fn test_hash_to_curve_circuit() {
         type FloorPlanner = SimpleFloorPlanner;
 
         fn without_witnesses(&self) -> Self {
-            Self::default()
+            Self {
+                messages: vec![pallas::Base::zero(); self.messages.len()],
+                expected_point: pallas::Affine::default(),
+            }
         }
 
         fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
@@ -310,74 +296,102 @@ fn test_hash_to_curve_circuit() {
         ) -> Result<(), Error> {
             let (advices, hash_to_curve_config, ecc_config) = config;
             let ecc_chip = EccChip::construct(ecc_config);
+            let hash_to_curve_chip = HashToCurveChip::construct(hash_to_curve_config, ecc_chip);
 
-            let messages = [pallas::Base::zero(); POSEIDON_TO_CURVE_INPUT_LEN - 1];
-            let messages_vars = messages
-                .into_iter()
-                .map(|v| {
-                    assign_free_advice(layouter.namespace(|| "u_0"), advices[0], Value::known(v))
-                        .unwrap()
+            let messages_vars = self
+                .messages
+                .iter()
+                .map(|&v| {
+                    assign_free_advice(
+                        layouter.namespace(|| "message"),
+                        advices[0],
+                        Value::known(v),
+                    )
                 })
-                .collect::<Vec<_>>();
-            let ret = hash_to_curve_circuit(
-                layouter.namespace(|| "hash to curve"),
-                hash_to_curve_config,
-                ecc_chip.clone(),
-                &messages_vars,
+                .collect::<Result<Vec<_>, Error>>()?;
+            let ret = hash_to_curve_chip
+                .hash_to_curve::<L>(layouter.namespace(|| "hash to curve"), &messages_vars)?;
+            let expect_ret = Point::new(
+                hash_to_curve_chip.ecc_chip(),
+                layouter.namespace(|| "expect_point"),
+                Value::known(self.expected_point),
             )?;
-            let expect_ret = {
-                let expect_point = poseidon_to_curve::<POSEIDON_TO_CURVE_INPUT_LEN>(&messages);
-                Point::new(
-                    ecc_chip,
-                    layouter.namespace(|| "expect_point"),
-                    Value::known(expect_point.to_affine()),
-                )
-            }?;
             ret.constrain_equal(layouter, &expect_ret)
         }
     }
 
-    let circuit = MyCircuit {};
-
-    let prover = MockProver::run(11, &circuit, vec![]).unwrap();
-    assert_eq!(prover.verify(), Ok(()));
-
-    // TODO: there is still space to improve the performance. keep the test
-    // {
-    //     use halo2_proofs::{
-    //         dev::MockProver,
-    //         plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier},
-    //         poly::commitment::Params,
-    //         transcript::{Blake2bRead, Blake2bWrite},
-    //     };
-    //     use pasta_curves::vesta;
-    //     use rand::rngs::OsRng;
-
-    //     let mut rng = OsRng;
-    //     let params = Params::new(11);
-    //     let empty_circuit = MyCircuit {};
-    //     let vk = keygen_vk(&params, &empty_circuit).expect("keygen_vk should not fail");
-    //     let pk = keygen_pk(&params, vk, &empty_circuit).expect("keygen_pk should not fail");
-    //     let mut transcript = Blake2bWrite::<_, vesta::Affine, _>::init(vec![]);
-    //     use std::time::Instant;
-    //     let start = Instant::now();
-    //     create_proof(
-    //         &params,
-    //         &pk,
-    //         &[empty_circuit],
-    //         &[&[]],
-    //         &mut rng,
-    //         &mut transcript,
-    //     )
-    //     .unwrap();
-    //     let proof = transcript.finalize();
-    //     println!(
-    //         "hash to curve time: {:?}",
-    //         Instant::now().duration_since(start)
-    //     );
-
-    //     let strategy = SingleVerifier::new(&params);
-    //     let mut transcript = Blake2bRead::init(&proof[..]);
-    //     assert!(verify_proof(&params, pk.get_vk(), strategy, &[&[]], &mut transcript).is_ok());
-    // }
+    fn verify<const L: usize>(circuit: &MyCircuit<L>) -> bool {
+        MockProver::run(12, circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn test_hash_to_curve_circuit() {
+        // The resource kind input
+        let circuit = MyCircuit::<POSEIDON_TO_CURVE_INPUT_LEN>::new(vec![
+            pallas::Base::zero();
+            POSEIDON_TO_CURVE_INPUT_LEN
+                - 1
+        ]);
+        assert!(verify(&circuit));
+        let circuit = MyCircuit::<POSEIDON_TO_CURVE_INPUT_LEN>::new(vec![
+            pallas::Base::random(OsRng),
+            pallas::Base::random(OsRng),
+        ]);
+        assert!(verify(&circuit));
+
+        // TODO: there is still space to improve the performance. keep the test
+        // {
+        //     use halo2_proofs::{
+        //         plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier},
+        //         poly::commitment::Params,
+        //         transcript::{Blake2bRead, Blake2bWrite},
+        //     };
+        //     use pasta_curves::vesta;
+
+        //     let mut rng = OsRng;
+        //     let params = Params::new(12);
+        //     let vk = keygen_vk(&params, &circuit).expect("keygen_vk should not fail");
+        //     let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk should not fail");
+        //     let mut transcript = Blake2bWrite::<_, vesta::Affine, _>::init(vec![]);
+        //     use std::time::Instant;
+        //     let start = Instant::now();
+        //     create_proof(&params, &pk, &[circuit], &[&[]], &mut rng, &mut transcript).unwrap();
+        //     let proof = transcript.finalize();
+        //     println!(
+        //         "hash to curve time: {:?}",
+        //         Instant::now().duration_since(start)
+        //     );
+
+        //     let strategy = SingleVerifier::new(&params);
+        //     let mut transcript = Blake2bRead::init(&proof[..]);
+        //     assert!(verify_proof(&params, pk.get_vk(), strategy, &[&[]], &mut transcript).is_ok());
+        // }
+    }
+
+    #[test]
+    fn test_halo2_hash_to_curve_chip_variable_length() {
+        let random_messages = |len: usize| {
+            (0..len)
+                .map(|_| pallas::Base::random(OsRng))
+                .collect::<Vec<_>>()
+        };
+        assert!(verify(&MyCircuit::<2>::new(random_messages(1))));
+        assert!(verify(&MyCircuit::<5>::new(random_messages(4))));
+        assert!(verify(&MyCircuit::<8>::new(random_messages(7))));
+
+        // The point of different messages
+        let mut circuit = MyCircuit::<5>::new(random_messages(4));
+        circuit.messages[3] += pallas::Base::one();
+        assert!(!verify(&circuit));
+
+        // The point of a message with a trailing zero is different
+        let messages = random_messages(3);
+        let mut circuit =
+            MyCircuit::<5>::new([messages.clone(), vec![pallas::Base::zero()]].concat());
+        circuit.expected_point = poseidon_to_curve::<4>(&messages).to_affine();
+        assert!(!verify(&circuit));
+    }
 }
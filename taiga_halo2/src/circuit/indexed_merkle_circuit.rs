@@ -0,0 +1,507 @@
+/// The indexed Merkle tree chip proves membership and non-membership of a value against the root
+/// of an `IndexedMerkleTree`. The values are compared as canonical integers: they are decomposed
+/// to 128-bit halves, and the decompositions are checked to be less than the field modulus.
+use crate::{
+    circuit::{
+        gadgets::{
+            assign_free_advice, assign_free_constant, poseidon_hash::poseidon_hash_gadget,
+            range_check_bits,
+        },
+        merkle_circuit::{merkle_poseidon_gadget, MerklePoseidonChip, MerklePoseidonConfig},
+    },
+    indexed_merkle_tree::IndexedMerkleWitness,
+};
+use ff::PrimeField;
+use halo2_gadgets::{
+    poseidon::Pow5Config as PoseidonConfig,
+    utilities::{bool_check, lookup_range_check::LookupRangeCheckConfig},
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas;
+
+// The high and low 128 bits of the field modulus
+const MODULUS_HI: u128 = 1 << 126;
+const MODULUS_LO: u128 = 0x224698fc094cf91b992d30ed00000001;
+
+type Cell = AssignedCell<pallas::Base, pallas::Base>;
+
+#[derive(Clone, Debug)]
+pub struct IndexedMerkleConfig {
+    advices: [Column<Advice>; 6],
+    // value = hi * 2^128 + lo
+    s_decompose: Selector,
+    // (a_hi, a_lo) < (b_hi, b_lo)
+    s_lt: Selector,
+    merkle_config: MerklePoseidonConfig,
+    poseidon_config: PoseidonConfig<pallas::Base, 3, 2>,
+    lookup_config: LookupRangeCheckConfig<pallas::Base, 10>,
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexedMerkleChip {
+    config: IndexedMerkleConfig,
+}
+
+impl Chip<pallas::Base> for IndexedMerkleChip {
+    type Config = IndexedMerkleConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl IndexedMerkleChip {
+    pub fn construct(config: IndexedMerkleConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<pallas::Base>,
+        advices: [Column<Advice>; 6],
+        merkle_config: MerklePoseidonConfig,
+        poseidon_config: PoseidonConfig<pallas::Base, 3, 2>,
+        lookup_config: LookupRangeCheckConfig<pallas::Base, 10>,
+    ) -> IndexedMerkleConfig {
+        let s_decompose = meta.selector();
+        let s_lt = meta.selector();
+
+        meta.create_gate("indexed merkle decompose", |meta| {
+            let s_decompose = meta.query_selector(s_decompose);
+            let value = meta.query_advice(advices[0], Rotation::cur());
+            let hi = meta.query_advice(advices[1], Rotation::cur());
+            let lo = meta.query_advice(advices[2], Rotation::cur());
+
+            Constraints::with_selector(
+                s_decompose,
+                [(
+                    "value = hi * 2^128 + lo",
+                    hi * (pallas::Base::from_u128(u128::MAX) + pallas::Base::one()) + lo - value,
+                )],
+            )
+        });
+
+        // If a_hi < b_hi, t = b_hi - a_hi - 1. Otherwise a_hi = b_hi and t = b_lo - a_lo - 1.
+        // t is in [0, 2^128) iff the relation holds.
+        meta.create_gate("indexed merkle less than", |meta| {
+            let s_lt = meta.query_selector(s_lt);
+            let a_hi = meta.query_advice(advices[0], Rotation::cur());
+            let a_lo = meta.query_advice(advices[1], Rotation::cur());
+            let b_hi = meta.query_advice(advices[2], Rotation::cur());
+            let b_lo = meta.query_advice(advices[3], Rotation::cur());
+            let hi_lt = meta.query_advice(advices[4], Rotation::cur());
+            let t = meta.query_advice(advices[5], Rotation::cur());
+            let one = Expression::Constant(pallas::Base::one());
+
+            Constraints::with_selector(
+                s_lt,
+                [
+                    ("bool_check hi_lt", bool_check(hi_lt.clone())),
+                    (
+                        "a_hi = b_hi if hi_lt is false",
+                        (one.clone() - hi_lt.clone()) * (a_hi.clone() - b_hi.clone()),
+                    ),
+                    (
+                        "t",
+                        hi_lt.clone() * (b_hi - a_hi - one.clone())
+                            + (one.clone() - hi_lt) * (b_lo - a_lo - one)
+                            - t,
+                    ),
+                ],
+            )
+        });
+
+        IndexedMerkleConfig {
+            advices,
+            s_decompose,
+            s_lt,
+            merkle_config,
+            poseidon_config,
+            lookup_config,
+        }
+    }
+
+    /// Proves the value is in the tree and returns the root.
+    pub fn membership(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        value: &Cell,
+        witness: &IndexedMerkleWitness,
+    ) -> Result<Cell, Error> {
+        let (leaf_value, _, root) = self.leaf_root(layouter.namespace(|| "leaf root"), witness)?;
+        layouter.assign_region(
+            || "leaf value = value",
+            |mut region| region.constrain_equal(leaf_value.cell(), value.cell()),
+        )?;
+        Ok(root)
+    }
+
+    /// Proves the value is not in the tree with its low leaf and returns the root.
+    pub fn non_membership(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        value: &Cell,
+        low_leaf: &IndexedMerkleWitness,
+    ) -> Result<Cell, Error> {
+        let (leaf_value, next_value, root) =
+            self.leaf_root(layouter.namespace(|| "low leaf root"), low_leaf)?;
+        self.less_than(
+            layouter.namespace(|| "leaf value < value"),
+            &leaf_value,
+            value,
+        )?;
+        self.less_than(
+            layouter.namespace(|| "value < next value"),
+            value,
+            &next_value,
+        )?;
+        Ok(root)
+    }
+
+    /// Constrains `a < b` as canonical integers.
+    pub fn less_than(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &Cell,
+        b: &Cell,
+    ) -> Result<(), Error> {
+        let a = self.decompose(layouter.namespace(|| "decompose a"), a)?;
+        let b = self.decompose(layouter.namespace(|| "decompose b"), b)?;
+        self.halves_less_than(layouter.namespace(|| "a < b"), &a, &b)
+    }
+
+    // Returns the leaf value, the next value and the root of the leaf
+    fn leaf_root(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        witness: &IndexedMerkleWitness,
+    ) -> Result<(Cell, Cell, Cell), Error> {
+        let advice = self.config.advices[0];
+        let value = assign_free_advice(
+            layouter.namespace(|| "witness leaf value"),
+            advice,
+            Value::known(witness.leaf.value),
+        )?;
+        let next_index = assign_free_advice(
+            layouter.namespace(|| "witness next index"),
+            advice,
+            Value::known(pallas::Base::from(witness.leaf.next_index)),
+        )?;
+        let next_value = assign_free_advice(
+            layouter.namespace(|| "witness next value"),
+            advice,
+            Value::known(witness.leaf.next_value),
+        )?;
+        let leaf = poseidon_hash_gadget(
+            self.config.poseidon_config.clone(),
+            layouter.namespace(|| "leaf hash"),
+            [value.clone(), next_index, next_value.clone()],
+        )?;
+        let root = merkle_poseidon_gadget(
+            layouter.namespace(|| "merkle root"),
+            MerklePoseidonChip::construct(self.config.merkle_config.clone()),
+            leaf,
+            &witness.merkle_path.inner(),
+        )?;
+        Ok((value, next_value, root))
+    }
+
+    // Decompose the value to the canonical 128-bit halves
+    fn decompose(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        value: &Cell,
+    ) -> Result<(Cell, Cell), Error> {
+        let config = self.config();
+        let (hi, lo) = layouter.assign_region(
+            || "decompose",
+            |mut region: Region<'_, pallas::Base>| {
+                config.s_decompose.enable(&mut region, 0)?;
+                value.copy_advice(|| "value", &mut region, config.advices[0], 0)?;
+                let repr = value.value().map(|v| v.to_repr());
+                let hi = region.assign_advice(
+                    || "hi",
+                    config.advices[1],
+                    0,
+                    || repr.map(|repr| pallas::Base::from_u128(u128_from_le(&repr[16..]))),
+                )?;
+                let lo = region.assign_advice(
+                    || "lo",
+                    config.advices[2],
+                    0,
+                    || repr.map(|repr| pallas::Base::from_u128(u128_from_le(&repr[..16]))),
+                )?;
+                Ok((hi, lo))
+            },
+        )?;
+        range_check_bits(
+            layouter.namespace(|| "hi range check"),
+            &config.lookup_config,
+            hi.clone(),
+            127,
+        )?;
+        range_check_bits(
+            layouter.namespace(|| "lo range check"),
+            &config.lookup_config,
+            lo.clone(),
+            128,
+        )?;
+
+        // The decomposition is less than the modulus, so it's canonical
+        let modulus_hi = assign_free_constant(
+            layouter.namespace(|| "modulus hi"),
+            config.advices[0],
+            pallas::Base::from_u128(MODULUS_HI),
+        )?;
+        let modulus_lo = assign_free_constant(
+            layouter.namespace(|| "modulus lo"),
+            config.advices[0],
+            pallas::Base::from_u128(MODULUS_LO),
+        )?;
+        self.halves_less_than(
+            layouter.namespace(|| "value < modulus"),
+            &(hi.clone(), lo.clone()),
+            &(modulus_hi, modulus_lo),
+        )?;
+        Ok((hi, lo))
+    }
+
+    // Constrain (a_hi, a_lo) < (b_hi, b_lo) for the halves in [0, 2^128)
+    fn halves_less_than(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        a: &(Cell, Cell),
+        b: &(Cell, Cell),
+    ) -> Result<(), Error> {
+        let config = self.config();
+        let t = layouter.assign_region(
+            || "less than",
+            |mut region: Region<'_, pallas::Base>| {
+                config.s_lt.enable(&mut region, 0)?;
+                let a_hi =
+                    a.0.copy_advice(|| "a_hi", &mut region, config.advices[0], 0)?;
+                let a_lo =
+                    a.1.copy_advice(|| "a_lo", &mut region, config.advices[1], 0)?;
+                let b_hi =
+                    b.0.copy_advice(|| "b_hi", &mut region, config.advices[2], 0)?;
+                let b_lo =
+                    b.1.copy_advice(|| "b_lo", &mut region, config.advices[3], 0)?;
+                let hi_lt = a_hi.value().zip(b_hi.value()).map(|(a_hi, b_hi)| {
+                    u128_from_le(&a_hi.to_repr()[..16]) < u128_from_le(&b_hi.to_repr()[..16])
+                });
+                region.assign_advice(
+                    || "hi_lt",
+                    config.advices[4],
+                    0,
+                    || hi_lt.map(|hi_lt| pallas::Base::from(hi_lt as u64)),
+                )?;
+                let t = hi_lt
+                    .zip(a_hi.value().zip(a_lo.value()))
+                    .zip(b_hi.value().zip(b_lo.value()))
+                    .map(|((hi_lt, (a_hi, a_lo)), (b_hi, b_lo))| {
+                        if hi_lt {
+                            b_hi - a_hi - pallas::Base::one()
+                        } else {
+                            b_lo - a_lo - pallas::Base::one()
+                        }
+                    });
+                region.assign_advice(|| "t", config.advices[5], 0, || t)
+            },
+        )?;
+        range_check_bits(
+            layouter.namespace(|| "t range check"),
+            &config.lookup_config,
+            t,
+            128,
+        )
+    }
+}
+
+fn u128_from_le(bytes: &[u8]) -> u128 {
+    u128::from_le_bytes(bytes.try_into().unwrap())
+}
+
+#[test]
+fn test_halo2_indexed_merkle_chip() {
+    use crate::indexed_merkle_tree::IndexedMerkleTree;
+    use halo2_gadgets::poseidon::{primitives as poseidon, Pow5Chip as PoseidonChip};
+    use halo2_proofs::{
+        arithmetic::Field,
+        circuit::floor_planner,
+        dev::MockProver,
+        plonk::{Circuit, TableColumn},
+    };
+    use rand::rngs::OsRng;
+
+    #[derive(Default)]
+    struct MyCircuit {
+        value: pallas::Base,
+        witness: Option<IndexedMerkleWitness>,
+        is_member: bool,
+        root: pallas::Base,
+    }
+
+    #[derive(Clone, Debug)]
+    struct MyConfig {
+        table_idx: TableColumn,
+        indexed_merkle_config: IndexedMerkleConfig,
+    }
+
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = MyConfig;
+        type FloorPlanner = floor_planner::V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            let advices: [Column<Advice>; 10] = (0..10)
+                .map(|_| meta.advice_column())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+            for advice in advices.iter() {
+                meta.enable_equality(*advice);
+            }
+            let table_idx = meta.lookup_table_column();
+            let lookup_config = LookupRangeCheckConfig::configure(meta, advices[9], table_idx);
+
+            let rc_a = (0..3).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+            let rc_b = (0..3).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+            meta.enable_constant(rc_b[0]);
+            let poseidon_config = PoseidonChip::configure::<poseidon::P128Pow5T3>(
+                meta,
+                advices[6..9].try_into().unwrap(),
+                advices[5],
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            );
+            let merkle_config = MerklePoseidonChip::configure(
+                meta,
+                advices[..5].try_into().unwrap(),
+                poseidon_config.clone(),
+            );
+            let indexed_merkle_config = IndexedMerkleChip::configure(
+                meta,
+                advices[..6].try_into().unwrap(),
+                merkle_config,
+                poseidon_config,
+                lookup_config,
+            );
+            MyConfig {
+                table_idx,
+                indexed_merkle_config,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            layouter.assign_table(
+                || "table_idx",
+                |mut table| {
+                    for index in 0..(1 << 10) {
+                        table.assign_cell(
+                            || "table_idx",
+                            config.table_idx,
+                            index,
+                            || Value::known(pallas::Base::from(index as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+            let advice = config.indexed_merkle_config.advices[0];
+            let chip = IndexedMerkleChip::construct(config.indexed_merkle_config);
+            let value = assign_free_advice(
+                layouter.namespace(|| "witness value"),
+                advice,
+                Value::known(self.value),
+            )?;
+            let witness = self.witness.clone().unwrap_or(IndexedMerkleWitness {
+                leaf: Default::default(),
+                merkle_path: crate::merkle_tree::MerklePath::random(&mut OsRng, 8),
+            });
+            let root = if self.is_member {
+                chip.membership(layouter.namespace(|| "membership"), &value, &witness)?
+            } else {
+                chip.non_membership(layouter.namespace(|| "non-membership"), &value, &witness)?
+            };
+            let expected_root = assign_free_advice(
+                layouter.namespace(|| "witness root"),
+                advice,
+                Value::known(self.root),
+            )?;
+            layouter.assign_region(
+                || "check root",
+                |mut region| region.constrain_equal(root.cell(), expected_root.cell()),
+            )
+        }
+    }
+
+    let verify = |circuit: MyCircuit| {
+        MockProver::run(13, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+
+    let mut tree = IndexedMerkleTree::new(8);
+    let values: Vec<pallas::Base> = (0..5).map(|_| pallas::Base::random(OsRng)).collect();
+    for value in values.iter() {
+        tree.insert(*value);
+    }
+    let root = tree.root().inner();
+
+    // Membership
+    assert!(verify(MyCircuit {
+        value: values[0],
+        witness: tree.membership_witness(&values[0]),
+        is_member: true,
+        root,
+    }));
+
+    // Non-membership
+    let absent = pallas::Base::random(OsRng);
+    let low_leaf = tree.non_membership_witness(&absent);
+    assert!(verify(MyCircuit {
+        value: absent,
+        witness: low_leaf.clone(),
+        is_member: false,
+        root,
+    }));
+    // The largest non-sentinel value
+    let max = -pallas::Base::from(2);
+    assert!(verify(MyCircuit {
+        value: max,
+        witness: tree.non_membership_witness(&max),
+        is_member: false,
+        root,
+    }));
+
+    // A member has no low leaf, and the leaf of another value doesn't work
+    let member_leaf = tree.membership_witness(&values[0]);
+    assert!(!verify(MyCircuit {
+        value: values[0],
+        witness: member_leaf,
+        is_member: false,
+        root,
+    }));
+    assert!(!verify(MyCircuit {
+        value: values[0],
+        witness: low_leaf,
+        is_member: false,
+        root,
+    }));
+}
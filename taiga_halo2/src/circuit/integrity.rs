@@ -3,7 +3,7 @@ use crate::circuit::{
         assign_free_advice, assign_free_constant, conditional_select::ConditionalSelectConfig,
         poseidon_hash::poseidon_hash_gadget,
     },
-    hash_to_curve::{hash_to_curve_circuit, HashToCurveConfig},
+    hash_to_curve::{HashToCurveChip, HashToCurveInstructions},
     merkle_circuit::{merkle_poseidon_gadget, MerklePoseidonChip},
     resource_commitment::{resource_commit, ResourceCommitChip},
     resource_logic_circuit::{InputResourceVariables, ResourceStatus, ResourceVariables},
@@ -180,6 +180,8 @@ pub fn check_input_resource(
         nonce,
         npk,
         rseed,
+        psi,
+        rcm,
     };
 
     Ok(InputResourceVariables {
@@ -321,6 +323,8 @@ pub fn check_output_resource(
         nonce: old_nf,
         npk,
         rseed,
+        psi,
+        rcm,
     })
 }
 
@@ -501,6 +505,8 @@ pub fn load_resource(
         nonce,
         npk,
         rseed,
+        psi,
+        rcm,
     };
 
     Ok(ResourceStatus {
@@ -513,15 +519,12 @@ pub fn load_resource(
 
 pub fn derive_kind(
     mut layouter: impl Layouter<pallas::Base>,
-    hash_to_curve_config: HashToCurveConfig,
-    ecc_chip: EccChip<TaigaFixedBases>,
+    hash_to_curve_chip: HashToCurveChip,
     logic: AssignedCell<pallas::Base, pallas::Base>,
     label: AssignedCell<pallas::Base, pallas::Base>,
 ) -> Result<NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>, Error> {
-    let point = hash_to_curve_circuit(
+    let point = hash_to_curve_chip.hash_to_curve::<POSEIDON_TO_CURVE_INPUT_LEN>(
         layouter.namespace(|| "hash to curve"),
-        hash_to_curve_config,
-        ecc_chip.clone(),
         &[logic.clone(), label.clone()],
     )?;
 
@@ -531,7 +534,7 @@ pub fn derive_kind(
         poseidon_to_curve::<POSEIDON_TO_CURVE_INPUT_LEN>(&[vk, data]).to_affine()
     });
     let non_identity_point_var = NonIdentityPoint::new(
-        ecc_chip,
+        hash_to_curve_chip.ecc_chip(),
         layouter.namespace(|| "non-identity resource kind"),
         non_identity_point,
     )?;
@@ -546,7 +549,7 @@ pub fn derive_kind(
 pub fn compute_delta_commitment(
     mut layouter: impl Layouter<pallas::Base>,
     ecc_chip: EccChip<TaigaFixedBases>,
-    hash_to_curve_config: HashToCurveConfig,
+    hash_to_curve_chip: HashToCurveChip,
     input_logic: AssignedCell<pallas::Base, pallas::Base>,
     input_label: AssignedCell<pallas::Base, pallas::Base>,
     input_quantity: AssignedCell<pallas::Base, pallas::Base>,
@@ -558,8 +561,7 @@ pub fn compute_delta_commitment(
     // input value base point
     let input_kind = derive_kind(
         layouter.namespace(|| "derive input resource kind"),
-        hash_to_curve_config.clone(),
-        ecc_chip.clone(),
+        hash_to_curve_chip.clone(),
         input_logic,
         input_label,
     )?;
@@ -574,8 +576,7 @@ pub fn compute_delta_commitment(
     // output value base point
     let output_kind = derive_kind(
         layouter.namespace(|| "derive output resource kind"),
-        hash_to_curve_config,
-        ecc_chip.clone(),
+        hash_to_curve_chip,
         output_logic,
         output_label,
     )?;
@@ -5,10 +5,13 @@ pub mod merkle_circuit;
 #[macro_use]
 pub mod resource_logic_circuit;
 pub mod blake2s;
+pub mod cost;
 pub mod curve;
 pub mod hash_to_curve;
+pub mod indexed_merkle_circuit;
 pub mod resource_commitment;
 pub mod resource_encryption_circuit;
 pub mod resource_logic_bytecode;
 pub mod resource_logic_examples;
+pub mod sha256;
 mod vamp_ir_utils;
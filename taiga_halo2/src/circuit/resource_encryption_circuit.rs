@@ -4,8 +4,9 @@ use crate::circuit::gadgets::{
 };
 use crate::constant::{
     BaseFieldGenerators, TaigaFixedBases, POSEIDON_RATE, POSEIDON_WIDTH,
-    RESOURCE_ENCRYPTION_PLAINTEXT_NUM,
-    RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_BEGIN_IDX,
+    RESOURCE_ENCRYPTION_PLAINTEXT_NUM, RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX,
+    RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_X_IDX,
+    RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_Y_IDX,
 };
 use ff::PrimeField;
 use halo2_gadgets::{
@@ -23,6 +24,9 @@ use halo2_proofs::{
 };
 use pasta_curves::pallas;
 
+// The message is padded to the default plaintext length, and longer messages are allowed.
+// The ciphertext of a message with n elements takes n + 2 public inputs from
+// `RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX`, see `resource_logic_public_input_num`.
 #[allow(clippy::too_many_arguments)]
 pub fn resource_encryption_gadget(
     mut layouter: impl Layouter<pallas::Base>,
@@ -42,8 +46,8 @@ pub fn resource_encryption_gadget(
         advice,
         Value::known(pallas::Base::zero()),
     )?;
-    let paddings =
-        std::iter::repeat(padding_zero).take(RESOURCE_ENCRYPTION_PLAINTEXT_NUM - message.len());
+    let paddings = std::iter::repeat(padding_zero)
+        .take(RESOURCE_ENCRYPTION_PLAINTEXT_NUM.saturating_sub(message.len()));
     message.extend(paddings);
 
     // Compute symmetric secret key
@@ -122,16 +126,24 @@ pub fn resource_encryption_gadget(
     >>::permute(&poseidon_chip, &mut layouter, &state)?;
     cipher.push(state[0].clone().into());
 
-    // Add sender's pk
-    cipher.push(sender_pk.inner().x());
-    cipher.push(sender_pk.inner().y());
+    // Publicize the sender's pk
+    layouter.constrain_instance(
+        sender_pk.inner().x().cell(),
+        instances,
+        RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_X_IDX,
+    )?;
+    layouter.constrain_instance(
+        sender_pk.inner().y().cell(),
+        instances,
+        RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_Y_IDX,
+    )?;
 
     // Publicize the cipher
     for (i, ele) in cipher.iter().enumerate() {
         layouter.constrain_instance(
             ele.cell(),
             instances,
-            RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_BEGIN_IDX + i,
+            RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX + i,
         )?;
     }
 
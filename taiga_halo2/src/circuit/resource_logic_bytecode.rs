@@ -2,14 +2,22 @@
 use crate::circuit::resource_logic_examples::TrivialResourceLogicCircuit;
 #[cfg(feature = "examples")]
 use crate::circuit::resource_logic_examples::{
+    allowlist::AllowlistResourceLogicCircuit,
+    htlc::HtlcResourceLogicCircuit,
+    limit_order_intent::LimitOrderIntentResourceLogicCircuit,
+    multisig_verification::MultisigVerificationResourceLogicCircuit,
+    nft::NftResourceLogicCircuit,
     or_relation_intent::OrRelationIntentResourceLogicCircuit,
     partial_fulfillment_intent::PartialFulfillmentIntentResourceLogicCircuit,
     receiver_resource_logic::ReceiverResourceLogicCircuit,
     signature_verification::SignatureVerificationResourceLogicCircuit,
-    token::TokenResourceLogicCircuit,
+    state_transition::counter::CounterResourceLogicCircuit,
+    sudoku::{dealer_intent::DealerIntentResourceLogicCircuit, SudokuResourceLogicCircuit},
+    token::{issuance::IssuedTokenResourceLogicCircuit, TokenResourceLogicCircuit},
 };
 use crate::error::TransactionError;
 use crate::shielded_ptx::ResourceLogicVerifyingInfoSet;
+use crate::transaction::ValidityWindow;
 use crate::{
     circuit::resource_logic_circuit::{
         ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, VampIRResourceLogicCircuit,
@@ -42,6 +50,15 @@ pub enum ResourceLogicRepresentation {
     PartialFulfillmentIntent,
     OrRelationIntent,
     CascadeIntent,
+    IssuedToken,
+    MultisigVerification,
+    Htlc,
+    Nft,
+    LimitOrderIntent,
+    Sudoku,
+    SudokuDealerIntent,
+    Counter,
+    Allowlist,
     // Add other native resource_logic types here if needed
 }
 
@@ -91,6 +108,52 @@ impl ResourceLogicByteCode {
                 Ok(resource_logic.get_verifying_info())
             }
             #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::IssuedToken => {
+                let resource_logic = IssuedTokenResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::MultisigVerification => {
+                let resource_logic =
+                    MultisigVerificationResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Htlc => {
+                let resource_logic = HtlcResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Nft => {
+                let resource_logic = NftResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::LimitOrderIntent => {
+                let resource_logic = LimitOrderIntentResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Sudoku => {
+                let resource_logic = SudokuResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::SudokuDealerIntent => {
+                let resource_logic = DealerIntentResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Counter => {
+                let resource_logic = CounterResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Allowlist => {
+                let resource_logic = AllowlistResourceLogicCircuit::from_bytes(&self.inputs);
+                Ok(resource_logic.get_verifying_info())
+            }
+            #[cfg(feature = "examples")]
             ResourceLogicRepresentation::SignatureVerification => {
                 let resource_logic =
                     SignatureVerificationResourceLogicCircuit::from_bytes(&self.inputs);
@@ -121,6 +184,7 @@ impl ResourceLogicByteCode {
     pub fn verify_transparently(
         &self,
         compliance_resource_merkle_root: &pallas::Base,
+        validity_window: &ValidityWindow,
     ) -> Result<pallas::Base, TransactionError> {
         // check resource logic transparently
         let public_inputs = match &self.circuit {
@@ -147,6 +211,52 @@ impl ResourceLogicByteCode {
                 resource_logic.verify_transparently()?
             }
             #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::IssuedToken => {
+                let resource_logic = IssuedTokenResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::MultisigVerification => {
+                let resource_logic =
+                    MultisigVerificationResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Htlc => {
+                let resource_logic = HtlcResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Nft => {
+                let resource_logic = NftResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::LimitOrderIntent => {
+                let resource_logic = LimitOrderIntentResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Sudoku => {
+                let resource_logic = SudokuResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::SudokuDealerIntent => {
+                let resource_logic = DealerIntentResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Counter => {
+                let resource_logic = CounterResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
+            ResourceLogicRepresentation::Allowlist => {
+                let resource_logic = AllowlistResourceLogicCircuit::from_bytes(&self.inputs);
+                resource_logic.verify_transparently()?
+            }
+            #[cfg(feature = "examples")]
             ResourceLogicRepresentation::SignatureVerification => {
                 let resource_logic =
                     SignatureVerificationResourceLogicCircuit::from_bytes(&self.inputs);
@@ -180,6 +290,12 @@ impl ResourceLogicByteCode {
             return Err(TransactionError::InconsistentResourceMerkleRoot);
         }
 
+        // check the resource logic is proved against a window that contains the transaction window
+        match public_inputs.get_validity_window() {
+            Some(logic_window) if logic_window.contains(validity_window) => {}
+            _ => return Err(TransactionError::InvalidValidityWindow),
+        }
+
         Ok(public_inputs.get_from_index(RESOURCE_LOGIC_CIRCUIT_SELF_RESOURCE_ID_IDX))
     }
 }
@@ -214,12 +330,14 @@ impl ApplicationByteCode {
     pub fn verify_transparently(
         &self,
         compliance_root: &pallas::Base,
+        validity_window: &ValidityWindow,
     ) -> Result<pallas::Base, TransactionError> {
         let self_resource_id = self
             .app_resource_logic_bytecode
-            .verify_transparently(compliance_root)?;
+            .verify_transparently(compliance_root, validity_window)?;
         for dynamic_resource_logic in self.dynamic_resource_logic_bytecode.iter() {
-            let id = dynamic_resource_logic.verify_transparently(compliance_root)?;
+            let id =
+                dynamic_resource_logic.verify_transparently(compliance_root, validity_window)?;
             // check: the app_resource_logic and dynamic_resource_logics belong to the resource
             if id != self_resource_id {
                 return Err(TransactionError::InconsistentSelfResourceID);
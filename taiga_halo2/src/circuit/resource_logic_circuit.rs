@@ -4,20 +4,27 @@ use crate::{
         blake2s::Blake2sConfig,
         gadgets::{
             add::{AddChip, AddConfig},
+            boolean::{BooleanChip, BooleanConfig},
+            comparison::{U64ComparisonChip, U64ComparisonConfig},
             conditional_equal::ConditionalEqualConfig,
             conditional_select::ConditionalSelectConfig,
+            div_mod::{DivModChip, DivModConfig},
             extended_or_relation::ExtendedOrRelationConfig,
             mul::{MulChip, MulConfig},
             sub::{SubChip, SubConfig},
         },
+        indexed_merkle_circuit::{IndexedMerkleChip, IndexedMerkleConfig},
         integrity::load_resource,
         merkle_circuit::{MerklePoseidonChip, MerklePoseidonConfig},
         resource_commitment::{ResourceCommitChip, ResourceCommitConfig},
+        sha256::{Sha256Chip, Sha256Config},
         vamp_ir_utils::{get_circuit_assignments, parse, VariableAssignmentError},
     },
     constant::{
-        TaigaFixedBases, RESOURCE_ENCRYPTION_CIPHERTEXT_NUM, RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        TaigaFixedBases, RESOURCE_LOGIC_CIRCUIT_EXPIRES_AT_IDX,
+        RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX, RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
         RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM,
+        RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX,
         RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_X_IDX,
         RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_Y_IDX,
         RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_BEGIN_IDX,
@@ -25,11 +32,13 @@ use crate::{
         RESOURCE_LOGIC_CIRCUIT_SELF_RESOURCE_ID_IDX, SETUP_PARAMS_MAP,
     },
     error::TransactionError,
+    keys::OutgoingViewingKey,
     proof::Proof,
-    resource::RandomSeed,
-    resource_encryption::{ResourceCiphertext, SecretKey},
+    resource::{RandomSeed, ResourceCommitment},
+    resource_encryption::{OutgoingCiphertext, ResourceCiphertext, SecretKey},
     resource_logic_vk::ResourceLogicVerifyingKey,
     resource_tree::ResourceExistenceWitness,
+    transaction::ValidityWindow,
     utils::mod_r_p,
 };
 use dyn_clone::{clone_trait_object, DynClone};
@@ -132,14 +141,16 @@ impl<'a> Decoder<'a> for ResourceLogicVerifyingInfo {
     }
 }
 
+// The public inputs contain at least the default layout. The resource logics that encrypt
+// longer messages have more public inputs for the ciphertext.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct ResourceLogicPublicInputs([pallas::Base; RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM]);
+pub struct ResourceLogicPublicInputs(Vec<pallas::Base>);
 
 #[cfg(feature = "nif")]
 impl Encoder for ResourceLogicPublicInputs {
     fn encode<'a>(&self, env: Env<'a>) -> Term<'a> {
-        self.0.to_vec().encode(env)
+        self.0.encode(env)
     }
 }
 
@@ -174,12 +185,14 @@ impl ResourceLogicVerifyingInfo {
 #[cfg(feature = "borsh")]
 impl BorshSerialize for ResourceLogicVerifyingInfo {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        use byteorder::{LittleEndian, WriteBytesExt};
         use ff::PrimeField;
         // Write vk
         self.vk.write(writer)?;
         // Write proof
         self.proof.serialize(writer)?;
         // Write public inputs
+        writer.write_u32::<LittleEndian>(self.public_inputs.inner().len() as u32)?;
         for ele in self.public_inputs.inner().iter() {
             writer.write_all(&ele.to_repr())?;
         }
@@ -193,6 +206,7 @@ impl BorshDeserialize for ResourceLogicVerifyingInfo {
         // Read vk
         use crate::circuit::resource_logic_examples::TrivialResourceLogicCircuit;
         use crate::utils::read_base_field;
+        use byteorder::{LittleEndian, ReadBytesExt};
         let params = SETUP_PARAMS_MAP
             .get(&RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE)
             .unwrap();
@@ -200,7 +214,14 @@ impl BorshDeserialize for ResourceLogicVerifyingInfo {
         // Read proof
         let proof = Proof::deserialize_reader(reader)?;
         // Read public inputs
-        let public_inputs: Vec<_> = (0..RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM)
+        let public_inputs_len = reader.read_u32::<LittleEndian>()?;
+        if (public_inputs_len as usize) < RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "not enough resource logic public inputs",
+            ));
+        }
+        let public_inputs: Vec<_> = (0..public_inputs_len)
             .map(|_| read_base_field(reader))
             .collect::<Result<_, _>>()?;
         Ok(ResourceLogicVerifyingInfo {
@@ -242,12 +263,12 @@ where
 }
 
 impl ResourceLogicPublicInputs {
-    pub fn inner(&self) -> &[pallas::Base; RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM] {
+    pub fn inner(&self) -> &[pallas::Base] {
         &self.0
     }
 
     pub fn get_from_index(&self, idx: usize) -> pallas::Base {
-        assert!(idx < RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM);
+        assert!(idx < self.0.len());
         self.0[idx]
     }
 
@@ -268,34 +289,73 @@ impl ResourceLogicPublicInputs {
     }
 
     pub fn to_vec(&self) -> Vec<pallas::Base> {
-        self.0.to_vec()
+        self.0.clone()
     }
 
     pub fn decrypt(&self, sk: pallas::Base) -> Option<Vec<pallas::Base>> {
-        let cipher: ResourceCiphertext = self.0
-            [RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_BEGIN_IDX
-                ..RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_BEGIN_IDX
-                    + RESOURCE_ENCRYPTION_CIPHERTEXT_NUM]
+        let sender_pk = self.get_sender_pk()?;
+        let key = SecretKey::from_dh_exchange(&sender_pk, &mod_r_p(sk));
+        self.get_resource_ciphertext().decrypt(&key)
+    }
+
+    /// Decrypt the resource with the sender's outgoing viewing key and the outgoing ciphertext.
+    pub fn decrypt_outgoing(
+        &self,
+        ovk: &OutgoingViewingKey,
+        out_cipher: &OutgoingCiphertext,
+    ) -> Option<Vec<pallas::Base>> {
+        let sender_pk = self.get_sender_pk()?;
+        let cm = ResourceCommitment::from(
+            self.get_from_index(RESOURCE_LOGIC_CIRCUIT_SELF_RESOURCE_ID_IDX),
+        );
+        let (rcv_pk, sk) = out_cipher.decrypt(ovk, &cm, &sender_pk)?;
+        let key = SecretKey::from_dh_exchange(&rcv_pk, &mod_r_p(sk));
+        self.get_resource_ciphertext().decrypt(&key)
+    }
+
+    /// Get the transaction validity window the resource logic is proved against.
+    /// Returns None if the bounds are not u64 heights.
+    pub fn get_validity_window(&self) -> Option<ValidityWindow> {
+        let not_before = field_to_u64(&self.get_from_index(RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX))?;
+        let expires_at = field_to_u64(&self.get_from_index(RESOURCE_LOGIC_CIRCUIT_EXPIRES_AT_IDX))?;
+        Some(ValidityWindow::new(not_before, expires_at))
+    }
+
+    // The ciphertext takes the rest of the public inputs.
+    pub fn get_resource_ciphertext(&self) -> ResourceCiphertext {
+        self.0[RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX..]
             .to_vec()
-            .into();
-        let sender_pk = pallas::Affine::from_xy(
+            .into()
+    }
+
+    // The public inputs of resource logics without encryption are random padding,
+    // which may not be a valid point.
+    fn get_sender_pk(&self) -> Option<pallas::Point> {
+        let sender_pk: Option<pallas::Affine> = pallas::Affine::from_xy(
             self.get_from_index(RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_X_IDX),
             self.get_from_index(RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_Y_IDX),
         )
-        .unwrap()
-        .to_curve();
-        let key = SecretKey::from_dh_exchange(&sender_pk, &mod_r_p(sk));
-        cipher.decrypt(&key)
+        .into();
+        Some(sender_pk?.to_curve())
     }
 }
 
+fn field_to_u64(value: &pallas::Base) -> Option<u64> {
+    use ff::PrimeField;
+    let repr = value.to_repr();
+    if repr[8..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(u64::from_le_bytes(repr[..8].try_into().unwrap()))
+}
+
 impl From<Vec<pallas::Base>> for ResourceLogicPublicInputs {
     fn from(public_input_vec: Vec<pallas::Base>) -> Self {
-        ResourceLogicPublicInputs(
-            public_input_vec
-                .try_into()
-                .expect("public input with incorrect length"),
-        )
+        assert!(
+            public_input_vec.len() >= RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM,
+            "public input with incorrect length"
+        );
+        ResourceLogicPublicInputs(public_input_vec)
     }
 }
 
@@ -307,13 +367,18 @@ pub struct ResourceLogicConfig {
     pub ecc_config: EccConfig<TaigaFixedBases>,
     pub poseidon_config: PoseidonConfig<pallas::Base, 3, 2>,
     pub merkle_config: MerklePoseidonConfig,
+    pub indexed_merkle_config: IndexedMerkleConfig,
     pub conditional_equal_config: ConditionalEqualConfig,
     pub conditional_select_config: ConditionalSelectConfig,
     pub extended_or_relation_config: ExtendedOrRelationConfig,
     pub add_config: AddConfig,
     pub sub_config: SubConfig,
     pub mul_config: MulConfig,
+    pub comparison_config: U64ComparisonConfig,
+    pub div_mod_config: DivModConfig,
+    pub boolean_config: BooleanConfig,
     pub blake2s_config: Blake2sConfig<pallas::Base>,
+    pub sha256_config: Sha256Config,
     pub resource_commit_config: ResourceCommitConfig,
 }
 
@@ -374,10 +439,15 @@ impl ResourceLogicConfig {
         let add_config = AddChip::configure(meta, [advices[0], advices[1]]);
         let sub_config = SubChip::configure(meta, [advices[0], advices[1]]);
         let mul_config = MulChip::configure(meta, [advices[0], advices[1]]);
+        let comparison_config =
+            U64ComparisonChip::configure(meta, [advices[0], advices[1]], range_check);
+        let div_mod_config = DivModChip::configure(meta, [advices[0], advices[1]]);
+        let boolean_config = BooleanChip::configure(meta, advices[..4].try_into().unwrap());
 
         let extended_or_relation_config =
             ExtendedOrRelationConfig::configure(meta, [advices[0], advices[1], advices[2]]);
         let blake2s_config = Blake2sConfig::configure(meta, advices);
+        let sha256_config = Sha256Chip::configure(meta, advices);
         let resource_commit_config = ResourceCommitChip::configure(
             meta,
             advices[0..3].try_into().unwrap(),
@@ -390,6 +460,13 @@ impl ResourceLogicConfig {
             advices[..5].try_into().unwrap(),
             poseidon_config.clone(),
         );
+        let indexed_merkle_config = IndexedMerkleChip::configure(
+            meta,
+            advices[..6].try_into().unwrap(),
+            merkle_config.clone(),
+            poseidon_config.clone(),
+            range_check,
+        );
 
         Self {
             advices,
@@ -398,13 +475,18 @@ impl ResourceLogicConfig {
             ecc_config,
             poseidon_config,
             merkle_config,
+            indexed_merkle_config,
             conditional_equal_config,
             conditional_select_config,
             extended_or_relation_config,
             add_config,
             sub_config,
             mul_config,
+            comparison_config,
+            div_mod_config,
+            boolean_config,
             blake2s_config,
+            sha256_config,
             resource_commit_config,
         }
     }
@@ -492,6 +574,12 @@ pub trait ResourceLogicCircuit: Circuit<pallas::Base> + ResourceLogicVerifyingIn
         Ok(())
     }
 
+    // The transaction validity window is a free public input. The resource logics that care about
+    // it constrain it with the validity window gadget, and the default window is unbounded.
+    fn get_validity_window(&self) -> ValidityWindow {
+        ValidityWindow::default()
+    }
+
     fn get_mandatory_public_inputs(&self) -> Vec<pallas::Base> {
         let resource_witness = self.get_self_resource();
         let root = resource_witness.get_root();
@@ -499,6 +587,15 @@ pub trait ResourceLogicCircuit: Circuit<pallas::Base> + ResourceLogicVerifyingIn
         vec![root, id]
     }
 
+    // The window public inputs follow the dynamic resource logic commitments.
+    fn get_validity_window_public_inputs(&self) -> [pallas::Base; 2] {
+        let window = self.get_validity_window();
+        [
+            pallas::Base::from(window.not_before),
+            pallas::Base::from(window.expires_at),
+        ]
+    }
+
     fn get_public_inputs(&self, rng: impl RngCore) -> ResourceLogicPublicInputs;
 
     fn get_self_resource(&self) -> ResourceExistenceWitness;
@@ -522,6 +619,8 @@ pub struct ResourceVariables {
     pub nonce: AssignedCell<pallas::Base, pallas::Base>,
     pub npk: AssignedCell<pallas::Base, pallas::Base>,
     pub rseed: AssignedCell<pallas::Base, pallas::Base>,
+    pub psi: AssignedCell<pallas::Base, pallas::Base>,
+    pub rcm: AssignedCell<pallas::Base, pallas::Base>,
 }
 
 // Variables in the input resource
@@ -712,6 +811,28 @@ impl VampIRResourceLogicCircuit {
     }
 }
 
+impl VampIRResourceLogicCircuit {
+    // The vamp-ir logics that don't declare the validity window public inputs are proved against
+    // the unbounded window. The logics that declare more public inputs constrain the window
+    // themselves.
+    fn get_padded_public_inputs(&self, mut rng: impl RngCore) -> Vec<pallas::Base> {
+        let mut public_inputs = self.public_inputs.clone();
+        let rseed = RandomSeed::random(&mut rng);
+        public_inputs.extend(ResourceLogicPublicInputs::get_public_input_padding(
+            self.public_inputs.len(),
+            &rseed,
+        ));
+        if self.public_inputs.len() <= RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX {
+            let window = ValidityWindow::default();
+            public_inputs[RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX] =
+                pallas::Base::from(window.not_before);
+            public_inputs[RESOURCE_LOGIC_CIRCUIT_EXPIRES_AT_IDX] =
+                pallas::Base::from(window.expires_at);
+        }
+        public_inputs
+    }
+}
+
 impl ResourceLogicVerifyingInfoTrait for VampIRResourceLogicCircuit {
     fn get_verifying_info(&self) -> ResourceLogicVerifyingInfo {
         let mut rng = OsRng;
@@ -719,12 +840,7 @@ impl ResourceLogicVerifyingInfoTrait for VampIRResourceLogicCircuit {
         let pk =
             keygen_pk(&self.params, vk.clone(), &self.circuit).expect("keygen_pk should not fail");
 
-        let mut public_inputs = self.public_inputs.clone();
-        let rseed = RandomSeed::random(&mut rng);
-        public_inputs.extend(ResourceLogicPublicInputs::get_public_input_padding(
-            self.public_inputs.len(),
-            &rseed,
-        ));
+        let public_inputs = self.get_padded_public_inputs(&mut rng);
 
         let proof = Proof::create(
             &pk,
@@ -744,12 +860,7 @@ impl ResourceLogicVerifyingInfoTrait for VampIRResourceLogicCircuit {
     fn verify_transparently(&self) -> Result<ResourceLogicPublicInputs, TransactionError> {
         use halo2_proofs::dev::MockProver;
         let mut rng = OsRng;
-        let mut public_inputs = self.public_inputs.clone();
-        let rseed = RandomSeed::random(&mut rng);
-        public_inputs.extend(ResourceLogicPublicInputs::get_public_input_padding(
-            self.public_inputs.len(),
-            &rseed,
-        ));
+        let public_inputs = self.get_padded_public_inputs(&mut rng);
         let prover =
             MockProver::<pallas::Base>::run(15, &self.circuit, vec![public_inputs.to_vec()])
                 .unwrap();
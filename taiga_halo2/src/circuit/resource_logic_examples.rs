@@ -25,9 +25,19 @@ use lazy_static::lazy_static;
 use pasta_curves::{pallas, vesta};
 use rand::{rngs::OsRng, RngCore};
 
+#[cfg(feature = "examples")]
+pub mod allowlist;
 #[cfg(feature = "examples")]
 mod field_addition;
 #[cfg(feature = "examples")]
+pub mod htlc;
+#[cfg(feature = "examples")]
+pub mod limit_order_intent;
+#[cfg(feature = "examples")]
+pub mod multisig_verification;
+#[cfg(feature = "examples")]
+pub mod nft;
+#[cfg(feature = "examples")]
 pub mod or_relation_intent;
 #[cfg(feature = "examples")]
 pub mod partial_fulfillment_intent;
@@ -36,6 +46,10 @@ pub mod receiver_resource_logic;
 #[cfg(feature = "examples")]
 pub mod signature_verification;
 #[cfg(feature = "examples")]
+pub mod state_transition;
+#[cfg(feature = "examples")]
+pub mod sudoku;
+#[cfg(feature = "examples")]
 pub mod token;
 
 lazy_static! {
@@ -98,6 +112,7 @@ impl ResourceLogicCircuit for TrivialResourceLogicCircuit {
             ResourceLogicCommitment::default().to_public_inputs();
         public_inputs.extend(default_resource_logic_cm);
         public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         let padding = ResourceLogicPublicInputs::get_public_input_padding(
             public_inputs.len(),
             &RandomSeed::random(&mut rng),
@@ -172,4 +187,50 @@ pub mod tests {
         .unwrap();
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[cfg(feature = "examples")]
+    #[test]
+    fn test_resource_logic_circuit_costs() {
+        use super::{
+            allowlist::AllowlistResourceLogicCircuit,
+            htlc::HtlcResourceLogicCircuit,
+            limit_order_intent::LimitOrderIntentResourceLogicCircuit,
+            multisig_verification::MultisigVerificationResourceLogicCircuit,
+            nft::NftResourceLogicCircuit,
+            or_relation_intent::OrRelationIntentResourceLogicCircuit,
+            partial_fulfillment_intent::PartialFulfillmentIntentResourceLogicCircuit,
+            receiver_resource_logic::ReceiverResourceLogicCircuit,
+            signature_verification::SignatureVerificationResourceLogicCircuit,
+            state_transition::counter::CounterResourceLogicCircuit,
+            sudoku::{dealer_intent::DealerIntentResourceLogicCircuit, SudokuResourceLogicCircuit},
+            token::{issuance::IssuedTokenResourceLogicCircuit, TokenResourceLogicCircuit},
+        };
+        use crate::circuit::cost::CircuitCostReport;
+        use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+        use halo2_proofs::plonk::Circuit;
+
+        // All the resource logics are verified with the same params size
+        fn check<C: Circuit<pallas::Base> + Default>(name: &str) {
+            let report =
+                CircuitCostReport::measure(RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE, &C::default())
+                    .unwrap();
+            assert!(report.fits(), "{} doesn't fit:\n{}", name, report);
+        }
+
+        check::<TrivialResourceLogicCircuit>("trivial");
+        check::<TokenResourceLogicCircuit>("token");
+        check::<IssuedTokenResourceLogicCircuit>("issued token");
+        check::<ReceiverResourceLogicCircuit>("receiver");
+        check::<SignatureVerificationResourceLogicCircuit>("signature verification");
+        check::<MultisigVerificationResourceLogicCircuit>("multisig verification");
+        check::<HtlcResourceLogicCircuit>("htlc");
+        check::<AllowlistResourceLogicCircuit>("allowlist");
+        check::<NftResourceLogicCircuit>("nft");
+        check::<OrRelationIntentResourceLogicCircuit>("or relation intent");
+        check::<PartialFulfillmentIntentResourceLogicCircuit>("partial fulfillment intent");
+        check::<LimitOrderIntentResourceLogicCircuit>("limit order intent");
+        check::<SudokuResourceLogicCircuit>("sudoku");
+        check::<DealerIntentResourceLogicCircuit>("sudoku dealer intent");
+        check::<CounterResourceLogicCircuit>("counter");
+    }
 }
@@ -0,0 +1,275 @@
+/// The allowlist resource logic gates the recipients of a compliance-gated token: the npk of an
+/// output resource must be a leaf of the allowlist Merkle tree. The allowlist root is fixed in the
+/// token label, see `TokenName::encode_label`, so the issuer publishes it with the token kind.
+/// The token logic commits to this logic as the second dynamic resource logic of the output
+/// resources, see `TokenResource::generate_output_token_resource_logics_with_allowlist`.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::poseidon_hash::poseidon_hash_gadget,
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            sub::{SubChip, SubInstructions},
+        },
+        merkle_circuit::{merkle_poseidon_gadget, MerklePoseidonChip},
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::token::TokenName,
+    },
+    constant::{SETUP_PARAMS_MAP, TAIGA_COMMITMENT_TREE_DEPTH},
+    error::TransactionError,
+    merkle_tree::MerklePath,
+    proof::Proof,
+    resource::RandomSeed,
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    utils::read_base_field,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_proofs::{
+    circuit::{floor_planner, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::{group::ff::PrimeField, pallas};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+// The allowlist tree has the depth of the commitment tree
+pub const ALLOWLIST_TREE_DEPTH: usize = TAIGA_COMMITMENT_TREE_DEPTH;
+
+lazy_static! {
+    pub static ref ALLOWLIST_VK: ResourceLogicVerifyingKey =
+        AllowlistResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_ALLOWLIST_VK: pallas::Base = ALLOWLIST_VK.get_compressed();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AllowlistResourceLogicCircuit {
+    pub self_resource: ResourceExistenceWitness,
+    pub token_name: TokenName,
+    pub allowlist_root: pallas::Base,
+    // The path of the npk in the allowlist tree, it's only checked for the output resources.
+    pub allowlist_path: MerklePath,
+}
+
+impl AllowlistResourceLogicCircuit {
+    pub fn new(
+        self_resource: ResourceExistenceWitness,
+        token_name: TokenName,
+        allowlist_root: pallas::Base,
+        allowlist_path: MerklePath,
+    ) -> Self {
+        assert_eq!(allowlist_path.inner().len(), ALLOWLIST_TREE_DEPTH);
+        Self {
+            self_resource,
+            token_name,
+            allowlist_root,
+            allowlist_path,
+        }
+    }
+
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(ResourceLogicRepresentation::Allowlist, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for AllowlistResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        let token_name = assign_free_advice(
+            layouter.namespace(|| "witness token name"),
+            config.advices[0],
+            Value::known(self.token_name.encode()),
+        )?;
+        let allowlist_root = assign_free_advice(
+            layouter.namespace(|| "witness allowlist root"),
+            config.advices[0],
+            Value::known(self.allowlist_root),
+        )?;
+
+        // The allowlist root is fixed in the label, see `TokenName::encode_label`
+        let label = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "label encoding"),
+            [token_name, allowlist_root.clone()],
+        )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| region.constrain_equal(label.cell(), self_resource.resource.label.cell()),
+        )?;
+
+        // Compute the allowlist root with the npk as the leaf
+        let merkle_chip = MerklePoseidonChip::construct(config.merkle_config.clone());
+        let npk_root = merkle_poseidon_gadget(
+            layouter.namespace(|| "allowlist membership"),
+            merkle_chip,
+            self_resource.resource.npk.clone(),
+            &self.allowlist_path.inner(),
+        )?;
+
+        // Only check the recipients, i.e. the output resources
+        let sub_chip = SubChip::construct(config.sub_config, ());
+        let one = assign_free_constant(
+            layouter.namespace(|| "constant one"),
+            config.advices[0],
+            pallas::Base::one(),
+        )?;
+        let is_output = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "is_output = 1 - is_input"),
+            &one,
+            &self_resource.is_input,
+        )?;
+        layouter.assign_region(
+            || "conditional equal: check allowlist root",
+            |mut region| {
+                config.conditional_equal_config.assign_region(
+                    &is_output,
+                    &npk_root,
+                    &allowlist_root,
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+resource_logic_circuit_impl!(AllowlistResourceLogicCircuit);
+resource_logic_verifying_info_impl!(AllowlistResourceLogicCircuit);
+
+impl BorshSerialize for AllowlistResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.token_name.serialize(writer)?;
+        writer.write_all(&self.allowlist_root.to_repr())?;
+        self.allowlist_path.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for AllowlistResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let token_name = TokenName::deserialize_reader(reader)?;
+        let allowlist_root = read_base_field(reader)?;
+        let allowlist_path = MerklePath::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            token_name,
+            allowlist_root,
+            allowlist_path,
+        })
+    }
+}
+
+#[test]
+fn test_halo2_allowlist_resource_logic_circuit() {
+    use crate::constant::{RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE, TAIGA_RESOURCE_TREE_DEPTH};
+    use crate::merkle_tree::{Node, LR};
+    use crate::resource::{tests::random_resource, Resource};
+    use halo2_proofs::{arithmetic::Field, dev::MockProver};
+
+    let mut rng = OsRng;
+    let token_name = TokenName::new("regulated_token".to_string());
+    let mut resource = random_resource(&mut rng);
+    let allowlist_path = MerklePath::random(&mut rng, ALLOWLIST_TREE_DEPTH);
+    let allowlist_root = allowlist_path.root(Node::from(resource.get_npk())).inner();
+    resource.kind.label = token_name.encode_label(Some(allowlist_root));
+
+    let create_circuit = |resource: Resource, is_input: bool, allowlist_root: pallas::Base| {
+        let lr = if is_input { LR::R } else { LR::L };
+        let merkle_path = [(pallas::Base::zero(), lr); TAIGA_RESOURCE_TREE_DEPTH];
+        AllowlistResourceLogicCircuit::new(
+            ResourceExistenceWitness::new(resource, merkle_path),
+            token_name.clone(),
+            allowlist_root,
+            allowlist_path.clone(),
+        )
+    };
+    let verify = |circuit: &AllowlistResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+    };
+
+    // The recipient is in the allowlist
+    let circuit = {
+        let circuit_bytes = create_circuit(resource, false, allowlist_root).to_bytes();
+        AllowlistResourceLogicCircuit::from_bytes(&circuit_bytes)
+    };
+    assert_eq!(verify(&circuit), Ok(()));
+
+    // The recipient is not in the allowlist
+    let mut other_resource = random_resource(&mut rng);
+    other_resource.kind.label = resource.kind.label;
+    assert!(verify(&create_circuit(other_resource, false, allowlist_root)).is_err());
+
+    // The recipient is in another allowlist than the one fixed in the label
+    let other_root = allowlist_path
+        .root(Node::from(other_resource.get_npk()))
+        .inner();
+    assert!(verify(&create_circuit(other_resource, false, other_root)).is_err());
+
+    // A random allowlist root
+    let random_root = pallas::Base::random(&mut rng);
+    assert!(verify(&create_circuit(resource, false, random_root)).is_err());
+
+    // The input resources are not checked against the allowlist
+    assert_eq!(
+        verify(&create_circuit(other_resource, true, allowlist_root)),
+        Ok(())
+    );
+}
@@ -82,6 +82,7 @@ impl ResourceLogicCircuit for FieldAdditionResourceLogicCircuit {
             ResourceLogicCommitment::default().to_public_inputs();
         public_inputs.extend(default_resource_logic_cm);
         public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         public_inputs.push(self.a + self.b);
         let padding = ResourceLogicPublicInputs::get_public_input_padding(
             public_inputs.len(),
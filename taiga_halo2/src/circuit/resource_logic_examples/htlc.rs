@@ -0,0 +1,693 @@
+/// The hash time locked contract(HTLC) resource logic authorizes a resource in two ways:
+/// claim: the receiver reveals the poseidon preimage of the hashlock before the deadline;
+/// refund: the sender takes the resource back at or after the deadline.
+/// The spend is bound to the transaction validity window: a claim must expire before the
+/// deadline, and a refund must not be valid before the deadline. Like the multisig logic, the
+/// HTLC policy is committed to a policy pk which takes the place of the signer pk in the token
+/// value encoding.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+            range_check_u64,
+            sub::{SubChip, SubInstructions},
+            validity_window::load_validity_window,
+        },
+        resource_commitment::ResourceCommitChip,
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::{
+            signature_verification::SchnorrSignature, token::TokenAuthorization,
+        },
+    },
+    constant::{BaseFieldGenerators, TaigaFixedBasesFull, SETUP_PARAMS_MAP},
+    error::TransactionError,
+    proof::Proof,
+    resource::RandomSeed,
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    transaction::ValidityWindow,
+    utils::{mod_r_p, poseidon_hash_n, read_base_field, read_point},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_gadgets::ecc::{
+    chip::EccChip, FixedPoint, FixedPointBaseField, NonIdentityPoint, ScalarFixed, ScalarVar,
+};
+use halo2_proofs::{
+    circuit::{floor_planner, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::{
+    arithmetic::CurveAffine,
+    group::{ff::PrimeField, Curve, Group, GroupEncoding},
+    pallas,
+};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+lazy_static! {
+    pub static ref HTLC_VK: ResourceLogicVerifyingKey =
+        HtlcResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_HTLC_VK: pallas::Base = HTLC_VK.get_compressed();
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtlcPolicy {
+    // hashlock = poseidon_hash(preimage)
+    pub hashlock: pallas::Base,
+    // The resource can be claimed in a transaction that expires before the deadline, and refunded
+    // in a transaction that is valid no earlier than the deadline.
+    pub deadline: u64,
+    pub receiver_pk: pallas::Point,
+    pub sender_pk: pallas::Point,
+}
+
+impl Default for HtlcPolicy {
+    fn default() -> Self {
+        Self {
+            hashlock: pallas::Base::zero(),
+            deadline: 0,
+            receiver_pk: pallas::Point::generator(),
+            sender_pk: pallas::Point::generator(),
+        }
+    }
+}
+
+impl HtlcPolicy {
+    pub fn new(
+        hashlock: pallas::Base,
+        deadline: u64,
+        receiver_pk: pallas::Point,
+        sender_pk: pallas::Point,
+    ) -> Self {
+        Self {
+            hashlock,
+            deadline,
+            receiver_pk,
+            sender_pk,
+        }
+    }
+
+    pub fn compute_hashlock(preimage: pallas::Base) -> pallas::Base {
+        poseidon_hash_n([preimage])
+    }
+
+    // policy_hash = poseidon_hash(hashlock || deadline || receiver_pk || sender_pk)
+    pub fn get_policy_hash(&self) -> pallas::Base {
+        let receiver_pk_coord = self.receiver_pk.to_affine().coordinates().unwrap();
+        let sender_pk_coord = self.sender_pk.to_affine().coordinates().unwrap();
+        poseidon_hash_n([
+            self.hashlock,
+            pallas::Base::from(self.deadline),
+            *receiver_pk_coord.x(),
+            *receiver_pk_coord.y(),
+            *sender_pk_coord.x(),
+            *sender_pk_coord.y(),
+        ])
+    }
+
+    // policy_pk = Hash(policy) * G
+    pub fn get_policy_pk(&self) -> pallas::Point {
+        pallas::Point::generator() * mod_r_p(self.get_policy_hash())
+    }
+
+    /// The token authorization controlled by the HTLC. The output resources are
+    /// encrypted to rcv_pk.
+    pub fn to_token_authorization(&self, rcv_pk: pallas::Point) -> TokenAuthorization {
+        TokenAuthorization {
+            pk: self.get_policy_pk(),
+            vk: *COMPRESSED_HTLC_VK,
+            rcv_pk,
+        }
+    }
+}
+
+/// The way to spend the HTLC resource.
+#[derive(Clone, Debug)]
+pub enum HtlcSpend {
+    Claim {
+        preimage: pallas::Base,
+        receiver_sk: pallas::Scalar,
+    },
+    Refund {
+        sender_sk: pallas::Scalar,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct HtlcResourceLogicCircuit {
+    pub self_resource: ResourceExistenceWitness,
+    pub resource_logic_vk: pallas::Base,
+    pub policy: HtlcPolicy,
+    // The validity window of the transaction spending the resource
+    pub window: ValidityWindow,
+    pub is_claim: bool,
+    // The preimage is only used in the claim
+    pub preimage: pallas::Base,
+    // The signature of the receiver in the claim, or the sender in the refund
+    pub signature: SchnorrSignature,
+    pub receiver_resource_logic_vk: pallas::Base,
+    // rcv_pk is only used to check the value encoding
+    pub rcv_pk: pallas::Point,
+}
+
+impl Default for HtlcResourceLogicCircuit {
+    fn default() -> Self {
+        Self {
+            self_resource: ResourceExistenceWitness::default(),
+            resource_logic_vk: pallas::Base::zero(),
+            policy: HtlcPolicy::default(),
+            window: ValidityWindow::default(),
+            is_claim: false,
+            preimage: pallas::Base::zero(),
+            signature: SchnorrSignature::default(),
+            receiver_resource_logic_vk: pallas::Base::zero(),
+            rcv_pk: pallas::Point::generator(),
+        }
+    }
+}
+
+impl HtlcResourceLogicCircuit {
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_spend_and_sign<R: RngCore>(
+        mut rng: R,
+        self_resource: ResourceExistenceWitness,
+        resource_logic_vk: pallas::Base,
+        policy: HtlcPolicy,
+        spend: HtlcSpend,
+        window: ValidityWindow,
+        receiver_resource_logic_vk: pallas::Base,
+        rcv_pk: pallas::Point,
+    ) -> Self {
+        let message = vec![self_resource.get_root()];
+        let (is_claim, preimage, sk) = match spend {
+            HtlcSpend::Claim {
+                preimage,
+                receiver_sk,
+            } => (true, preimage, receiver_sk),
+            HtlcSpend::Refund { sender_sk } => (false, pallas::Base::zero(), sender_sk),
+        };
+        let signature = SchnorrSignature::sign(&mut rng, sk, message);
+        Self {
+            self_resource,
+            resource_logic_vk,
+            policy,
+            window,
+            is_claim,
+            preimage,
+            signature,
+            receiver_resource_logic_vk,
+            rcv_pk,
+        }
+    }
+
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(ResourceLogicRepresentation::Htlc, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for HtlcResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // Construct an ECC chip
+        let ecc_chip = EccChip::construct(config.ecc_config);
+        let sub_chip = SubChip::construct(config.sub_config, ());
+        let mul_chip = MulChip::construct(config.mul_config);
+
+        // Witness the policy
+        let hashlock = assign_free_advice(
+            layouter.namespace(|| "witness hashlock"),
+            config.advices[0],
+            Value::known(self.policy.hashlock),
+        )?;
+        let deadline = assign_free_advice(
+            layouter.namespace(|| "witness deadline"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.policy.deadline)),
+        )?;
+        let receiver_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness receiver pk"),
+            Value::known(self.policy.receiver_pk.to_affine()),
+        )?;
+        let sender_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness sender pk"),
+            Value::known(self.policy.sender_pk.to_affine()),
+        )?;
+
+        // policy_pk = Hash(policy) * G
+        let policy_pk = {
+            let policy_hash = poseidon_hash_gadget(
+                config.poseidon_config.clone(),
+                layouter.namespace(|| "policy hash"),
+                [
+                    hashlock.clone(),
+                    deadline.clone(),
+                    receiver_pk.inner().x(),
+                    receiver_pk.inner().y(),
+                    sender_pk.inner().x(),
+                    sender_pk.inner().y(),
+                ],
+            )?;
+            let generator = FixedPointBaseField::from_inner(
+                ecc_chip.clone(),
+                BaseFieldGenerators::BaseGenerator,
+            );
+            generator.mul(
+                layouter.namespace(|| "policy_hash * generator"),
+                policy_hash,
+            )?
+        };
+
+        let auth_resource_logic_vk = assign_free_advice(
+            layouter.namespace(|| "witness auth resource_logic vk"),
+            config.advices[0],
+            Value::known(self.resource_logic_vk),
+        )?;
+        let receiver_resource_logic_vk = assign_free_advice(
+            layouter.namespace(|| "witness receiver resource_logic vk"),
+            config.advices[0],
+            Value::known(self.receiver_resource_logic_vk),
+        )?;
+
+        let rcv_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness rcv_pk"),
+            Value::known(self.rcv_pk.to_affine()),
+        )?;
+
+        // Decode the value, and check the value encoding
+        let encoded_value = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "value encoding"),
+            [
+                policy_pk.inner().x(),
+                policy_pk.inner().y(),
+                auth_resource_logic_vk,
+                receiver_resource_logic_vk,
+                rcv_pk.inner().x(),
+                rcv_pk.inner().y(),
+            ],
+        )?;
+
+        layouter.assign_region(
+            || "check value encoding",
+            |mut region| {
+                region.constrain_equal(encoded_value.cell(), self_resource.resource.value.cell())
+            },
+        )?;
+
+        // Load the transaction validity window
+        let window = load_validity_window(
+            layouter.namespace(|| "load validity window"),
+            config.instances,
+            config.advices[0],
+        )?;
+
+        // is_claim is boolean: is_claim * is_claim = is_claim
+        let is_claim = assign_free_advice(
+            layouter.namespace(|| "witness is_claim"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.is_claim)),
+        )?;
+        let is_claim_square = mul_chip.mul(
+            layouter.namespace(|| "is_claim * is_claim"),
+            &is_claim,
+            &is_claim,
+        )?;
+        layouter.assign_region(
+            || "is_claim is boolean",
+            |mut region| region.constrain_equal(is_claim_square.cell(), is_claim.cell()),
+        )?;
+
+        // Check the preimage in the claim
+        let preimage = assign_free_advice(
+            layouter.namespace(|| "witness preimage"),
+            config.advices[0],
+            Value::known(self.preimage),
+        )?;
+        let preimage_hash = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "preimage hash"),
+            [preimage],
+        )?;
+        layouter.assign_region(
+            || "conditional equal: check hashlock",
+            |mut region| {
+                config.conditional_equal_config.assign_region(
+                    &is_claim,
+                    &preimage_hash,
+                    &hashlock,
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+
+        // Check the validity window
+        // claim: expires_at < deadline, i.e. deadline - expires_at - 1 in [0, 2^64)
+        // refund: not_before >= deadline, i.e. not_before - deadline in [0, 2^64)
+        let claim_diff = {
+            let diff = SubInstructions::sub(
+                &sub_chip,
+                layouter.namespace(|| "deadline - expires_at"),
+                &deadline,
+                &window.expires_at,
+            )?;
+            let one = assign_free_constant(
+                layouter.namespace(|| "constant one"),
+                config.advices[0],
+                pallas::Base::one(),
+            )?;
+            SubInstructions::sub(
+                &sub_chip,
+                layouter.namespace(|| "deadline - expires_at - 1"),
+                &diff,
+                &one,
+            )?
+        };
+        let refund_diff = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "not_before - deadline"),
+            &window.not_before,
+            &deadline,
+        )?;
+        let height_diff = layouter.assign_region(
+            || "conditional select: height diff",
+            |mut region| {
+                config.conditional_select_config.assign_region(
+                    &is_claim,
+                    &claim_diff,
+                    &refund_diff,
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+        let resource_commit_chip = ResourceCommitChip::construct(config.resource_commit_config);
+        range_check_u64(
+            layouter.namespace(|| "height diff range check"),
+            resource_commit_chip.get_lookup_config(),
+            height_diff,
+        )?;
+
+        // The receiver signs the claim and the sender signs the refund
+        let signer_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness signer pk"),
+            Value::known(self.signature.pk().to_affine()),
+        )?;
+        layouter.assign_region(
+            || "check signer pk",
+            |mut region| {
+                let x = config.conditional_select_config.assign_region(
+                    &is_claim,
+                    &receiver_pk.inner().x(),
+                    &sender_pk.inner().x(),
+                    0,
+                    &mut region,
+                )?;
+                region.constrain_equal(x.cell(), signer_pk.inner().x().cell())?;
+                let y = config.conditional_select_config.assign_region(
+                    &is_claim,
+                    &receiver_pk.inner().y(),
+                    &sender_pk.inner().y(),
+                    2,
+                    &mut region,
+                )?;
+                region.constrain_equal(y.cell(), signer_pk.inner().y().cell())
+            },
+        )?;
+
+        let r = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness r"),
+            Value::known(self.signature.r().to_affine()),
+        )?;
+        let s_scalar = ScalarFixed::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness s"),
+            Value::known(self.signature.s()),
+        )?;
+
+        // Verify: s*G = R + Hash(r||P||m)*P
+        // s*G
+        let generator =
+            FixedPoint::from_inner(ecc_chip.clone(), TaigaFixedBasesFull::BaseGenerator);
+        let (s_g, _) = generator.mul(layouter.namespace(|| "s_scalar * generator"), &s_scalar)?;
+
+        // Hash(r||P||m)
+        let h_scalar = {
+            let h = poseidon_hash_gadget(
+                config.poseidon_config,
+                layouter.namespace(|| "Poseidon_hash(r, P, m)"),
+                [
+                    r.inner().x(),
+                    r.inner().y(),
+                    signer_pk.inner().x(),
+                    signer_pk.inner().y(),
+                    self_resource.resource_merkle_root,
+                ],
+            )?;
+
+            ScalarVar::from_base(ecc_chip, layouter.namespace(|| "ScalarVar from_base"), &h)?
+        };
+
+        // Hash(r||P||m)*P
+        let (h_p, _) = signer_pk.mul(layouter.namespace(|| "hP"), h_scalar)?;
+
+        // R + Hash(r||P||m)*P
+        let rhs = r.add(layouter.namespace(|| "R + Hash(r||P||m)*P"), &h_p)?;
+
+        s_g.constrain_equal(layouter.namespace(|| "s*G = R + Hash(r||P||m)*P"), &rhs)?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+
+    fn get_validity_window(&self) -> ValidityWindow {
+        self.window
+    }
+}
+
+resource_logic_circuit_impl!(HtlcResourceLogicCircuit);
+resource_logic_verifying_info_impl!(HtlcResourceLogicCircuit);
+
+impl BorshSerialize for HtlcResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        writer.write_all(&self.resource_logic_vk.to_repr())?;
+        self.policy.serialize(writer)?;
+        self.window.serialize(writer)?;
+        self.is_claim.serialize(writer)?;
+        writer.write_all(&self.preimage.to_repr())?;
+        self.signature.serialize(writer)?;
+        writer.write_all(&self.receiver_resource_logic_vk.to_repr())?;
+        writer.write_all(&self.rcv_pk.to_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for HtlcResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let resource_logic_vk = read_base_field(reader)?;
+        let policy = HtlcPolicy::deserialize_reader(reader)?;
+        let window = ValidityWindow::deserialize_reader(reader)?;
+        let is_claim = bool::deserialize_reader(reader)?;
+        let preimage = read_base_field(reader)?;
+        let signature = SchnorrSignature::deserialize_reader(reader)?;
+        let receiver_resource_logic_vk = read_base_field(reader)?;
+        let rcv_pk = read_point(reader)?;
+        Ok(Self {
+            self_resource,
+            resource_logic_vk,
+            policy,
+            window,
+            is_claim,
+            preimage,
+            signature,
+            receiver_resource_logic_vk,
+            rcv_pk,
+        })
+    }
+}
+
+impl BorshSerialize for HtlcPolicy {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.hashlock.to_repr())?;
+        self.deadline.serialize(writer)?;
+        writer.write_all(&self.receiver_pk.to_bytes())?;
+        writer.write_all(&self.sender_pk.to_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for HtlcPolicy {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let hashlock = read_base_field(reader)?;
+        let deadline = u64::deserialize_reader(reader)?;
+        let receiver_pk = read_point(reader)?;
+        let sender_pk = read_point(reader)?;
+        Ok(Self {
+            hashlock,
+            deadline,
+            receiver_pk,
+            sender_pk,
+        })
+    }
+}
+
+#[test]
+fn test_halo2_htlc_resource_logic_circuit() {
+    use crate::circuit::resource_logic_examples::receiver_resource_logic::COMPRESSED_RECEIVER_VK;
+    use crate::constant::{RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE, TAIGA_RESOURCE_TREE_DEPTH};
+    use crate::merkle_tree::LR;
+    use crate::resource::tests::random_resource;
+    use halo2_proofs::{arithmetic::Field, dev::MockProver};
+
+    let mut rng = OsRng;
+    let receiver_sk = pallas::Scalar::random(&mut rng);
+    let sender_sk = pallas::Scalar::random(&mut rng);
+    let preimage = pallas::Base::random(&mut rng);
+    let policy = HtlcPolicy::new(
+        HtlcPolicy::compute_hashlock(preimage),
+        100,
+        pallas::Point::generator() * receiver_sk,
+        pallas::Point::generator() * sender_sk,
+    );
+
+    let auth_vk = pallas::Base::random(&mut rng);
+    let rcv_pk = pallas::Point::random(&mut rng);
+    let mut auth = policy.to_token_authorization(rcv_pk);
+    auth.vk = auth_vk;
+    let mut resource = random_resource(&mut rng);
+    resource.value = auth.to_value();
+    let merkle_path = [(pallas::Base::zero(), LR::R); TAIGA_RESOURCE_TREE_DEPTH];
+    let resource_witness = ResourceExistenceWitness::new(resource, merkle_path);
+    let create_circuit = |spend: HtlcSpend, window: ValidityWindow| {
+        HtlcResourceLogicCircuit::from_spend_and_sign(
+            OsRng,
+            resource_witness,
+            auth_vk,
+            policy.clone(),
+            spend,
+            window,
+            *COMPRESSED_RECEIVER_VK,
+            rcv_pk,
+        )
+    };
+    let verify = |circuit: &HtlcResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        assert_eq!(public_inputs.get_validity_window(), Some(circuit.window));
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+    };
+    let claim = HtlcSpend::Claim {
+        preimage,
+        receiver_sk,
+    };
+    let refund = HtlcSpend::Refund { sender_sk };
+
+    // Claim in a transaction expiring before the deadline
+    let circuit = {
+        let circuit_bytes = create_circuit(claim.clone(), ValidityWindow::new(50, 99)).to_bytes();
+        HtlcResourceLogicCircuit::from_bytes(&circuit_bytes)
+    };
+    assert_eq!(verify(&circuit), Ok(()));
+
+    // Claim after the deadline fails
+    assert!(verify(&create_circuit(claim.clone(), ValidityWindow::new(50, 100))).is_err());
+    assert!(verify(&create_circuit(
+        claim.clone(),
+        ValidityWindow::new(120, 150)
+    ))
+    .is_err());
+
+    // Claim without an expiry fails
+    assert!(verify(&create_circuit(claim, ValidityWindow::default())).is_err());
+
+    // Claim with a wrong preimage fails
+    let wrong_claim = HtlcSpend::Claim {
+        preimage: pallas::Base::random(&mut rng),
+        receiver_sk,
+    };
+    assert!(verify(&create_circuit(wrong_claim, ValidityWindow::new(50, 99))).is_err());
+
+    // Refund in a transaction valid from the deadline
+    assert_eq!(
+        verify(&create_circuit(
+            refund.clone(),
+            ValidityWindow::new(100, 150)
+        )),
+        Ok(())
+    );
+
+    // Refund before the deadline fails
+    assert!(verify(&create_circuit(
+        refund.clone(),
+        ValidityWindow::new(99, 150)
+    ))
+    .is_err());
+    assert!(verify(&create_circuit(refund, ValidityWindow::default())).is_err());
+
+    // Refund signed by the receiver fails
+    let wrong_refund = HtlcSpend::Refund {
+        sender_sk: receiver_sk,
+    };
+    assert!(verify(&create_circuit(wrong_refund, ValidityWindow::new(100, 150))).is_err());
+}
@@ -0,0 +1,939 @@
+/// The limit order intent sells up to the whole sell resource for at least the limit price.
+/// For instance, Alice has 10 BTC(sell_resource) and wants at least 3 ETH per 2 BTC. A solver
+/// can fill any portion at any price no worse than the limit: exchanging 4 BTC for 7 ETH(offer
+/// resource) and returning the other 6 BTC(returned resource) to Alice. The price is rational, so
+/// the fills don't have to be multiples of a fixed ratio.
+///
+/// The intent resource can only be created by consuming the sell resource in the same
+/// transaction, so the sold tokens are locked in the intent until it's filled.
+///
+/// Every fill satisfies `received * price_den >= given * price_num`, which is checked with u64
+/// multiplications and a 128-bit range check on the difference.
+///
+/// The order expires at the `expires_at` height: the transaction filling it must expire at or
+/// before that height, which is checked against the transaction validity window.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            comparison::{U64ComparisonChip, U64ComparisonInstructions},
+            conditional_equal::conditional_equal,
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+            range_check_bits, range_check_u64,
+            sub::{SubChip, SubInstructions},
+            validity_window::load_validity_window,
+        },
+        integrity::load_resource,
+        merkle_circuit::MerklePoseidonChip,
+        resource_commitment::ResourceCommitChip,
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::token::{
+            Token, TokenAuthorization, TokenName, TokenResource, TOKEN_VK,
+        },
+    },
+    constant::SETUP_PARAMS_MAP,
+    error::TransactionError,
+    proof::Proof,
+    resource::{RandomSeed, Resource},
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    transaction::ValidityWindow,
+    utils::poseidon_hash_n,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::{
+    circuit::{floor_planner, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::pallas;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+lazy_static! {
+    pub static ref LIMIT_ORDER_INTENT_VK: ResourceLogicVerifyingKey =
+        LimitOrderIntentResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_LIMIT_ORDER_INTENT_VK: pallas::Base =
+        LIMIT_ORDER_INTENT_VK.get_compressed();
+}
+
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct LimitOrder {
+    pub sell: TokenResource,
+    pub buy: TokenName,
+    // The limit price is price_num bought tokens per price_den sold tokens.
+    pub price_num: u64,
+    pub price_den: u64,
+    // The order can't be filled after the expiry height
+    pub expires_at: u64,
+    pub auth: TokenAuthorization,
+}
+
+impl LimitOrder {
+    pub fn random(
+        mut rng: impl RngCore,
+        sell: Token,
+        buy: TokenName,
+        price_num: u64,
+        price_den: u64,
+        expires_at: u64,
+        auth: TokenAuthorization,
+    ) -> Self {
+        assert_ne!(price_den, 0);
+
+        let sell = {
+            let nk = pallas::Base::random(&mut rng);
+            sell.create_random_input_token_resource(&mut rng, nk, &auth)
+        };
+
+        LimitOrder {
+            sell,
+            buy,
+            price_num,
+            price_den,
+            expires_at,
+            auth,
+        }
+    }
+
+    /// Check the fill respects the limit price: received * price_den >= given * price_num
+    pub fn is_valid_fill(&self, given: u64, received: u64) -> bool {
+        given <= self.sell.resource().quantity
+            && received as u128 * self.price_den as u128 >= given as u128 * self.price_num as u128
+    }
+
+    /// Fill the order by giving `given` sold tokens for `received` bought tokens. Returns the
+    /// offer resource and the returned resource with the rest of the sold tokens, both to the
+    /// owner of the order. Returns `TransactionError::InvalidOffer` if the fill exceeds the sold
+    /// quantity or is worse than the limit price.
+    pub fn fill(
+        &self,
+        mut rng: impl RngCore,
+        given: u64,
+        received: u64,
+    ) -> Result<(Resource, Resource), TransactionError> {
+        if !self.is_valid_fill(given, received) {
+            return Err(TransactionError::InvalidOffer);
+        }
+
+        let npk = self.sell.resource().nk_container.get_npk();
+        let offer_resource = Token::new(self.buy.inner(), received)
+            .create_random_output_token_resource(&mut rng, npk, &self.auth);
+        let returned_resource = Token::new(
+            self.sell.token_name().inner(),
+            self.sell.resource().quantity - given,
+        )
+        .create_random_output_token_resource(&mut rng, npk, &self.auth);
+
+        Ok((*offer_resource.resource(), *returned_resource.resource()))
+    }
+
+    pub fn encode_label(&self) -> pallas::Base {
+        poseidon_hash_n([
+            self.sell.encode_name(),
+            self.sell.encode_quantity(),
+            self.buy.encode(),
+            pallas::Base::from(self.price_num),
+            pallas::Base::from(self.price_den),
+            pallas::Base::from(self.expires_at),
+            // Assuming the sold_token and bought_token have the same TOKEN_VK
+            TOKEN_VK.get_compressed(),
+            self.sell.resource().get_npk(),
+            self.sell.resource().value,
+        ])
+    }
+
+    pub fn create_intent_resource<R: RngCore>(&self, mut rng: R) -> Resource {
+        let rseed = pallas::Base::random(&mut rng);
+
+        Resource::new_input_resource(
+            *COMPRESSED_LIMIT_ORDER_INTENT_VK,
+            self.encode_label(),
+            pallas::Base::zero(),
+            1u64,
+            self.sell.resource().nk_container.get_nk().unwrap(),
+            self.sell.resource().get_nf().unwrap(),
+            true,
+            rseed,
+        )
+    }
+}
+
+// LimitOrderIntentResourceLogicCircuit
+#[derive(Clone, Debug, Default)]
+pub struct LimitOrderIntentResourceLogicCircuit {
+    // intent resource
+    pub self_resource: ResourceExistenceWitness,
+    // constraints on sell_resource will be enabled only when creating the intent resource, otherwise it's a dummy one
+    pub sell_resource: ResourceExistenceWitness,
+    // constraints will be enabled only when consuming the intent resource, otherwise it's a dummy one
+    pub offer_resource: ResourceExistenceWitness,
+    // constraints will be enabled only when consuming the intent resource, otherwise it's a dummy one
+    pub returned_resource: ResourceExistenceWitness,
+    pub order: LimitOrder,
+    // The quantity of the sold tokens given in the fill, it's zero when creating the intent resource
+    pub given_quantity: u64,
+    // The validity window of the transaction
+    pub window: ValidityWindow,
+}
+
+impl LimitOrderIntentResourceLogicCircuit {
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(
+            ResourceLogicRepresentation::LimitOrderIntent,
+            self.to_bytes(),
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for LimitOrderIntentResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // Construct a merkle chip
+        let merkle_chip = MerklePoseidonChip::construct(config.merkle_config.clone());
+
+        // Construct a resource_commit chip
+        let resource_commit_chip =
+            ResourceCommitChip::construct(config.resource_commit_config.clone());
+
+        let sub_chip = SubChip::construct(config.sub_config.clone(), ());
+        let mul_chip = MulChip::construct(config.mul_config.clone());
+        let comparison_chip = U64ComparisonChip::construct(config.comparison_config.clone());
+
+        // load the sell resource
+        let sell_resource = load_resource(
+            layouter.namespace(|| "load the sell resource"),
+            config.advices,
+            resource_commit_chip.clone(),
+            config.conditional_select_config,
+            merkle_chip.clone(),
+            &self.sell_resource,
+        )?;
+
+        // load the offer resource
+        let offer_resource = load_resource(
+            layouter.namespace(|| "load the offer resource"),
+            config.advices,
+            resource_commit_chip.clone(),
+            config.conditional_select_config,
+            merkle_chip.clone(),
+            &self.offer_resource,
+        )?;
+
+        // load the returned resource
+        let returned_resource = load_resource(
+            layouter.namespace(|| "load the returned resource"),
+            config.advices,
+            resource_commit_chip.clone(),
+            config.conditional_select_config,
+            merkle_chip,
+            &self.returned_resource,
+        )?;
+
+        // Witness the label
+        let token_resource_logic_vk = assign_free_advice(
+            layouter.namespace(|| "witness token resource_logic vk"),
+            config.advices[0],
+            Value::known(TOKEN_VK.get_compressed()),
+        )?;
+        let sold_token = assign_free_advice(
+            layouter.namespace(|| "witness sold_token"),
+            config.advices[0],
+            Value::known(self.order.sell.encode_name()),
+        )?;
+        let sold_token_quantity = assign_free_advice(
+            layouter.namespace(|| "witness sold_token_quantity"),
+            config.advices[0],
+            Value::known(self.order.sell.encode_quantity()),
+        )?;
+        let bought_token = assign_free_advice(
+            layouter.namespace(|| "witness bought_token"),
+            config.advices[0],
+            Value::known(self.order.buy.encode()),
+        )?;
+        let price_num = assign_free_advice(
+            layouter.namespace(|| "witness price_num"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.order.price_num)),
+        )?;
+        let price_den = assign_free_advice(
+            layouter.namespace(|| "witness price_den"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.order.price_den)),
+        )?;
+        let expires_at = assign_free_advice(
+            layouter.namespace(|| "witness expires_at"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.order.expires_at)),
+        )?;
+        let receiver_npk = assign_free_advice(
+            layouter.namespace(|| "witness receiver npk"),
+            config.advices[0],
+            Value::known(self.order.sell.resource().get_npk()),
+        )?;
+        let receiver_value = assign_free_advice(
+            layouter.namespace(|| "witness receiver value"),
+            config.advices[0],
+            Value::known(self.order.sell.resource().value),
+        )?;
+
+        // Encode the label of intent resource
+        let encoded_label = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "label encoding"),
+            [
+                sold_token.clone(),
+                sold_token_quantity.clone(),
+                bought_token.clone(),
+                price_num.clone(),
+                price_den.clone(),
+                expires_at.clone(),
+                token_resource_logic_vk.clone(),
+                receiver_npk.clone(),
+                receiver_value.clone(),
+            ],
+        )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| {
+                region.constrain_equal(encoded_label.cell(), self_resource.resource.label.cell())
+            },
+        )?;
+
+        // intent resource creation: the sell resource matches the label and is consumed in the
+        // same transaction, the intent resource is paired with it
+        let constant_one = assign_free_constant(
+            layouter.namespace(|| "one"),
+            config.advices[0],
+            pallas::Base::one(),
+        )?;
+        // is_input - 1 is non-zero for the output intent resource
+        let is_output = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "is_output"),
+            &self_resource.is_input,
+            &constant_one,
+        )?;
+        for (name, lhs, rhs) in [
+            (
+                "sell root",
+                &self_resource.resource_merkle_root,
+                &sell_resource.resource_merkle_root,
+            ),
+            ("sell is_input", &constant_one, &sell_resource.is_input),
+            (
+                "intent nonce",
+                &sell_resource.identity,
+                &self_resource.resource.nonce,
+            ),
+            (
+                "sell token vk",
+                &token_resource_logic_vk,
+                &sell_resource.resource.logic,
+            ),
+            (
+                "sell token label",
+                &sold_token,
+                &sell_resource.resource.label,
+            ),
+            (
+                "sell token quantity",
+                &sold_token_quantity,
+                &sell_resource.resource.quantity,
+            ),
+        ] {
+            conditional_equal(
+                &mut layouter,
+                &config.conditional_equal_config,
+                name,
+                &is_output,
+                lhs,
+                rhs,
+            )?;
+        }
+
+        // intent resource consumption: the offer resource and the returned resource with the
+        // rest of the sold tokens go to the receiver
+        let given_quantity = assign_free_advice(
+            layouter.namespace(|| "witness given_quantity"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.given_quantity)),
+        )?;
+        let returned_quantity = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "sold_quantity - given_quantity"),
+            &sold_token_quantity,
+            &given_quantity,
+        )?;
+        let constant_zero = assign_free_constant(
+            layouter.namespace(|| "zero"),
+            config.advices[0],
+            pallas::Base::zero(),
+        )?;
+
+        let is_input = &self_resource.is_input;
+        for (name, lhs, rhs) in [
+            (
+                "offer root",
+                &self_resource.resource_merkle_root,
+                &offer_resource.resource_merkle_root,
+            ),
+            ("offer is_input", &constant_zero, &offer_resource.is_input),
+            (
+                "offer token vk",
+                &token_resource_logic_vk,
+                &offer_resource.resource.logic,
+            ),
+            (
+                "offer token label",
+                &bought_token,
+                &offer_resource.resource.label,
+            ),
+            ("offer npk", &receiver_npk, &offer_resource.resource.npk),
+            (
+                "offer value",
+                &receiver_value,
+                &offer_resource.resource.value,
+            ),
+            (
+                "returned root",
+                &self_resource.resource_merkle_root,
+                &returned_resource.resource_merkle_root,
+            ),
+            (
+                "returned is_input",
+                &constant_zero,
+                &returned_resource.is_input,
+            ),
+            (
+                "returned token vk",
+                &token_resource_logic_vk,
+                &returned_resource.resource.logic,
+            ),
+            (
+                "returned token label",
+                &sold_token,
+                &returned_resource.resource.label,
+            ),
+            (
+                "returned token quantity",
+                &returned_quantity,
+                &returned_resource.resource.quantity,
+            ),
+            (
+                "returned npk",
+                &receiver_npk,
+                &returned_resource.resource.npk,
+            ),
+            (
+                "returned value",
+                &receiver_value,
+                &returned_resource.resource.value,
+            ),
+        ] {
+            conditional_equal(
+                &mut layouter,
+                &config.conditional_equal_config,
+                name,
+                is_input,
+                lhs,
+                rhs,
+            )?;
+        }
+
+        // limit price check
+        let received_mul_price_den = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "received_quantity * price_den"),
+            &offer_resource.resource.quantity,
+            &price_den,
+        )?;
+        let given_mul_price_num = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "given_quantity * price_num"),
+            &given_quantity,
+            &price_num,
+        )?;
+        let price_diff = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "received * price_den - given * price_num"),
+            &received_mul_price_den,
+            &given_mul_price_num,
+        )?;
+
+        // The offer and returned resources are dummy ones when creating the intent resource,
+        // so only the consumption is range checked.
+        let (given_quantity, returned_quantity, price_diff) = layouter.assign_region(
+            || "conditional select: fill quantities",
+            |mut region| {
+                let given_quantity = config.conditional_select_config.assign_region(
+                    is_input,
+                    &given_quantity,
+                    &constant_zero,
+                    0,
+                    &mut region,
+                )?;
+                let returned_quantity = config.conditional_select_config.assign_region(
+                    is_input,
+                    &returned_quantity,
+                    &constant_zero,
+                    2,
+                    &mut region,
+                )?;
+                let price_diff = config.conditional_select_config.assign_region(
+                    is_input,
+                    &price_diff,
+                    &constant_zero,
+                    4,
+                    &mut region,
+                )?;
+                Ok((given_quantity, returned_quantity, price_diff))
+            },
+        )?;
+
+        // The given quantity is not greater than the sold quantity
+        range_check_u64(
+            layouter.namespace(|| "given quantity range check"),
+            resource_commit_chip.get_lookup_config(),
+            given_quantity,
+        )?;
+        range_check_u64(
+            layouter.namespace(|| "returned quantity range check"),
+            resource_commit_chip.get_lookup_config(),
+            returned_quantity,
+        )?;
+
+        // received * price_den >= given * price_num, both sides are less than 2^128
+        range_check_bits(
+            layouter.namespace(|| "limit price range check"),
+            resource_commit_chip.get_lookup_config(),
+            price_diff,
+            128,
+        )?;
+
+        // The transaction consuming the intent resource expires at or before the order expiry
+        let window = load_validity_window(
+            layouter.namespace(|| "load validity window"),
+            config.instances,
+            config.advices[0],
+        )?;
+        let fill_expires_at = layouter.assign_region(
+            || "conditional select: fill expiry",
+            |mut region| {
+                config.conditional_select_config.assign_region(
+                    is_input,
+                    &window.expires_at,
+                    &constant_zero,
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+        comparison_chip.less_than_or_equal(
+            layouter.namespace(|| "check order expiry"),
+            &fill_expires_at,
+            &expires_at,
+        )?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+
+    fn get_validity_window(&self) -> ValidityWindow {
+        self.window
+    }
+}
+
+resource_logic_circuit_impl!(LimitOrderIntentResourceLogicCircuit);
+resource_logic_verifying_info_impl!(LimitOrderIntentResourceLogicCircuit);
+
+impl BorshSerialize for LimitOrderIntentResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.sell_resource.serialize(writer)?;
+        self.offer_resource.serialize(writer)?;
+        self.returned_resource.serialize(writer)?;
+        self.order.serialize(writer)?;
+        self.given_quantity.serialize(writer)?;
+        self.window.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for LimitOrderIntentResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let sell_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let offer_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let returned_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let order = LimitOrder::deserialize_reader(reader)?;
+        let given_quantity = u64::deserialize_reader(reader)?;
+        let window = ValidityWindow::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            sell_resource,
+            offer_resource,
+            returned_resource,
+            order,
+            given_quantity,
+            window,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::resource_logic_examples::signature_verification::COMPRESSED_TOKEN_AUTH_VK;
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use crate::nullifier::{Nullifier, NullifierKeyContainer};
+    use crate::resource_tree::ResourceMerkleTreeLeaves;
+    use halo2_proofs::dev::MockProver;
+
+    const EXPIRES_AT: u64 = 100;
+
+    // Sell up to 10 token1 for at least 3 token2 per 2 token1 until the height 100
+    fn limit_order(mut rng: impl RngCore) -> LimitOrder {
+        let sk = pallas::Scalar::random(&mut rng);
+        let auth = TokenAuthorization::from_sk_vk(&sk, &COMPRESSED_TOKEN_AUTH_VK);
+        let sell = Token::new("token1".to_string(), 10u64);
+        LimitOrder::random(
+            &mut rng,
+            sell,
+            TokenName::new("token2".to_string()),
+            3,
+            2,
+            EXPIRES_AT,
+            auth,
+        )
+    }
+
+    fn create_intent_circuit(
+        order: LimitOrder,
+        sell_resource: Resource,
+        intent_resource: Resource,
+    ) -> LimitOrderIntentResourceLogicCircuit {
+        let sell_nf = sell_resource.get_nf().unwrap().inner();
+        let intent_cm = intent_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![sell_nf, intent_cm]);
+        let sell_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(sell_nf).unwrap();
+            ResourceExistenceWitness::new(sell_resource, merkle_path)
+        };
+        let intent_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(intent_cm).unwrap();
+            ResourceExistenceWitness::new(intent_resource, merkle_path)
+        };
+
+        LimitOrderIntentResourceLogicCircuit {
+            self_resource: intent_resource_witness,
+            sell_resource: sell_resource_witness,
+            offer_resource: ResourceExistenceWitness::default(), // a dummy resource
+            returned_resource: ResourceExistenceWitness::default(), // a dummy resource
+            order,
+            given_quantity: 0,
+            window: ValidityWindow::default(),
+        }
+    }
+
+    fn consume_intent_circuit(
+        mut rng: impl RngCore,
+        order: LimitOrder,
+        offer_resource: Resource,
+        returned_resource: Resource,
+        given_quantity: u64,
+        window: ValidityWindow,
+    ) -> LimitOrderIntentResourceLogicCircuit {
+        let intent_resource = order.create_intent_resource(&mut rng);
+        let intent_nf = intent_resource.get_nf().unwrap().inner();
+        let offer_cm = offer_resource.commitment().inner();
+        let returned_cm = returned_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![
+            intent_nf,
+            offer_cm,
+            pallas::Base::zero(),
+            returned_cm,
+        ]);
+        let witness = |resource: Resource, id: pallas::Base| {
+            let merkle_path = resource_merkle_tree.generate_path(id).unwrap();
+            ResourceExistenceWitness::new(resource, merkle_path)
+        };
+
+        LimitOrderIntentResourceLogicCircuit {
+            self_resource: witness(intent_resource, intent_nf),
+            sell_resource: ResourceExistenceWitness::default(), // a dummy one
+            offer_resource: witness(offer_resource, offer_cm),
+            returned_resource: witness(returned_resource, returned_cm),
+            order,
+            given_quantity,
+            window,
+        }
+    }
+
+    fn verify(circuit: &LimitOrderIntentResourceLogicCircuit) -> bool {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+        .is_ok()
+    }
+
+    #[test]
+    fn test_halo2_limit_order_intent_creation() {
+        let mut rng = OsRng;
+        let order = limit_order(&mut rng);
+        let sell_resource = *order.sell.resource();
+        let intent_resource = order.create_intent_resource(&mut rng);
+
+        let circuit = create_intent_circuit(order.clone(), sell_resource, intent_resource);
+        let circuit = {
+            let circuit_bytes = circuit.to_bytes();
+            LimitOrderIntentResourceLogicCircuit::from_bytes(&circuit_bytes)
+        };
+        assert!(verify(&circuit));
+
+        // The sell resource must be consumed in the same transaction
+        let mut circuit = create_intent_circuit(order.clone(), sell_resource, intent_resource);
+        let other_resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![
+            sell_resource.get_nf().unwrap().inner(),
+            pallas::Base::random(&mut rng),
+        ]);
+        circuit.sell_resource = ResourceExistenceWitness::new(
+            sell_resource,
+            other_resource_merkle_tree
+                .generate_path(sell_resource.get_nf().unwrap().inner())
+                .unwrap(),
+        );
+        assert!(!verify(&circuit));
+
+        // The sell resource must be an input resource
+        let mut circuit = create_intent_circuit(order.clone(), sell_resource, intent_resource);
+        let sell_cm = sell_resource.commitment().inner();
+        let intent_cm = intent_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![
+            sell_resource.get_nf().unwrap().inner(),
+            intent_cm,
+            pallas::Base::zero(),
+            sell_cm,
+        ]);
+        circuit.self_resource = ResourceExistenceWitness::new(
+            intent_resource,
+            resource_merkle_tree.generate_path(intent_cm).unwrap(),
+        );
+        circuit.sell_resource = ResourceExistenceWitness::new(
+            sell_resource,
+            resource_merkle_tree.generate_path(sell_cm).unwrap(),
+        );
+        assert!(!verify(&circuit));
+
+        // The intent resource must be paired with the sell resource
+        let mut invalid_intent_resource = intent_resource;
+        invalid_intent_resource.nonce = Nullifier::random(&mut rng);
+        let circuit = create_intent_circuit(order.clone(), sell_resource, invalid_intent_resource);
+        assert!(!verify(&circuit));
+
+        // A dummy sell resource can't create the intent resource
+        let mut dummy_sell_resource = sell_resource;
+        dummy_sell_resource.nonce = Nullifier::random(&mut rng);
+        let circuit = create_intent_circuit(order, dummy_sell_resource, intent_resource);
+        assert!(!verify(&circuit));
+    }
+
+    #[test]
+    fn test_halo2_limit_order_intent_fill() {
+        let mut rng = OsRng;
+        let order = limit_order(&mut rng);
+        let fill_window = ValidityWindow::new(0, EXPIRES_AT);
+
+        // Fill 4 token1 for 7 token2, better than the limit price
+        let (offer_resource, returned_resource) = order.fill(&mut rng, 4, 7).unwrap();
+        let circuit = consume_intent_circuit(
+            &mut rng,
+            order.clone(),
+            offer_resource,
+            returned_resource,
+            4,
+            fill_window,
+        );
+        let circuit = {
+            let circuit_bytes = circuit.to_bytes();
+            LimitOrderIntentResourceLogicCircuit::from_bytes(&circuit_bytes)
+        };
+        assert!(verify(&circuit));
+
+        // Fill 4 token1 for 5 token2, worse than the limit price
+        assert!(matches!(
+            order.fill(&mut rng, 4, 5),
+            Err(TransactionError::InvalidOffer)
+        ));
+        let (mut offer_resource, returned_resource) = order.fill(&mut rng, 4, 6).unwrap();
+        offer_resource.quantity = 5;
+        let circuit = consume_intent_circuit(
+            &mut rng,
+            order.clone(),
+            offer_resource,
+            returned_resource,
+            4,
+            fill_window,
+        );
+        assert!(!verify(&circuit));
+
+        // Fill all the token1 at the limit price, nothing is returned
+        let (offer_resource, returned_resource) = order.fill(&mut rng, 10, 15).unwrap();
+        assert_eq!(returned_resource.quantity, 0);
+        let circuit = consume_intent_circuit(
+            &mut rng,
+            order.clone(),
+            offer_resource,
+            returned_resource,
+            10,
+            fill_window,
+        );
+        assert!(verify(&circuit));
+
+        // Giving more than the sold quantity fails
+        assert!(matches!(
+            order.fill(&mut rng, 11, 17),
+            Err(TransactionError::InvalidOffer)
+        ));
+        let (offer_resource, returned_resource) = order.fill(&mut rng, 10, 17).unwrap();
+        let circuit = consume_intent_circuit(
+            &mut rng,
+            order,
+            offer_resource,
+            returned_resource,
+            11,
+            fill_window,
+        );
+        assert!(!verify(&circuit));
+    }
+
+    #[test]
+    fn test_halo2_limit_order_intent_partial_fill() {
+        let mut rng = OsRng;
+        let order = limit_order(&mut rng);
+        let fill_window = ValidityWindow::new(0, EXPIRES_AT);
+        let create_circuit = |offer_resource: Resource, returned_resource: Resource| {
+            consume_intent_circuit(
+                OsRng,
+                order.clone(),
+                offer_resource,
+                returned_resource,
+                4,
+                fill_window,
+            )
+        };
+
+        // Fill 4 token1 for 7 token2, the other 6 token1 are returned to the owner
+        let (offer_resource, returned_resource) = order.fill(&mut rng, 4, 7).unwrap();
+        assert_eq!(returned_resource.quantity, 6);
+        assert_eq!(returned_resource.get_label(), order.sell.encode_name());
+        assert!(verify(&create_circuit(offer_resource, returned_resource)));
+
+        // The returned quantity must be the rest of the sold quantity
+        let mut invalid_resource = returned_resource;
+        invalid_resource.quantity = 5;
+        assert!(!verify(&create_circuit(offer_resource, invalid_resource)));
+
+        // The returned resource must be the sold token
+        let invalid_resource = *Token::new("token3".to_string(), 6)
+            .create_random_output_token_resource(
+                &mut rng,
+                order.sell.resource().get_npk(),
+                &order.auth,
+            )
+            .resource();
+        assert!(!verify(&create_circuit(offer_resource, invalid_resource)));
+
+        // The returned resource must go to the owner
+        let mut invalid_resource = returned_resource;
+        invalid_resource.nk_container =
+            NullifierKeyContainer::from_npk(pallas::Base::random(&mut rng));
+        assert!(!verify(&create_circuit(offer_resource, invalid_resource)));
+        let mut invalid_resource = returned_resource;
+        invalid_resource.value = pallas::Base::random(&mut rng);
+        assert!(!verify(&create_circuit(offer_resource, invalid_resource)));
+    }
+
+    #[test]
+    fn test_halo2_limit_order_intent_expiry() {
+        let mut rng = OsRng;
+        let order = limit_order(&mut rng);
+        let (offer_resource, returned_resource) = order.fill(&mut rng, 4, 7).unwrap();
+        let create_circuit = |window: ValidityWindow| {
+            consume_intent_circuit(
+                OsRng,
+                order.clone(),
+                offer_resource,
+                returned_resource,
+                4,
+                window,
+            )
+        };
+        // The fill expires at the order expiry
+        let circuit = create_circuit(ValidityWindow::new(50, EXPIRES_AT));
+        assert_eq!(
+            circuit.get_public_inputs(OsRng).get_validity_window(),
+            Some(circuit.window)
+        );
+        assert!(verify(&circuit));
+
+        // The fill may be valid after the order expiry
+        assert!(!verify(&create_circuit(ValidityWindow::new(
+            50,
+            EXPIRES_AT + 1
+        ))));
+        assert!(!verify(&create_circuit(ValidityWindow::default())));
+    }
+}
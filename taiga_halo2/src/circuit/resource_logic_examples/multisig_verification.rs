@@ -0,0 +1,614 @@
+/// The multisig verification resource logic authorizes a resource with at least k of n schnorr
+/// signatures.
+/// The policy(threshold and public keys) is committed to a policy pk: policy_pk = Hash(policy) * G.
+/// The policy pk takes the place of the signer pk in the token value encoding, so the multisig
+/// logic can be used as the token auth dynamic logic instead of the signature verification logic.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::{
+            add::{AddChip, AddInstructions},
+            assign_free_advice,
+            comparison::{U64ComparisonChip, U64ComparisonInstructions},
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+        },
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::{
+            signature_verification::SchnorrSignature, token::TokenAuthorization,
+        },
+    },
+    constant::{
+        BaseFieldGenerators, TaigaFixedBasesFull, POSEIDON_TO_CURVE_INPUT_LEN, SETUP_PARAMS_MAP,
+    },
+    error::TransactionError,
+    proof::Proof,
+    resource::RandomSeed,
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    utils::{mod_r_p, poseidon_hash_n, poseidon_to_curve, read_base_field, read_point},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_gadgets::ecc::{
+    chip::EccChip, FixedPoint, FixedPointBaseField, NonIdentityPoint, ScalarFixed, ScalarVar,
+};
+use halo2_proofs::{
+    circuit::{floor_planner, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::{
+    arithmetic::CurveAffine,
+    group::{ff::PrimeField, Curve, Group, GroupEncoding},
+    pallas,
+};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+// The maximum number of signers in a policy.
+pub const MULTISIG_MAX_SIGNERS: usize = 5;
+// Hash(threshold || pk_1 || ... || pk_n)
+const POLICY_HASH_LEN: usize = 1 + MULTISIG_MAX_SIGNERS * 2;
+const MULTISIG_PADDING_PERSONALIZATION: &[u8; 16] = b"Taiga_MultisigPd";
+
+lazy_static! {
+    pub static ref MULTISIG_AUTH_VK: ResourceLogicVerifyingKey =
+        MultisigVerificationResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_MULTISIG_AUTH_VK: pallas::Base = MULTISIG_AUTH_VK.get_compressed();
+    // The unused signer slots are padded with a point whose discrete log is unknown.
+    pub static ref MULTISIG_PADDING_PK: pallas::Point =
+        poseidon_to_curve::<POSEIDON_TO_CURVE_INPUT_LEN>(&[
+            pallas::Base::from_u128(u128::from_le_bytes(*MULTISIG_PADDING_PERSONALIZATION)),
+            pallas::Base::zero(),
+        ]);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultisigPolicy {
+    threshold: u8,
+    // The public keys of the signers, padded with MULTISIG_PADDING_PK.
+    pks: [pallas::Point; MULTISIG_MAX_SIGNERS],
+}
+
+impl Default for MultisigPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: 1,
+            pks: [pallas::Point::generator(); MULTISIG_MAX_SIGNERS],
+        }
+    }
+}
+
+impl MultisigPolicy {
+    /// Create a k-of-n policy. The signer public keys must be distinct.
+    pub fn new(threshold: u8, pks: &[pallas::Point]) -> Result<Self, TransactionError> {
+        let distinct = pks
+            .iter()
+            .enumerate()
+            .all(|(i, pk)| !pks[..i].contains(pk) && *pk != *MULTISIG_PADDING_PK);
+        if threshold == 0
+            || threshold as usize > pks.len()
+            || pks.len() > MULTISIG_MAX_SIGNERS
+            || !distinct
+        {
+            return Err(TransactionError::InvalidMultisigPolicy);
+        }
+
+        let mut policy_pks = [*MULTISIG_PADDING_PK; MULTISIG_MAX_SIGNERS];
+        policy_pks[..pks.len()].copy_from_slice(pks);
+        Ok(Self {
+            threshold,
+            pks: policy_pks,
+        })
+    }
+
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    pub fn pks(&self) -> &[pallas::Point; MULTISIG_MAX_SIGNERS] {
+        &self.pks
+    }
+
+    pub fn get_policy_hash(&self) -> pallas::Base {
+        let mut message = [pallas::Base::zero(); POLICY_HASH_LEN];
+        message[0] = pallas::Base::from(self.threshold as u64);
+        self.pks.iter().enumerate().for_each(|(i, pk)| {
+            let pk_coord = pk.to_affine().coordinates().unwrap();
+            message[2 * i + 1] = *pk_coord.x();
+            message[2 * i + 2] = *pk_coord.y();
+        });
+        poseidon_hash_n(message)
+    }
+
+    // policy_pk = Hash(policy) * G
+    pub fn get_policy_pk(&self) -> pallas::Point {
+        pallas::Point::generator() * mod_r_p(self.get_policy_hash())
+    }
+
+    /// The token authorization controlled by the policy. The output resources are
+    /// encrypted to rcv_pk.
+    pub fn to_token_authorization(&self, rcv_pk: pallas::Point) -> TokenAuthorization {
+        TokenAuthorization {
+            pk: self.get_policy_pk(),
+            vk: *COMPRESSED_MULTISIG_AUTH_VK,
+            rcv_pk,
+        }
+    }
+}
+
+// MultisigVerificationResourceLogicCircuit uses the schnorr signatures.
+#[derive(Clone, Debug)]
+pub struct MultisigVerificationResourceLogicCircuit {
+    pub self_resource: ResourceExistenceWitness,
+    pub resource_logic_vk: pallas::Base,
+    pub policy: MultisigPolicy,
+    // The signature of the i-th signer in the policy, None if the signer doesn't sign.
+    pub signatures: [Option<SchnorrSignature>; MULTISIG_MAX_SIGNERS],
+    pub receiver_resource_logic_vk: pallas::Base,
+    // rcv_pk is only used to check the value encoding
+    pub rcv_pk: pallas::Point,
+}
+
+impl Default for MultisigVerificationResourceLogicCircuit {
+    fn default() -> Self {
+        Self {
+            self_resource: ResourceExistenceWitness::default(),
+            resource_logic_vk: pallas::Base::zero(),
+            policy: MultisigPolicy::default(),
+            signatures: Default::default(),
+            receiver_resource_logic_vk: pallas::Base::zero(),
+            rcv_pk: pallas::Point::generator(),
+        }
+    }
+}
+
+impl MultisigVerificationResourceLogicCircuit {
+    pub fn new(
+        self_resource: ResourceExistenceWitness,
+        resource_logic_vk: pallas::Base,
+        policy: MultisigPolicy,
+        signatures: [Option<SchnorrSignature>; MULTISIG_MAX_SIGNERS],
+        receiver_resource_logic_vk: pallas::Base,
+        rcv_pk: pallas::Point,
+    ) -> Self {
+        Self {
+            self_resource,
+            resource_logic_vk,
+            policy,
+            signatures,
+            receiver_resource_logic_vk,
+            rcv_pk,
+        }
+    }
+
+    /// Sign the resource with the secret keys of the signers. The signers are identified
+    /// by their public keys in the policy, and any number of them from the threshold up to all
+    /// of them can sign.
+    pub fn from_sks_and_sign<R: RngCore>(
+        mut rng: R,
+        self_resource: ResourceExistenceWitness,
+        resource_logic_vk: pallas::Base,
+        policy: MultisigPolicy,
+        sks: &[pallas::Scalar],
+        receiver_resource_logic_vk: pallas::Base,
+        rcv_pk: pallas::Point,
+    ) -> Result<Self, TransactionError> {
+        let message = vec![self_resource.get_root()];
+        let mut signatures: [Option<SchnorrSignature>; MULTISIG_MAX_SIGNERS] = Default::default();
+        for sk in sks {
+            let pk = pallas::Point::generator() * sk;
+            let idx = policy
+                .pks
+                .iter()
+                .position(|policy_pk| *policy_pk == pk)
+                .ok_or(TransactionError::InvalidMultisigSigner)?;
+            signatures[idx] = Some(SchnorrSignature::sign(&mut rng, *sk, message.clone()));
+        }
+        Ok(Self {
+            self_resource,
+            resource_logic_vk,
+            policy,
+            signatures,
+            receiver_resource_logic_vk,
+            rcv_pk,
+        })
+    }
+
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(
+            ResourceLogicRepresentation::MultisigVerification,
+            self.to_bytes(),
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for MultisigVerificationResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // Construct an ECC chip
+        let ecc_chip = EccChip::construct(config.ecc_config);
+        let add_chip = AddChip::construct(config.add_config, ());
+        let mul_chip = MulChip::construct(config.mul_config);
+
+        // Witness the policy
+        let threshold = assign_free_advice(
+            layouter.namespace(|| "witness threshold"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.policy.threshold as u64)),
+        )?;
+        let pks = self
+            .policy
+            .pks
+            .iter()
+            .map(|pk| {
+                NonIdentityPoint::new(
+                    ecc_chip.clone(),
+                    layouter.namespace(|| "witness signer pk"),
+                    Value::known(pk.to_affine()),
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // policy_pk = Hash(policy) * G
+        let policy_pk = {
+            let mut message = vec![threshold.clone()];
+            pks.iter().for_each(|pk| {
+                message.push(pk.inner().x());
+                message.push(pk.inner().y());
+            });
+            let policy_hash = poseidon_hash_gadget(
+                config.poseidon_config.clone(),
+                layouter.namespace(|| "policy hash"),
+                message.try_into().unwrap(),
+            )?;
+            let generator = FixedPointBaseField::from_inner(
+                ecc_chip.clone(),
+                BaseFieldGenerators::BaseGenerator,
+            );
+            generator.mul(
+                layouter.namespace(|| "policy_hash * generator"),
+                policy_hash,
+            )?
+        };
+
+        let auth_resource_logic_vk = assign_free_advice(
+            layouter.namespace(|| "witness auth resource_logic vk"),
+            config.advices[0],
+            Value::known(self.resource_logic_vk),
+        )?;
+        let receiver_resource_logic_vk = assign_free_advice(
+            layouter.namespace(|| "witness receiver resource_logic vk"),
+            config.advices[0],
+            Value::known(self.receiver_resource_logic_vk),
+        )?;
+
+        let rcv_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness rcv_pk"),
+            Value::known(self.rcv_pk.to_affine()),
+        )?;
+
+        // Decode the value, and check the value encoding
+        let encoded_value = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "value encoding"),
+            [
+                policy_pk.inner().x(),
+                policy_pk.inner().y(),
+                auth_resource_logic_vk,
+                receiver_resource_logic_vk,
+                rcv_pk.inner().x(),
+                rcv_pk.inner().y(),
+            ],
+        )?;
+
+        layouter.assign_region(
+            || "check value encoding",
+            |mut region| {
+                region.constrain_equal(encoded_value.cell(), self_resource.resource.value.cell())
+            },
+        )?;
+
+        // Verify the signatures of the signers who sign
+        let mut signed_num = None;
+        for (pk, signature) in pks.iter().zip(self.signatures.iter()) {
+            let is_signed = assign_free_advice(
+                layouter.namespace(|| "witness is_signed"),
+                config.advices[0],
+                Value::known(pallas::Base::from(signature.is_some())),
+            )?;
+
+            // is_signed is boolean: is_signed * is_signed = is_signed
+            let is_signed_square = mul_chip.mul(
+                layouter.namespace(|| "is_signed * is_signed"),
+                &is_signed,
+                &is_signed,
+            )?;
+            layouter.assign_region(
+                || "is_signed is boolean",
+                |mut region| region.constrain_equal(is_signed_square.cell(), is_signed.cell()),
+            )?;
+
+            // Use the default signature as a dummy witness if the signer doesn't sign
+            let signature = signature.clone().unwrap_or_default();
+            let r = NonIdentityPoint::new(
+                ecc_chip.clone(),
+                layouter.namespace(|| "witness r"),
+                Value::known(signature.r().to_affine()),
+            )?;
+            let s_scalar = ScalarFixed::new(
+                ecc_chip.clone(),
+                layouter.namespace(|| "witness s"),
+                Value::known(signature.s()),
+            )?;
+
+            // Verify: s*G = R + Hash(r||P||m)*P if is_signed
+            // s*G
+            let generator =
+                FixedPoint::from_inner(ecc_chip.clone(), TaigaFixedBasesFull::BaseGenerator);
+            let (s_g, _) =
+                generator.mul(layouter.namespace(|| "s_scalar * generator"), &s_scalar)?;
+
+            // Hash(r||P||m)
+            let h_scalar = {
+                let h = poseidon_hash_gadget(
+                    config.poseidon_config.clone(),
+                    layouter.namespace(|| "Poseidon_hash(r, P, m)"),
+                    [
+                        r.inner().x(),
+                        r.inner().y(),
+                        pk.inner().x(),
+                        pk.inner().y(),
+                        self_resource.resource_merkle_root.clone(),
+                    ],
+                )?;
+
+                ScalarVar::from_base(
+                    ecc_chip.clone(),
+                    layouter.namespace(|| "ScalarVar from_base"),
+                    &h,
+                )?
+            };
+
+            // Hash(r||P||m)*P
+            let (h_p, _) = pk.mul(layouter.namespace(|| "hP"), h_scalar)?;
+
+            // R + Hash(r||P||m)*P
+            let rhs = r.add(layouter.namespace(|| "R + Hash(r||P||m)*P"), &h_p)?;
+
+            layouter.assign_region(
+                || "conditional equal: s*G = R + Hash(r||P||m)*P",
+                |mut region| {
+                    config.conditional_equal_config.assign_region(
+                        &is_signed,
+                        &s_g.inner().x(),
+                        &rhs.inner().x(),
+                        0,
+                        &mut region,
+                    )?;
+                    config.conditional_equal_config.assign_region(
+                        &is_signed,
+                        &s_g.inner().y(),
+                        &rhs.inner().y(),
+                        1,
+                        &mut region,
+                    )
+                },
+            )?;
+
+            signed_num = match signed_num {
+                None => Some(is_signed),
+                Some(num) => Some(add_chip.add(
+                    layouter.namespace(|| "signed_num + is_signed"),
+                    &num,
+                    &is_signed,
+                )?),
+            };
+        }
+
+        // The number of valid signatures reaches the threshold
+        let signed_num = signed_num.unwrap();
+        let comparison_chip = U64ComparisonChip::construct(config.comparison_config);
+        comparison_chip.less_than_or_equal(
+            layouter.namespace(|| "threshold <= signed_num"),
+            &threshold,
+            &signed_num,
+        )?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+resource_logic_circuit_impl!(MultisigVerificationResourceLogicCircuit);
+resource_logic_verifying_info_impl!(MultisigVerificationResourceLogicCircuit);
+
+impl BorshSerialize for MultisigVerificationResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        writer.write_all(&self.resource_logic_vk.to_repr())?;
+        self.policy.serialize(writer)?;
+        for signature in self.signatures.iter() {
+            signature.serialize(writer)?;
+        }
+        writer.write_all(&self.receiver_resource_logic_vk.to_repr())?;
+        writer.write_all(&self.rcv_pk.to_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for MultisigVerificationResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let resource_logic_vk = read_base_field(reader)?;
+        let policy = MultisigPolicy::deserialize_reader(reader)?;
+        let mut signatures: [Option<SchnorrSignature>; MULTISIG_MAX_SIGNERS] = Default::default();
+        for signature in signatures.iter_mut() {
+            *signature = Option::<SchnorrSignature>::deserialize_reader(reader)?;
+        }
+        let receiver_resource_logic_vk = read_base_field(reader)?;
+        let rcv_pk = read_point(reader)?;
+        Ok(Self {
+            self_resource,
+            resource_logic_vk,
+            policy,
+            signatures,
+            receiver_resource_logic_vk,
+            rcv_pk,
+        })
+    }
+}
+
+impl BorshSerialize for MultisigPolicy {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.threshold.serialize(writer)?;
+        for pk in self.pks.iter() {
+            writer.write_all(&pk.to_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for MultisigPolicy {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let threshold = u8::deserialize_reader(reader)?;
+        let mut pks = [pallas::Point::identity(); MULTISIG_MAX_SIGNERS];
+        for pk in pks.iter_mut() {
+            *pk = read_point(reader)?;
+        }
+        Ok(Self { threshold, pks })
+    }
+}
+
+#[test]
+fn test_halo2_multisig_verification_resource_logic_circuit() {
+    use crate::circuit::resource_logic_examples::receiver_resource_logic::COMPRESSED_RECEIVER_VK;
+    use crate::constant::{RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE, TAIGA_RESOURCE_TREE_DEPTH};
+    use crate::merkle_tree::LR;
+    use crate::resource::tests::random_resource;
+    use halo2_proofs::{arithmetic::Field, dev::MockProver};
+
+    let mut rng = OsRng;
+    // 2-of-3 policy
+    let sks: Vec<pallas::Scalar> = (0..3).map(|_| pallas::Scalar::random(&mut rng)).collect();
+    let pks: Vec<pallas::Point> = sks
+        .iter()
+        .map(|sk| pallas::Point::generator() * sk)
+        .collect();
+    let policy = MultisigPolicy::new(2, &pks).unwrap();
+    assert!(MultisigPolicy::new(4, &pks).is_err());
+    assert!(MultisigPolicy::new(1, &[pks[0], pks[0]]).is_err());
+
+    let auth_vk = pallas::Base::random(&mut rng);
+    let rcv_pk = pallas::Point::random(&mut rng);
+    let mut auth = policy.to_token_authorization(rcv_pk);
+    auth.vk = auth_vk;
+    let mut resource = random_resource(&mut rng);
+    resource.value = auth.to_value();
+    let merkle_path = [(pallas::Base::zero(), LR::R); TAIGA_RESOURCE_TREE_DEPTH];
+    let resource_witness = ResourceExistenceWitness::new(resource, merkle_path);
+    let create_circuit = |signer_sks: &[pallas::Scalar]| {
+        MultisigVerificationResourceLogicCircuit::from_sks_and_sign(
+            OsRng,
+            resource_witness,
+            auth_vk,
+            policy.clone(),
+            signer_sks,
+            *COMPRESSED_RECEIVER_VK,
+            rcv_pk,
+        )
+        .unwrap()
+    };
+    let verify = |circuit: &MultisigVerificationResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+    };
+
+    // Test serialization
+    let circuit = {
+        let circuit_bytes = create_circuit(&[sks[0], sks[2]]).to_bytes();
+        MultisigVerificationResourceLogicCircuit::from_bytes(&circuit_bytes)
+    };
+    assert_eq!(verify(&circuit), Ok(()));
+
+    // More signatures than the threshold
+    assert_eq!(verify(&create_circuit(&sks)), Ok(()));
+
+    // Not enough signatures
+    assert!(verify(&create_circuit(&[sks[1]])).is_err());
+
+    // The signer is not in the policy
+    assert!(matches!(
+        MultisigVerificationResourceLogicCircuit::from_sks_and_sign(
+            OsRng,
+            resource_witness,
+            auth_vk,
+            policy.clone(),
+            &[pallas::Scalar::random(&mut rng)],
+            *COMPRESSED_RECEIVER_VK,
+            rcv_pk,
+        ),
+        Err(TransactionError::InvalidMultisigSigner)
+    ));
+
+    // An invalid signature
+    let mut circuit = create_circuit(&[sks[0], sks[1]]);
+    circuit.signatures[1] = Some(SchnorrSignature::sign(
+        &mut rng,
+        pallas::Scalar::random(&mut rng),
+        vec![resource_witness.get_root()],
+    ));
+    assert!(verify(&circuit).is_err());
+}
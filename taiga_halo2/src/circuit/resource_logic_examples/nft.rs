@@ -0,0 +1,753 @@
+/// The NFT resource logic represents unique assets. Every NFT resource has quantity 1, and the
+/// label commits to the collection, the issuer and the token id, so each token id is a kind on
+/// its own and can't be merged or split.
+/// An NFT is minted by consuming the mint resource, an ephemeral input resource authorized by the
+/// issuer. Like the issuance resource of the issued token, the mint resource is determined by the
+/// label, so the mint nullifier can only be revealed once per token id and the NFT is unique
+/// whatever the metadata is: the value data of the mint resource is zero.
+/// The value of the other NFT resources commits to the token authorization and the metadata hash,
+/// see `TokenAuthorization::to_value_with_data`. The NFT is owned and transferred with the
+/// signature verification and receiver resource logics, which take the metadata hash as the value
+/// data. The consumed NFT creates the transferred NFT in the same partial transaction, and the
+/// metadata hash is carried unchanged.
+use crate::{
+    circuit::{
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            conditional_equal::conditional_equal,
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+            sub::{SubChip, SubInstructions},
+        },
+        integrity::load_resource,
+        merkle_circuit::MerklePoseidonChip,
+        resource_commitment::ResourceCommitChip,
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::{
+            receiver_resource_logic::COMPRESSED_RECEIVER_VK,
+            signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+            token::{
+                issuance::{
+                    constrain_issued_resource, create_issued_resource_auth_logic,
+                    create_issued_resource_receiver_logic, get_issued_resource_public_inputs,
+                },
+                TokenAuthorization, TokenName,
+            },
+        },
+    },
+    constant::{SETUP_PARAMS_MAP, TAIGA_RESOURCE_TREE_DEPTH},
+    error::TransactionError,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::LR,
+    nullifier::Nullifier,
+    proof::Proof,
+    resource::{RandomSeed, Resource, ResourceLogics},
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    utils::{poseidon_hash, poseidon_hash_n, read_base_field, read_point, to_field_elements},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use ff::Field;
+use group::{Curve, Group, GroupEncoding};
+use halo2_gadgets::ecc::{chip::EccChip, NonIdentityPoint};
+use halo2_proofs::{
+    circuit::{floor_planner, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::arithmetic::CurveAffine;
+use pasta_curves::{group::ff::PrimeField, pallas};
+use rand::{rngs::OsRng, RngCore};
+
+lazy_static! {
+    pub static ref NFT_VK: ResourceLogicVerifyingKey =
+        NftResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_NFT_VK: pallas::Base = NFT_VK.get_compressed();
+}
+
+#[derive(Clone, Debug)]
+pub struct Nft {
+    collection: TokenName,
+    issuer_pk: pallas::Point,
+    token_id: pallas::Base,
+    metadata_hash: pallas::Base,
+}
+
+impl Nft {
+    pub fn new(
+        collection: String,
+        issuer_pk: pallas::Point,
+        token_id: pallas::Base,
+        metadata_hash: pallas::Base,
+    ) -> Self {
+        Self {
+            collection: TokenName::new(collection),
+            issuer_pk,
+            token_id,
+            metadata_hash,
+        }
+    }
+
+    /// The issuer authorizes the mint with the authorization key of the spending key.
+    pub fn from_issuer_key(
+        collection: String,
+        issuer_sk: &SpendingKey,
+        token_id: pallas::Base,
+        metadata_hash: pallas::Base,
+    ) -> Self {
+        Self::new(collection, issuer_sk.get_auth_pk(), token_id, metadata_hash)
+    }
+
+    // Hash the metadata bytes, e.g. a json document or an uri, to a field element.
+    // The length is absorbed first to separate the messages with trailing zeros.
+    pub fn hash_metadata(metadata: &[u8]) -> pallas::Base {
+        to_field_elements(metadata)
+            .into_iter()
+            .fold(pallas::Base::from(metadata.len() as u64), poseidon_hash)
+    }
+
+    pub fn collection(&self) -> &TokenName {
+        &self.collection
+    }
+
+    pub fn issuer_pk(&self) -> pallas::Point {
+        self.issuer_pk
+    }
+
+    pub fn token_id(&self) -> pallas::Base {
+        self.token_id
+    }
+
+    pub fn metadata_hash(&self) -> pallas::Base {
+        self.metadata_hash
+    }
+
+    // label = poseidon_hash(collection || issuer_pk || token_id)
+    pub fn encode_label(&self) -> pallas::Base {
+        let issuer_pk_coord = self.issuer_pk.to_affine().coordinates().unwrap();
+        poseidon_hash_n([
+            self.collection.encode(),
+            *issuer_pk_coord.x(),
+            *issuer_pk_coord.y(),
+            self.token_id,
+        ])
+    }
+
+    // value = poseidon_hash(auth_value || metadata_hash)
+    pub fn encode_value(&self, auth: &TokenAuthorization) -> pallas::Base {
+        auth.to_value_with_data(self.metadata_hash)
+    }
+
+    /// The mint resource is consumed by the issuer's signature.
+    pub fn issuer_auth(&self) -> TokenAuthorization {
+        TokenAuthorization::new(self.issuer_pk, *COMPRESSED_TOKEN_AUTH_VK)
+    }
+
+    /// Create the mint resource. All fields are determined by the label, the value data is zero.
+    pub fn create_mint_resource(&self) -> NftResource {
+        let label = self.encode_label();
+        let resource = Resource::new_input_resource(
+            *COMPRESSED_NFT_VK,
+            label,
+            self.issuer_auth().to_value_with_data(pallas::Base::zero()),
+            1,
+            pallas::Base::zero(),
+            Nullifier::from(label),
+            true,
+            pallas::Base::zero(),
+        );
+
+        NftResource {
+            nft: self.clone(),
+            resource,
+        }
+    }
+
+    pub fn create_random_input_resource_from_key<R: RngCore>(
+        &self,
+        mut rng: R,
+        sk: &SpendingKey,
+    ) -> NftResource {
+        let auth = TokenAuthorization::from_address(&sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+        let resource = Resource::new_input_resource(
+            *COMPRESSED_NFT_VK,
+            self.encode_label(),
+            self.encode_value(&auth),
+            1,
+            sk.get_nk(),
+            Nullifier::random(&mut rng),
+            false,
+            pallas::Base::random(&mut rng),
+        );
+
+        NftResource {
+            nft: self.clone(),
+            resource,
+        }
+    }
+
+    pub fn create_random_output_resource_to_address<R: RngCore>(
+        &self,
+        mut rng: R,
+        address: &PaymentAddress,
+    ) -> NftResource {
+        let auth = TokenAuthorization::from_address(address);
+        let resource = Resource::new_output_resource(
+            *COMPRESSED_NFT_VK,
+            self.encode_label(),
+            self.encode_value(&auth),
+            1,
+            address.get_npk(),
+            false,
+            pallas::Base::random(&mut rng),
+        );
+
+        NftResource {
+            nft: self.clone(),
+            resource,
+        }
+    }
+}
+
+impl Default for Nft {
+    fn default() -> Self {
+        Self {
+            collection: TokenName::new("Collection_name".to_string()),
+            issuer_pk: pallas::Point::generator(),
+            token_id: pallas::Base::zero(),
+            metadata_hash: pallas::Base::zero(),
+        }
+    }
+}
+
+impl BorshSerialize for Nft {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.collection.serialize(writer)?;
+        writer.write_all(&self.issuer_pk.to_bytes())?;
+        writer.write_all(&self.token_id.to_repr())?;
+        writer.write_all(&self.metadata_hash.to_repr())?;
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Nft {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let collection = TokenName::deserialize_reader(reader)?;
+        let issuer_pk = read_point(reader)?;
+        let token_id = read_base_field(reader)?;
+        let metadata_hash = read_base_field(reader)?;
+        Ok(Self {
+            collection,
+            issuer_pk,
+            token_id,
+            metadata_hash,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NftResource {
+    pub nft: Nft,
+    pub resource: Resource,
+}
+
+impl std::ops::Deref for NftResource {
+    type Target = Resource;
+
+    fn deref(&self) -> &Self::Target {
+        &self.resource
+    }
+}
+
+impl NftResource {
+    pub fn nft(&self) -> &Nft {
+        &self.nft
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    // The value data is zero for the mint resource and the metadata hash otherwise
+    fn value_data(&self) -> pallas::Base {
+        if self.resource.is_ephemeral {
+            pallas::Base::zero()
+        } else {
+            self.nft.metadata_hash
+        }
+    }
+
+    /// The transferred NFT is the output resource created from a consumed NFT, and the auth of
+    /// its owner. It's None for the mint resource.
+    pub fn generate_input_resource_logics<R: RngCore>(
+        &self,
+        mut rng: R,
+        auth: TokenAuthorization,
+        auth_sk: pallas::Scalar,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+        transferred: Option<(ResourceExistenceWitness, TokenAuthorization)>,
+    ) -> ResourceLogics {
+        let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
+        let (transferred_resource, transferred_auth) = transferred.unwrap_or_default();
+        let nft_resource_logic = NftResourceLogicCircuit {
+            self_resource,
+            nft: self.nft.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            transferred_resource,
+            transferred_auth,
+            rseed: RandomSeed::random(&mut rng),
+        };
+
+        let nft_auth_resource_logic = create_issued_resource_auth_logic(
+            &mut rng,
+            self_resource,
+            auth,
+            auth_sk,
+            Some(self.value_data()),
+        );
+
+        ResourceLogics::new(
+            Box::new(nft_resource_logic),
+            vec![Box::new(nft_auth_resource_logic)],
+        )
+    }
+
+    pub fn generate_output_resource_logics<R: RngCore>(
+        &self,
+        mut rng: R,
+        auth: TokenAuthorization,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> ResourceLogics {
+        let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
+        let nft_resource_logic = NftResourceLogicCircuit {
+            self_resource,
+            nft: self.nft.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            transferred_resource: ResourceExistenceWitness::default(),
+            transferred_auth: TokenAuthorization::default(),
+            rseed: RandomSeed::random(&mut rng),
+        };
+
+        let receiver_resource_logic = create_issued_resource_receiver_logic(
+            &mut rng,
+            self_resource,
+            auth,
+            Some(self.nft.metadata_hash),
+        );
+
+        ResourceLogics::new(
+            Box::new(nft_resource_logic),
+            vec![Box::new(receiver_resource_logic)],
+        )
+    }
+}
+
+// NftResourceLogicCircuit
+#[derive(Clone, Debug, Default)]
+pub struct NftResourceLogicCircuit {
+    self_resource: ResourceExistenceWitness,
+    // The nft goes to label, and the metadata hash goes to value.
+    pub nft: Nft,
+    // The auth goes to value and defines how to consume and create the resource.
+    pub auth: TokenAuthorization,
+    pub receiver_resource_logic_vk: pallas::Base,
+    // constraints will be enabled only when consuming a non-mint NFT, otherwise it's a dummy one
+    pub transferred_resource: ResourceExistenceWitness,
+    // The auth of the transferred NFT owner
+    pub transferred_auth: TokenAuthorization,
+    // rseed is to generate the randomness for resource_logic commitment
+    pub rseed: RandomSeed,
+}
+
+impl NftResourceLogicCircuit {
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(ResourceLogicRepresentation::Nft, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for NftResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // Construct an ECC chip
+        let ecc_chip = EccChip::construct(config.ecc_config.clone());
+
+        // Decode the label, and check the label encoding
+        let collection = assign_free_advice(
+            layouter.namespace(|| "witness collection"),
+            config.advices[0],
+            Value::known(self.nft.collection.encode()),
+        )?;
+        let issuer_pk = NonIdentityPoint::new(
+            ecc_chip,
+            layouter.namespace(|| "witness issuer pk"),
+            Value::known(self.nft.issuer_pk.to_affine()),
+        )?;
+        let token_id = assign_free_advice(
+            layouter.namespace(|| "witness token id"),
+            config.advices[0],
+            Value::known(self.nft.token_id),
+        )?;
+        let metadata_hash = assign_free_advice(
+            layouter.namespace(|| "witness metadata hash"),
+            config.advices[0],
+            Value::known(self.nft.metadata_hash),
+        )?;
+        let encoded_label = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "label encoding"),
+            [
+                collection,
+                issuer_pk.inner().x(),
+                issuer_pk.inner().y(),
+                token_id,
+            ],
+        )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| {
+                region.constrain_equal(encoded_label.cell(), self_resource.resource.label.cell())
+            },
+        )?;
+
+        // The NFT is not divisible
+        let constant_one = assign_free_constant(
+            layouter.namespace(|| "one"),
+            config.advices[0],
+            pallas::Base::one(),
+        )?;
+        layouter.assign_region(
+            || "check quantity",
+            |mut region| {
+                region.constrain_equal(constant_one.cell(), self_resource.resource.quantity.cell())
+            },
+        )?;
+
+        // The value data is zero for the mint resource, the ephemeral input resource, and the
+        // metadata hash otherwise.
+        let mul_chip = MulChip::construct(config.mul_config.clone());
+        let is_mint = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "is_input * is_ephemeral"),
+            &self_resource.is_input,
+            &self_resource.resource.is_ephemeral,
+        )?;
+        let constant_zero = assign_free_constant(
+            layouter.namespace(|| "zero"),
+            config.advices[0],
+            pallas::Base::zero(),
+        )?;
+        let value_data = layouter.assign_region(
+            || "conditional select: value data",
+            |mut region| {
+                config.conditional_select_config.assign_region(
+                    &is_mint,
+                    &constant_zero,
+                    &metadata_hash,
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+
+        // The mint resource is authorized by the issuer, and the other fields of the mint
+        // resource are determined by the label, so that the mint nullifier is unique.
+        constrain_issued_resource(
+            &mut layouter,
+            &config,
+            &self_resource,
+            &issuer_pk,
+            &self.auth,
+            self.receiver_resource_logic_vk,
+            Some(&value_data),
+            &self.rseed,
+        )?;
+
+        // The consumed NFT, except the mint resource, creates the transferred NFT with the same
+        // metadata hash in the same partial transaction
+        let sub_chip = SubChip::construct(config.sub_config.clone(), ());
+        let is_transfer = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "is_input - is_mint"),
+            &self_resource.is_input,
+            &is_mint,
+        )?;
+        let transferred_resource = load_resource(
+            layouter.namespace(|| "load the transferred resource"),
+            config.advices,
+            ResourceCommitChip::construct(config.resource_commit_config.clone()),
+            config.conditional_select_config,
+            MerklePoseidonChip::construct(config.merkle_config.clone()),
+            &self.transferred_resource,
+        )?;
+        let transferred_auth_value = assign_free_advice(
+            layouter.namespace(|| "witness transferred auth value"),
+            config.advices[0],
+            Value::known(self.transferred_auth.to_value()),
+        )?;
+        let transferred_value = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "transferred value encoding"),
+            [transferred_auth_value, metadata_hash],
+        )?;
+        for (name, lhs, rhs) in [
+            (
+                "transferred root",
+                &self_resource.resource_merkle_root,
+                &transferred_resource.resource_merkle_root,
+            ),
+            (
+                "transferred is_input",
+                &constant_zero,
+                &transferred_resource.is_input,
+            ),
+            (
+                "transferred logic",
+                &self_resource.resource.logic,
+                &transferred_resource.resource.logic,
+            ),
+            (
+                "transferred label",
+                &self_resource.resource.label,
+                &transferred_resource.resource.label,
+            ),
+            (
+                "transferred value",
+                &transferred_value,
+                &transferred_resource.resource.value,
+            ),
+        ] {
+            conditional_equal(
+                &mut layouter,
+                &config.conditional_equal_config,
+                name,
+                &is_transfer,
+                lhs,
+                rhs,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, rng: impl RngCore) -> ResourceLogicPublicInputs {
+        get_issued_resource_public_inputs(
+            self,
+            &self.auth,
+            self.receiver_resource_logic_vk,
+            &self.rseed,
+            rng,
+        )
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+resource_logic_circuit_impl!(NftResourceLogicCircuit);
+resource_logic_verifying_info_impl!(NftResourceLogicCircuit);
+
+impl BorshSerialize for NftResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.nft.serialize(writer)?;
+        self.auth.serialize(writer)?;
+        writer.write_all(&self.receiver_resource_logic_vk.to_repr())?;
+        self.transferred_resource.serialize(writer)?;
+        self.transferred_auth.serialize(writer)?;
+        self.rseed.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for NftResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let nft = Nft::deserialize_reader(reader)?;
+        let auth = TokenAuthorization::deserialize_reader(reader)?;
+        let receiver_resource_logic_vk = read_base_field(reader)?;
+        let transferred_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let transferred_auth = TokenAuthorization::deserialize_reader(reader)?;
+        let rseed = RandomSeed::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            nft,
+            auth,
+            receiver_resource_logic_vk,
+            transferred_resource,
+            transferred_auth,
+            rseed,
+        })
+    }
+}
+
+#[test]
+fn test_halo2_nft_resource_logic_circuit() {
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use crate::resource_tree::ResourceMerkleTreeLeaves;
+    use halo2_proofs::dev::MockProver;
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    let issuer_sk = SpendingKey::random(&mut rng);
+    let nft = Nft::from_issuer_key(
+        "punks".to_string(),
+        &issuer_sk,
+        pallas::Base::from(7u64),
+        Nft::hash_metadata(b"ipfs://punk-7"),
+    );
+    // The resource is an input if the first sibling is on the right
+    let input_path = [(pallas::Base::zero(), LR::R); TAIGA_RESOURCE_TREE_DEPTH];
+    let output_path = [(pallas::Base::zero(), LR::L); TAIGA_RESOURCE_TREE_DEPTH];
+    let create_circuit = |self_resource: ResourceExistenceWitness, auth: TokenAuthorization| {
+        NftResourceLogicCircuit {
+            self_resource,
+            nft: nft.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            transferred_resource: ResourceExistenceWitness::default(),
+            transferred_auth: TokenAuthorization::default(),
+            rseed: RandomSeed::random(&mut OsRng),
+        }
+    };
+    let verify = |circuit: &NftResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+    };
+
+    // The mint resource
+    let mint_resource = *nft.create_mint_resource().resource();
+    let mint_witness = |resource| ResourceExistenceWitness::new(resource, input_path);
+    let circuit = {
+        let circuit_bytes =
+            create_circuit(mint_witness(mint_resource), nft.issuer_auth()).to_bytes();
+        NftResourceLogicCircuit::from_bytes(&circuit_bytes)
+    };
+    assert_eq!(verify(&circuit), Ok(()));
+
+    // A different mint resource fails
+    let mut invalid_resource = mint_resource;
+    invalid_resource.rseed = pallas::Base::random(&mut rng);
+    assert!(verify(&create_circuit(
+        mint_witness(invalid_resource),
+        nft.issuer_auth()
+    ))
+    .is_err());
+
+    // The mint nullifier doesn't depend on the metadata, the token id can only be minted once
+    let other_metadata_nft = Nft::from_issuer_key(
+        "punks".to_string(),
+        &issuer_sk,
+        pallas::Base::from(7u64),
+        Nft::hash_metadata(b"ipfs://another-punk-7"),
+    );
+    let other_mint_resource = *other_metadata_nft.create_mint_resource().resource();
+    assert_eq!(other_mint_resource.get_label(), mint_resource.get_label());
+    assert_eq!(other_mint_resource.get_nf(), mint_resource.get_nf());
+
+    // Create the NFT
+    let owner_sk = SpendingKey::random(&mut rng);
+    let owner_address = owner_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let owner_auth = TokenAuthorization::from_address(&owner_address);
+    let output_resource = *nft
+        .create_random_output_resource_to_address(&mut rng, &owner_address)
+        .resource();
+    let output_witness = |resource| ResourceExistenceWitness::new(resource, output_path);
+    assert_eq!(
+        verify(&create_circuit(output_witness(output_resource), owner_auth)),
+        Ok(())
+    );
+
+    // The NFT is not divisible
+    let mut invalid_resource = output_resource;
+    invalid_resource.quantity = 2;
+    assert!(verify(&create_circuit(
+        output_witness(invalid_resource),
+        owner_auth
+    ))
+    .is_err());
+
+    // The value must commit to the metadata hash
+    let mut invalid_resource = output_resource;
+    invalid_resource.value = owner_auth.to_value();
+    assert!(verify(&create_circuit(
+        output_witness(invalid_resource),
+        owner_auth
+    ))
+    .is_err());
+
+    // The consumed NFT creates the transferred NFT with the same metadata hash
+    let input_resource = *nft
+        .create_random_input_resource_from_key(&mut rng, &owner_sk)
+        .resource();
+    let receiver_address = SpendingKey::random(&mut rng).get_address(*COMPRESSED_TOKEN_AUTH_VK);
+    let receiver_auth = TokenAuthorization::from_address(&receiver_address);
+    let create_transfer_circuit = |transferred_resource: Resource| {
+        let input_nf = input_resource.get_nf().unwrap().inner();
+        let transferred_cm = transferred_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![input_nf, transferred_cm]);
+        let witness = |resource: Resource, id: pallas::Base| {
+            ResourceExistenceWitness::new(resource, resource_merkle_tree.generate_path(id).unwrap())
+        };
+        NftResourceLogicCircuit {
+            transferred_resource: witness(transferred_resource, transferred_cm),
+            transferred_auth: receiver_auth,
+            ..create_circuit(witness(input_resource, input_nf), owner_auth)
+        }
+    };
+    let transferred_resource = *nft
+        .create_random_output_resource_to_address(&mut rng, &receiver_address)
+        .resource();
+    assert_eq!(
+        verify(&create_transfer_circuit(transferred_resource)),
+        Ok(())
+    );
+
+    // The metadata hash can't be changed on transfer
+    let transferred_resource = *other_metadata_nft
+        .create_random_output_resource_to_address(&mut rng, &receiver_address)
+        .resource();
+    assert!(verify(&create_transfer_circuit(transferred_resource)).is_err());
+
+    // The transferred NFT must be the same NFT
+    let other_nft = Nft::from_issuer_key(
+        "punks".to_string(),
+        &issuer_sk,
+        pallas::Base::from(8u64),
+        nft.metadata_hash(),
+    );
+    let transferred_resource = *other_nft
+        .create_random_output_resource_to_address(&mut rng, &receiver_address)
+        .resource();
+    assert!(verify(&create_transfer_circuit(transferred_resource)).is_err());
+
+    // The consumed NFT without the transferred NFT fails
+    let input_witness = ResourceExistenceWitness::new(input_resource, input_path);
+    assert!(verify(&create_circuit(input_witness, owner_auth)).is_err());
+}
@@ -1,13 +1,17 @@
-/// The intent can be satisfied with two conditions. For instance, Alice has 5
-/// BTC and desires either 1 Dolphin or 2 Monkeys. Then Alice creates an intent
-/// using the "or relation".
+/// The intent can be satisfied with any one of a bounded list of conditions. For
+/// instance, Alice has 5 BTC and desires either 1 Dolphin, 2 Monkeys or 3 Pandas.
+/// Then Alice creates an intent using the "or relation".
+///
+/// The alternatives are committed in the label as the root of a poseidon merkle
+/// tree with up to `OR_RELATION_INTENT_MAX_ALTERNATIVES` leaves, and the desired
+/// resource proves its (token name, quantity) is one of the leaves.
 ///
 use crate::{
     circuit::{
         blake2s::publicize_default_dynamic_resource_logic_commitments,
         gadgets::{assign_free_advice, assign_free_constant, poseidon_hash::poseidon_hash_gadget},
         integrity::load_resource,
-        merkle_circuit::MerklePoseidonChip,
+        merkle_circuit::{merkle_poseidon_gadget, MerklePoseidonChip},
         resource_commitment::ResourceCommitChip,
         resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
         resource_logic_circuit::{
@@ -18,14 +22,14 @@ use crate::{
     },
     constant::SETUP_PARAMS_MAP,
     error::TransactionError,
+    merkle_tree::LR,
     nullifier::Nullifier,
     proof::Proof,
     resource::{RandomSeed, Resource},
     resource_logic_commitment::ResourceLogicCommitment,
     resource_logic_vk::ResourceLogicVerifyingKey,
     resource_tree::ResourceExistenceWitness,
-    utils::poseidon_hash_n,
-    utils::read_base_field,
+    utils::{poseidon_hash, poseidon_hash_n, read_base_field},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use halo2_proofs::arithmetic::Field;
@@ -45,6 +49,78 @@ lazy_static! {
         OR_RELATION_INTENT_VK.get_compressed();
 }
 
+pub const OR_RELATION_INTENT_ALTERNATIVES_TREE_DEPTH: usize = 3;
+pub const OR_RELATION_INTENT_MAX_ALTERNATIVES: usize =
+    1 << OR_RELATION_INTENT_ALTERNATIVES_TREE_DEPTH;
+
+// The alternatives of the intent, committed as a poseidon merkle tree of (token name, token quantity)
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct OrRelationAlternatives(Vec<Token>);
+
+impl OrRelationAlternatives {
+    pub fn new(alternatives: Vec<Token>) -> Self {
+        assert!(
+            !alternatives.is_empty() && alternatives.len() <= OR_RELATION_INTENT_MAX_ALTERNATIVES,
+            "The number of alternatives must be in [1, OR_RELATION_INTENT_MAX_ALTERNATIVES]"
+        );
+        OrRelationAlternatives(alternatives)
+    }
+
+    pub fn inner(&self) -> &[Token] {
+        &self.0
+    }
+
+    pub fn encode_alternative(token: &Token) -> pallas::Base {
+        poseidon_hash(token.encode_name(), token.encode_quantity())
+    }
+
+    fn leaves(&self) -> Vec<pallas::Base> {
+        let mut leaves: Vec<pallas::Base> = self.0.iter().map(Self::encode_alternative).collect();
+        leaves.resize(OR_RELATION_INTENT_MAX_ALTERNATIVES, pallas::Base::zero());
+        leaves
+    }
+
+    pub fn root(&self) -> pallas::Base {
+        let mut cur_layer = self.leaves();
+        while cur_layer.len() > 1 {
+            cur_layer = cur_layer
+                .chunks(2)
+                .map(|pair| poseidon_hash(pair[0], pair[1]))
+                .collect();
+        }
+        cur_layer[0]
+    }
+
+    // Generate the merkle path of the alternative matching the token name and quantity
+    pub fn generate_path(
+        &self,
+        name: pallas::Base,
+        quantity: pallas::Base,
+    ) -> Option<[(pallas::Base, LR); OR_RELATION_INTENT_ALTERNATIVES_TREE_DEPTH]> {
+        let leaf = poseidon_hash(name, quantity);
+        let mut cur_layer = self.leaves();
+        let mut position = self
+            .0
+            .iter()
+            .position(|token| Self::encode_alternative(token) == leaf)?;
+        let mut merkle_path = Vec::new();
+        while cur_layer.len() > 1 {
+            let sibling_lr = LR::from(position % 2 != 0);
+            let sibling_value = match sibling_lr {
+                LR::L => cur_layer[position - 1],
+                LR::R => cur_layer[position + 1],
+            };
+            merkle_path.push((sibling_value, sibling_lr));
+            cur_layer = cur_layer
+                .chunks(2)
+                .map(|pair| poseidon_hash(pair[0], pair[1]))
+                .collect();
+            position /= 2;
+        }
+        Some(merkle_path.try_into().unwrap())
+    }
+}
+
 // OrRelationIntentResourceLogicCircuit
 #[derive(Clone, Debug, Default)]
 pub struct OrRelationIntentResourceLogicCircuit {
@@ -52,34 +128,39 @@ pub struct OrRelationIntentResourceLogicCircuit {
     pub self_resource: ResourceExistenceWitness,
     // If the self_resource(intent) is an output resource, a dummy desired resource is needed.
     pub desired_resource: ResourceExistenceWitness,
-    pub token_1: Token,
-    pub token_2: Token,
+    pub alternatives: OrRelationAlternatives,
     pub receiver_npk: pallas::Base,
     pub receiver_value: pallas::Base,
 }
 
 impl OrRelationIntentResourceLogicCircuit {
     pub fn encode_label(
-        token_1: &Token,
-        token_2: &Token,
+        alternatives: &OrRelationAlternatives,
         receiver_npk: pallas::Base,
         receiver_value: pallas::Base,
     ) -> pallas::Base {
-        let token_property_1 = token_1.encode_name();
-        let token_quantity_1 = token_1.encode_quantity();
-        let token_property_2 = token_2.encode_name();
-        let token_quantity_2 = token_2.encode_quantity();
         poseidon_hash_n([
-            token_property_1,
-            token_quantity_1,
-            token_property_2,
-            token_quantity_2,
+            alternatives.root(),
             TOKEN_VK.get_compressed(),
             receiver_npk,
             receiver_value,
         ])
     }
 
+    // The membership path of the desired resource. It's a dummy path when the
+    // desired resource doesn't match any alternative, e.g. creating the intent resource.
+    fn get_alternative_path(
+        &self,
+    ) -> [(pallas::Base, LR); OR_RELATION_INTENT_ALTERNATIVES_TREE_DEPTH] {
+        let desired_resource = self.desired_resource.get_resource();
+        self.alternatives
+            .generate_path(
+                desired_resource.kind.label,
+                pallas::Base::from(desired_resource.quantity),
+            )
+            .unwrap_or_default()
+    }
+
     pub fn to_bytecode(&self) -> ResourceLogicByteCode {
         ResourceLogicByteCode::new(
             ResourceLogicRepresentation::OrRelationIntent,
@@ -121,7 +202,7 @@ impl ResourceLogicCircuit for OrRelationIntentResourceLogicCircuit {
         // load the desired resource
         let desired_resource = {
             // Construct a merkle chip
-            let merkle_chip = MerklePoseidonChip::construct(config.merkle_config);
+            let merkle_chip = MerklePoseidonChip::construct(config.merkle_config.clone());
 
             // Construct a resource_commit chip
             let resource_commit_chip =
@@ -157,28 +238,10 @@ impl ResourceLogicCircuit for OrRelationIntentResourceLogicCircuit {
             Value::known(TOKEN_VK.get_compressed()),
         )?;
 
-        let token_property_1 = assign_free_advice(
-            layouter.namespace(|| "witness token name in token_1"),
-            config.advices[0],
-            Value::known(self.token_1.encode_name()),
-        )?;
-
-        let token_quantity_1 = assign_free_advice(
-            layouter.namespace(|| "witness token quantity in token_1"),
+        let alternatives_root = assign_free_advice(
+            layouter.namespace(|| "witness alternatives root"),
             config.advices[0],
-            Value::known(self.token_1.encode_quantity()),
-        )?;
-
-        let token_property_2 = assign_free_advice(
-            layouter.namespace(|| "witness token name in token_2"),
-            config.advices[0],
-            Value::known(self.token_2.encode_name()),
-        )?;
-
-        let token_quantity_2 = assign_free_advice(
-            layouter.namespace(|| "witness token quantity in token_2"),
-            config.advices[0],
-            Value::known(self.token_2.encode_quantity()),
+            Value::known(self.alternatives.root()),
         )?;
 
         let receiver_npk = assign_free_advice(
@@ -195,13 +258,10 @@ impl ResourceLogicCircuit for OrRelationIntentResourceLogicCircuit {
 
         // Encode the label of intent resource
         let encoded_label = poseidon_hash_gadget(
-            config.poseidon_config,
+            config.poseidon_config.clone(),
             layouter.namespace(|| "encode label"),
             [
-                token_property_1.clone(),
-                token_quantity_1.clone(),
-                token_property_2.clone(),
-                token_quantity_2.clone(),
+                alternatives_root.clone(),
                 token_resource_logic_vk.clone(),
                 receiver_npk.clone(),
                 receiver_value.clone(),
@@ -280,18 +340,29 @@ impl ResourceLogicCircuit for OrRelationIntentResourceLogicCircuit {
             )?;
         }
 
-        // check the token_property and token_quantity in conditions
+        // check the token_property and token_quantity of the desired resource is one of the alternatives
+        let alternative = poseidon_hash_gadget(
+            config.poseidon_config,
+            layouter.namespace(|| "encode the desired alternative"),
+            [
+                desired_resource.resource.label,
+                desired_resource.resource.quantity,
+            ],
+        )?;
+        let merkle_chip = MerklePoseidonChip::construct(config.merkle_config);
+        let desired_alternatives_root = merkle_poseidon_gadget(
+            layouter.namespace(|| "alternatives membership"),
+            merkle_chip,
+            alternative,
+            &self.get_alternative_path(),
+        )?;
         layouter.assign_region(
-            || "extended or relatioin",
+            || "conditional equal: check alternatives root",
             |mut region| {
-                config.extended_or_relation_config.assign_region(
+                config.conditional_equal_config.assign_region(
                     &self_resource.is_input,
-                    (&token_property_1, &token_quantity_1),
-                    (&token_property_2, &token_quantity_2),
-                    (
-                        &desired_resource.resource.label,
-                        &desired_resource.resource.quantity,
-                    ),
+                    &alternatives_root,
+                    &desired_alternatives_root,
                     0,
                     &mut region,
                 )
@@ -314,6 +385,7 @@ impl ResourceLogicCircuit for OrRelationIntentResourceLogicCircuit {
             ResourceLogicCommitment::default().to_public_inputs();
         public_inputs.extend(default_resource_logic_cm);
         public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         let padding = ResourceLogicPublicInputs::get_public_input_padding(
             public_inputs.len(),
             &RandomSeed::random(&mut rng),
@@ -335,8 +407,7 @@ impl BorshSerialize for OrRelationIntentResourceLogicCircuit {
         self.self_resource.serialize(writer)?;
         self.desired_resource.serialize(writer)?;
 
-        self.token_1.serialize(writer)?;
-        self.token_2.serialize(writer)?;
+        self.alternatives.serialize(writer)?;
 
         writer.write_all(&self.receiver_npk.to_repr())?;
         writer.write_all(&self.receiver_value.to_repr())?;
@@ -349,15 +420,13 @@ impl BorshDeserialize for OrRelationIntentResourceLogicCircuit {
     fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
         let desired_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
-        let token_1 = Token::deserialize_reader(reader)?;
-        let token_2 = Token::deserialize_reader(reader)?;
+        let alternatives = OrRelationAlternatives::deserialize_reader(reader)?;
         let receiver_npk = read_base_field(reader)?;
         let receiver_value = read_base_field(reader)?;
         Ok(Self {
             self_resource,
             desired_resource,
-            token_1,
-            token_2,
+            alternatives,
             receiver_npk,
             receiver_value,
         })
@@ -366,15 +435,13 @@ impl BorshDeserialize for OrRelationIntentResourceLogicCircuit {
 
 pub fn create_intent_resource<R: RngCore>(
     mut rng: R,
-    token_1: &Token,
-    token_2: &Token,
+    alternatives: &OrRelationAlternatives,
     receiver_npk: pallas::Base,
     receiver_value: pallas::Base,
     nk: pallas::Base,
 ) -> Resource {
     let label = OrRelationIntentResourceLogicCircuit::encode_label(
-        token_1,
-        token_2,
+        alternatives,
         receiver_npk,
         receiver_value,
     );
@@ -404,25 +471,27 @@ fn test_halo2_or_relation_intent_resource_logic_circuit() {
     use rand::rngs::OsRng;
 
     let mut rng = OsRng;
-    let circuit = {
-        let token_1 = Token::new("token1".to_string(), 1u64);
-        let token_2 = Token::new("token2".to_string(), 2u64);
+    let alternatives = OrRelationAlternatives::new(vec![
+        Token::new("token1".to_string(), 1u64),
+        Token::new("token2".to_string(), 2u64),
+        Token::new("token3".to_string(), 3u64),
+    ]);
+    let nk = pallas::Base::random(&mut rng);
+    let receiver = random_resource(&mut rng);
+    let intent_resource = create_intent_resource(
+        &mut rng,
+        &alternatives,
+        receiver.get_npk(),
+        receiver.value,
+        nk,
+    );
 
+    let consume_intent_circuit = |desired_token: &Token| {
         // Create an output desired resource
-        let mut desired_resource = random_resource(&mut rng);
+        let mut desired_resource = receiver;
         desired_resource.kind.logic = *COMPRESSED_TOKEN_VK;
-        desired_resource.kind.label = token_1.encode_name();
-        desired_resource.quantity = token_1.quantity();
-
-        let nk = pallas::Base::random(&mut rng);
-        let intent_resource = create_intent_resource(
-            &mut rng,
-            &token_1,
-            &token_2,
-            desired_resource.get_npk(),
-            desired_resource.value,
-            nk,
-        );
+        desired_resource.kind.label = desired_token.encode_name();
+        desired_resource.quantity = desired_token.quantity();
 
         // Collect resource merkle leaves
         let input_resource_nf_1 = intent_resource.get_nf().unwrap().inner();
@@ -447,26 +516,43 @@ fn test_halo2_or_relation_intent_resource_logic_circuit() {
         OrRelationIntentResourceLogicCircuit {
             self_resource: intent_resource_witness,
             desired_resource: desired_resource_witness,
-            token_1,
-            token_2,
+            alternatives: alternatives.clone(),
             receiver_npk: desired_resource.get_npk(),
             receiver_value: desired_resource.value,
         }
     };
 
-    // Test serialization
-    let circuit = {
-        let circuit_bytes = circuit.to_bytes();
-        OrRelationIntentResourceLogicCircuit::from_bytes(&circuit_bytes)
-    };
+    // Any one of the alternatives satisfies the intent
+    for token in alternatives.inner() {
+        let circuit = consume_intent_circuit(token);
 
-    let public_inputs = circuit.get_public_inputs(&mut rng);
+        // Test serialization
+        let circuit = {
+            let circuit_bytes = circuit.to_bytes();
+            OrRelationIntentResourceLogicCircuit::from_bytes(&circuit_bytes)
+        };
 
-    let prover = MockProver::<pallas::Base>::run(
-        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
-        &circuit,
-        vec![public_inputs.to_vec()],
-    )
-    .unwrap();
-    assert_eq!(prover.verify(), Ok(()));
+        let public_inputs = circuit.get_public_inputs(&mut rng);
+
+        let prover = MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            &circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    // A token out of the alternatives doesn't satisfy the intent
+    {
+        let circuit = consume_intent_circuit(&Token::new("token3".to_string(), 2u64));
+        let public_inputs = circuit.get_public_inputs(&mut rng);
+        let prover = MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            &circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap();
+        assert!(prover.verify().is_err());
+    }
 }
@@ -4,7 +4,7 @@
 use crate::{
     circuit::{
         blake2s::publicize_default_dynamic_resource_logic_commitments,
-        gadgets::{mul::MulChip, sub::SubChip},
+        gadgets::{comparison::U64ComparisonChip, div_mod::DivModChip, mul::MulChip, sub::SubChip},
         integrity::load_resource,
         merkle_circuit::MerklePoseidonChip,
         resource_commitment::ResourceCommitChip,
@@ -93,6 +93,10 @@ impl ResourceLogicCircuit for PartialFulfillmentIntentResourceLogicCircuit {
 
         let sub_chip = SubChip::construct(config.sub_config.clone(), ());
         let mul_chip = MulChip::construct(config.mul_config.clone());
+        let div_mod_chip = DivModChip::construct(
+            config.div_mod_config.clone(),
+            U64ComparisonChip::construct(config.comparison_config.clone()),
+        );
 
         // load the sell resource
         let sell_resource = load_resource(
@@ -178,9 +182,11 @@ impl ResourceLogicCircuit for PartialFulfillmentIntentResourceLogicCircuit {
             &self_resource,
             &offer_resource,
             &returned_resource,
+            config.advices[0],
             &config.conditional_equal_config,
             &sub_chip,
             &mul_chip,
+            &div_mod_chip,
             layouter.namespace(|| "partial fulfillment check"),
         )?;
 
@@ -200,6 +206,7 @@ impl ResourceLogicCircuit for PartialFulfillmentIntentResourceLogicCircuit {
             ResourceLogicCommitment::default().to_public_inputs();
         public_inputs.extend(default_resource_logic_cm);
         public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         let padding = ResourceLogicPublicInputs::get_public_input_padding(
             public_inputs.len(),
             &RandomSeed::random(&mut rng),
@@ -320,7 +327,7 @@ mod tests {
         let intent_resource = swap.create_intent_resource(&mut rng);
 
         let bob_sell = swap.buy.clone();
-        let (offer_resource, _returned_resource) = swap.fill(&mut rng, bob_sell);
+        let (offer_resource, _returned_resource) = swap.fill(&mut rng, bob_sell).unwrap();
 
         let intent_nf = intent_resource.get_nf().unwrap().inner();
         let offer_cm = offer_resource.commitment().inner();
@@ -365,7 +372,7 @@ mod tests {
         let intent_resource = swap.create_intent_resource(&mut rng);
 
         let bob_sell = Token::new(swap.buy.name().inner().to_string(), 2u64);
-        let (offer_resource, returned_resource) = swap.fill(&mut rng, bob_sell);
+        let (offer_resource, returned_resource) = swap.fill(&mut rng, bob_sell).unwrap();
 
         let intent_nf = intent_resource.get_nf().unwrap().inner();
         let offer_cm = offer_resource.commitment().inner();
@@ -416,4 +423,104 @@ mod tests {
         .unwrap();
         prover.assert_satisfied();
     }
+
+    #[test]
+    fn partial_fulfillment_with_rounding() {
+        let mut rng = OsRng;
+        let sell = Token::new("token1".to_string(), 3u64);
+        let buy = Token::new("token2".to_string(), 10u64);
+
+        let swap = swap(&mut rng, sell, buy);
+        let intent_resource = swap.create_intent_resource(&mut rng);
+
+        // 5 token2 fills 1.5 token1, rounded down to 1, and 2 token1 are returned
+        let bob_sell = Token::new(swap.buy.name().inner().to_string(), 5u64);
+        let (offer_resource, returned_resource) = swap.fill(&mut rng, bob_sell).unwrap();
+        assert_eq!(returned_resource.quantity, 2u64);
+
+        let intent_nf = intent_resource.get_nf().unwrap().inner();
+        let offer_cm = offer_resource.commitment().inner();
+        let returned_cm = returned_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![
+            intent_nf,
+            offer_cm,
+            pallas::Base::zero(),
+            returned_cm,
+        ]);
+
+        let intent_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(intent_nf).unwrap();
+            ResourceExistenceWitness::new(intent_resource, merkle_path)
+        };
+
+        let offer_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(offer_cm).unwrap();
+            ResourceExistenceWitness::new(offer_resource, merkle_path)
+        };
+
+        let returned_resource_witness = {
+            let merkle_path = resource_merkle_tree.generate_path(returned_cm).unwrap();
+            ResourceExistenceWitness::new(returned_resource, merkle_path)
+        };
+
+        let circuit = PartialFulfillmentIntentResourceLogicCircuit {
+            self_resource: intent_resource_witness,
+            sell_resource: ResourceExistenceWitness::default(), // a dummy one
+            offer_resource: offer_resource_witness,
+            returned_resource: returned_resource_witness,
+            swap,
+        };
+
+        let public_inputs = circuit.get_public_inputs(&mut rng);
+
+        let prover = MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            &circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn fill_quantity_overflow() {
+        let mut rng = OsRng;
+        let sell = Token::new("token1".to_string(), u64::MAX / 2);
+        let buy = Token::new("token2".to_string(), 4u64);
+        let swap = swap(&mut rng, sell, buy);
+
+        // The product of the sold quantity and the offer quantity overflows u64
+        let bob_sell = Token::new(swap.buy.name().inner().to_string(), 3u64);
+        assert!(matches!(
+            swap.fill(&mut rng, bob_sell),
+            Err(TransactionError::QuantityOverflow)
+        ));
+
+        // The product of the sold quantity and the offer quantity fits in u64
+        let bob_sell = Token::new(swap.buy.name().inner().to_string(), 2u64);
+        let (_, returned_resource) = swap.fill(&mut rng, bob_sell).unwrap();
+        assert_eq!(returned_resource.quantity, u64::MAX / 2 - u64::MAX / 4);
+    }
+
+    #[test]
+    fn fill_invalid_offer() {
+        let mut rng = OsRng;
+        let sell = Token::new("token1".to_string(), 2u64);
+        let buy = Token::new("token2".to_string(), 4u64);
+        let swap = swap(&mut rng, sell, buy);
+
+        // The offer is a different token
+        let bob_sell = Token::new("token3".to_string(), 4u64);
+        assert!(matches!(
+            swap.fill(&mut rng, bob_sell),
+            Err(TransactionError::InvalidOffer)
+        ));
+
+        // The offer exceeds the asked quantity
+        let bob_sell = Token::new(swap.buy.name().inner().to_string(), 5u64);
+        assert!(matches!(
+            swap.fill(&mut rng, bob_sell),
+            Err(TransactionError::InvalidOffer)
+        ));
+    }
 }
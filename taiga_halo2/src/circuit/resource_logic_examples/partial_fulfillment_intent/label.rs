@@ -1,7 +1,8 @@
 use crate::circuit::{
     gadgets::{
-        assign_free_constant,
+        assign_free_advice, assign_free_constant,
         conditional_equal::ConditionalEqualConfig,
+        div_mod::{DivModChip, DivModInstructions},
         mul::{MulChip, MulInstructions},
         poseidon_hash::poseidon_hash_gadget,
         sub::{SubChip, SubInstructions},
@@ -182,16 +183,18 @@ impl PartialFulfillmentIntentLabel {
 
     /// partial fulfillment check:
     /// validity of the returned resource
-    /// partial fulfillment equation
+    /// partial fulfillment equation, the sold quantity is rounded down in favour of the intent owner
     #[allow(clippy::too_many_arguments)]
     pub fn partial_fulfillment_check(
         &self,
         intent_resource: &ResourceStatus,
         offer_resource: &ResourceStatus,
         returned_resource: &ResourceStatus,
+        advice: Column<Advice>,
         config: &ConditionalEqualConfig,
         sub_chip: &SubChip<pallas::Base>,
         mul_chip: &MulChip<pallas::Base>,
+        div_mod_chip: &DivModChip,
         mut layouter: impl Layouter<pallas::Base>,
     ) -> Result<(), Error> {
         let is_partial_fulfillment = {
@@ -287,14 +290,6 @@ impl PartialFulfillmentIntentLabel {
                 &returned_resource.resource.quantity,
             )?;
 
-            // check (expected_bought_quantity * actual_sold_quantity) == (expected_sold_quantity * actual_bought_quantity)
-            // if it's partially fulfilled
-            let expected_bought_mul_actual_sold_quantity = MulInstructions::mul(
-                mul_chip,
-                layouter.namespace(|| "expected_bought_quantity * actual_sold_quantity"),
-                &self.bought_token_quantity,
-                &actual_sold_quantity,
-            )?;
             let expected_sold_mul_actual_bought_quantity = MulInstructions::mul(
                 mul_chip,
                 layouter.namespace(|| "expected_sold_quantity * actual_bought_quantity"),
@@ -302,18 +297,55 @@ impl PartialFulfillmentIntentLabel {
                 &offer_resource.resource.quantity,
             )?;
 
+            // The dividend is the product if it's partially fulfilled, otherwise zero. The product
+            // of the quantities must fit in u64.
+            let dividend = assign_free_advice(
+                layouter.namespace(|| "witness dividend"),
+                advice,
+                is_partial_fulfillment
+                    .value()
+                    .zip(expected_sold_mul_actual_bought_quantity.value())
+                    .map(|(flag, product)| {
+                        if *flag == pallas::Base::zero() {
+                            pallas::Base::zero()
+                        } else {
+                            *product
+                        }
+                    }),
+            )?;
             layouter.assign_region(
-                    || "conditional equal: expected_bought_quantity * actual_sold_quantity == expected_sold_quantity * actual_bought_quantity",
-                    |mut region| {
-                        config.assign_region(
-                            &is_partial_fulfillment,
-                            &expected_bought_mul_actual_sold_quantity,
-                            &expected_sold_mul_actual_bought_quantity,
-                            0,
-                            &mut region,
-                        )
-                    },
-                )?;
+                || "conditional equal: check dividend",
+                |mut region| {
+                    config.assign_region(
+                        &is_partial_fulfillment,
+                        &dividend,
+                        &expected_sold_mul_actual_bought_quantity,
+                        0,
+                        &mut region,
+                    )
+                },
+            )?;
+
+            // check actual_sold_quantity == (expected_sold_quantity * actual_bought_quantity) / expected_bought_quantity
+            // rounded down if it's partially fulfilled
+            let (filled_quantity, _) = div_mod_chip.div_mod(
+                layouter.namespace(|| "filled_quantity"),
+                &dividend,
+                &self.bought_token_quantity,
+            )?;
+
+            layouter.assign_region(
+                || "conditional equal: actual_sold_quantity == filled_quantity",
+                |mut region| {
+                    config.assign_region(
+                        &is_partial_fulfillment,
+                        &actual_sold_quantity,
+                        &filled_quantity,
+                        0,
+                        &mut region,
+                    )
+                },
+            )?;
         }
 
         Ok(())
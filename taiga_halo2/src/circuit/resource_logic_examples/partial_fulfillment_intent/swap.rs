@@ -4,6 +4,7 @@ use crate::{
         gadgets::assign_free_advice,
         resource_logic_examples::token::{Token, TokenAuthorization, TokenResource, TOKEN_VK},
     },
+    error::TransactionError,
     resource::Resource,
     utils::poseidon_hash_n,
 };
@@ -30,8 +31,6 @@ impl Swap {
         buy: Token,
         auth: TokenAuthorization,
     ) -> Self {
-        assert_eq!(buy.quantity() % sell.quantity(), 0);
-
         let sell = {
             let nk = pallas::Base::random(&mut rng);
             sell.create_random_input_token_resource(&mut rng, nk, &auth)
@@ -44,11 +43,26 @@ impl Swap {
     /// - completely fills the swap using a single `TokenResource`, or
     /// - partially fills the swap, producing a `TokenResource` and a
     ///   returned resource.
-    pub fn fill(&self, mut rng: impl RngCore, offer: Token) -> (Resource, Resource) {
-        assert_eq!(offer.name(), self.buy.name());
-
-        let ratio = self.buy.quantity() / self.sell.quantity;
-        assert_eq!(offer.quantity() % ratio, 0);
+    ///
+    /// The filled quantity of a partial fill is rounded down in favour of the
+    /// intent owner. The product of the sold quantity and the offer quantity
+    /// must fit in u64 since the circuit divides it with the u64 division
+    /// gadget, otherwise `TransactionError::QuantityOverflow` is returned.
+    /// `TransactionError::InvalidOffer` is returned if the offer is a different
+    /// token or exceeds the asked quantity.
+    pub fn fill(
+        &self,
+        mut rng: impl RngCore,
+        offer: Token,
+    ) -> Result<(Resource, Resource), TransactionError> {
+        if offer.name() != self.buy.name() || offer.quantity() > self.buy.quantity() {
+            return Err(TransactionError::InvalidOffer);
+        }
+        let sell_mul_offer = self
+            .sell
+            .quantity
+            .checked_mul(offer.quantity())
+            .ok_or(TransactionError::QuantityOverflow)?;
 
         let offer_resource = offer.create_random_output_token_resource(
             &mut rng,
@@ -57,7 +71,7 @@ impl Swap {
         );
 
         let returned_resource = if offer.quantity() < self.buy.quantity() {
-            let filled_quantity = offer.quantity() / ratio;
+            let filled_quantity = sell_mul_offer / self.buy.quantity();
             let returned_quantity = self.sell.quantity - filled_quantity;
             let returned_token = Token::new(
                 self.sell.token_name().inner().to_string(),
@@ -74,7 +88,7 @@ impl Swap {
             Resource::random_padding_resource(&mut rng)
         };
 
-        (*offer_resource, returned_resource)
+        Ok((*offer_resource, returned_resource))
     }
 
     pub fn encode_label(&self) -> pallas::Base {
@@ -1,26 +1,28 @@
 use crate::{
     circuit::{
         blake2s::publicize_default_dynamic_resource_logic_commitments,
-        gadgets::{add::AddChip, assign_free_advice, poseidon_hash::poseidon_hash_gadget},
+        gadgets::{add::AddChip, assign_free_advice},
         resource_encryption_circuit::resource_encryption_gadget,
         resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
         resource_logic_circuit::{
             ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
             ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
         },
-        resource_logic_examples::signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        resource_logic_examples::token::{assign_value_data, check_value_encoding},
     },
     constant::{GENERATOR, SETUP_PARAMS_MAP},
     error::TransactionError,
+    keys::OutgoingViewingKey,
     proof::Proof,
     resource::RandomSeed,
-    resource_encryption::{ResourceCiphertext, ResourcePlaintext, SecretKey},
+    resource_encryption::{OutgoingCiphertext, ResourceCiphertext, ResourcePlaintext, SecretKey},
     resource_logic_commitment::ResourceLogicCommitment,
     resource_logic_vk::ResourceLogicVerifyingKey,
     resource_tree::ResourceExistenceWitness,
     utils::{mod_r_p, read_base_field, read_point},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
+use byteorder::{ReadBytesExt, WriteBytesExt};
 use group::{cofactor::CofactorCurveAffine, ff::PrimeField, Curve, Group, GroupEncoding};
 use halo2_gadgets::ecc::{chip::EccChip, NonIdentityPoint};
 use halo2_proofs::{
@@ -49,7 +51,10 @@ pub struct ReceiverResourceLogicCircuit {
     pub encrypt_nonce: pallas::Base,
     pub sk: pallas::Base,
     pub rcv_pk: pallas::Point,
+    pub auth_pk: pallas::Point,
     pub auth_resource_logic_vk: pallas::Base,
+    // The optional data committed in the value, e.g. the NFT metadata hash
+    pub value_data: Option<pallas::Base>,
 }
 
 impl ReceiverResourceLogicCircuit {
@@ -64,6 +69,22 @@ impl ReceiverResourceLogicCircuit {
     pub fn from_bytes(bytes: &Vec<u8>) -> Self {
         BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
     }
+
+    /// The ephemeral pk published with the resource ciphertext.
+    pub fn get_sender_pk(&self) -> pallas::Point {
+        GENERATOR.to_curve() * mod_r_p(self.sk)
+    }
+
+    /// Create the outgoing ciphertext so that the sender can recover the resource with the ovk.
+    pub fn get_outgoing_ciphertext(&self, ovk: &OutgoingViewingKey) -> OutgoingCiphertext {
+        OutgoingCiphertext::encrypt(
+            ovk,
+            &self.self_resource.get_resource().commitment(),
+            &self.get_sender_pk(),
+            &self.rcv_pk,
+            &self.sk,
+        )
+    }
 }
 
 impl Default for ReceiverResourceLogicCircuit {
@@ -74,7 +95,9 @@ impl Default for ReceiverResourceLogicCircuit {
             encrypt_nonce: pallas::Base::zero(),
             sk: pallas::Base::zero(),
             rcv_pk: pallas::Point::generator(),
+            auth_pk: pallas::Point::generator(),
             auth_resource_logic_vk: pallas::Base::zero(),
+            value_data: None,
         }
     }
 }
@@ -100,7 +123,7 @@ impl ResourceLogicCircuit for ReceiverResourceLogicCircuit {
         )?;
 
         // Construct an ECC chip
-        let ecc_chip = EccChip::construct(config.ecc_config);
+        let ecc_chip = EccChip::construct(config.ecc_config.clone());
 
         let rcv_pk = NonIdentityPoint::new(
             ecc_chip.clone(),
@@ -108,10 +131,16 @@ impl ResourceLogicCircuit for ReceiverResourceLogicCircuit {
             Value::known(self.rcv_pk.to_affine()),
         )?;
 
+        let auth_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness auth_pk"),
+            Value::known(self.auth_pk.to_affine()),
+        )?;
+
         let auth_resource_logic_vk = assign_free_advice(
             layouter.namespace(|| "witness auth resource_logic vk"),
             config.advices[0],
-            Value::known(*COMPRESSED_TOKEN_AUTH_VK),
+            Value::known(self.auth_resource_logic_vk),
         )?;
         let receiver_resource_logic_vk = assign_free_advice(
             layouter.namespace(|| "witness receiver resource_logic vk"),
@@ -120,22 +149,17 @@ impl ResourceLogicCircuit for ReceiverResourceLogicCircuit {
         )?;
 
         // Decode the value, and check the value encoding
-        let encoded_value = poseidon_hash_gadget(
-            config.poseidon_config.clone(),
-            layouter.namespace(|| "value encoding"),
-            [
-                rcv_pk.inner().x(),
-                rcv_pk.inner().y(),
-                auth_resource_logic_vk,
-                receiver_resource_logic_vk,
-            ],
-        )?;
-
-        layouter.assign_region(
-            || "check value encoding",
-            |mut region| {
-                region.constrain_equal(encoded_value.cell(), self_resource.resource.value.cell())
-            },
+        let (has_data, data) = assign_value_data(&mut layouter, &config, self.value_data)?;
+        check_value_encoding(
+            &mut layouter,
+            &config,
+            &self_resource.resource.value,
+            &auth_pk,
+            &auth_resource_logic_vk,
+            &receiver_resource_logic_vk,
+            &rcv_pk,
+            &has_data,
+            &data,
         )?;
 
         let mut message = vec![
@@ -181,6 +205,7 @@ impl ResourceLogicCircuit for ReceiverResourceLogicCircuit {
             ResourceLogicCommitment::default().to_public_inputs();
         public_inputs.extend(default_resource_logic_cm);
         public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         let custom_public_input_padding =
             ResourceLogicPublicInputs::get_custom_public_input_padding(
                 public_inputs.len(),
@@ -199,16 +224,14 @@ impl ResourceLogicCircuit for ReceiverResourceLogicCircuit {
             pallas::Base::from(self_resource.is_ephemeral as u64),
             self_resource.rseed,
         ];
+        let pk_coord = self.get_sender_pk().to_affine().coordinates().unwrap();
+        public_inputs.push(*pk_coord.x());
+        public_inputs.push(*pk_coord.y());
+
         let plaintext = ResourcePlaintext::padding(&message);
         let key = SecretKey::from_dh_exchange(&self.rcv_pk, &mod_r_p(self.sk));
         let cipher = ResourceCiphertext::encrypt(&plaintext, &key, &self.encrypt_nonce);
         cipher.inner().iter().for_each(|&c| public_inputs.push(c));
-
-        let generator = GENERATOR.to_curve();
-        let pk = generator * mod_r_p(self.sk);
-        let pk_coord = pk.to_affine().coordinates().unwrap();
-        public_inputs.push(*pk_coord.x());
-        public_inputs.push(*pk_coord.y());
         public_inputs.into()
     }
 
@@ -227,7 +250,17 @@ impl BorshSerialize for ReceiverResourceLogicCircuit {
         writer.write_all(&self.encrypt_nonce.to_repr())?;
         writer.write_all(&self.sk.to_repr())?;
         writer.write_all(&self.rcv_pk.to_bytes())?;
+        writer.write_all(&self.auth_pk.to_bytes())?;
         writer.write_all(&self.auth_resource_logic_vk.to_repr())?;
+        match self.value_data {
+            None => {
+                writer.write_u8(0)?;
+            }
+            Some(data) => {
+                writer.write_u8(1)?;
+                writer.write_all(&data.to_repr())?;
+            }
+        };
 
         Ok(())
     }
@@ -240,23 +273,37 @@ impl BorshDeserialize for ReceiverResourceLogicCircuit {
         let encrypt_nonce = read_base_field(reader)?;
         let sk = read_base_field(reader)?;
         let rcv_pk = read_point(reader)?;
+        let auth_pk = read_point(reader)?;
         let auth_resource_logic_vk = read_base_field(reader)?;
+        let value_data_type = reader.read_u8()?;
+        let value_data = if value_data_type == 0 {
+            None
+        } else {
+            Some(read_base_field(reader)?)
+        };
         Ok(Self {
             self_resource,
             resource_logic_vk,
             encrypt_nonce,
             sk,
             rcv_pk,
+            auth_pk,
             auth_resource_logic_vk,
+            value_data,
         })
     }
 }
 
 #[test]
 fn test_halo2_receiver_resource_logic_circuit() {
+    use crate::circuit::resource_logic_examples::{
+        multisig_verification::COMPRESSED_MULTISIG_AUTH_VK,
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK, token::TokenAuthorization,
+    };
     use crate::constant::{RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE, TAIGA_RESOURCE_TREE_DEPTH};
+    use crate::keys::SpendingKey;
     use crate::merkle_tree::LR;
-    use crate::{resource::tests::random_resource, utils::poseidon_hash_n};
+    use crate::resource::tests::random_resource;
     use ff::{Field, PrimeField};
     use halo2_proofs::dev::MockProver;
     use rand::rngs::OsRng;
@@ -267,16 +314,11 @@ fn test_halo2_receiver_resource_logic_circuit() {
         let mut resource = random_resource(&mut rng);
         let encrypt_nonce = pallas::Base::from_u128(23333u128);
         let sk = pallas::Base::random(&mut rng);
-        let rcv_sk = pallas::Base::random(&mut rng);
-        let generator = GENERATOR.to_curve();
-        let rcv_pk = generator * mod_r_p(rcv_sk);
-        let rcv_pk_coord = rcv_pk.to_affine().coordinates().unwrap();
-        resource.value = poseidon_hash_n([
-            *rcv_pk_coord.x(),
-            *rcv_pk_coord.y(),
-            *COMPRESSED_TOKEN_AUTH_VK,
-            *COMPRESSED_RECEIVER_VK,
-        ]);
+        let rcv_key = SpendingKey::random(&mut rng);
+        let rcv_sk = rcv_key.get_ivk().inner();
+        let auth =
+            TokenAuthorization::from_address(&rcv_key.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+        resource.value = auth.to_value();
         let merkle_path = [(pallas::Base::zero(), LR::L); TAIGA_RESOURCE_TREE_DEPTH];
         let self_resource = ResourceExistenceWitness::new(resource, merkle_path);
         (
@@ -285,8 +327,10 @@ fn test_halo2_receiver_resource_logic_circuit() {
                 resource_logic_vk: *COMPRESSED_RECEIVER_VK,
                 encrypt_nonce,
                 sk,
-                rcv_pk,
+                rcv_pk: auth.rcv_pk,
+                auth_pk: auth.pk,
                 auth_resource_logic_vk: *COMPRESSED_TOKEN_AUTH_VK,
+                value_data: None,
             },
             rcv_sk,
         )
@@ -321,4 +365,42 @@ fn test_halo2_receiver_resource_logic_circuit() {
         pallas::Base::from(original_resource.is_ephemeral)
     );
     assert_eq!(de_cipher[7], original_resource.rseed);
+
+    // The sender recovers the resource with the ovk
+    let sender_key = SpendingKey::random(&mut rng);
+    let out_cipher = circuit.get_outgoing_ciphertext(&sender_key.get_ovk());
+    let de_out_cipher = public_inputs
+        .decrypt_outgoing(&sender_key.get_ovk(), &out_cipher)
+        .unwrap();
+    assert_eq!(de_out_cipher, de_cipher);
+
+    // Other keys can't recover the resource
+    let other_key = SpendingKey::random(&mut rng);
+    assert!(public_inputs
+        .decrypt_outgoing(&other_key.get_ovk(), &out_cipher)
+        .is_none());
+
+    // The value encoding takes the auth resource logic of the owner, e.g. the multisig
+    let circuit = {
+        let mut circuit = circuit;
+        let auth = TokenAuthorization {
+            pk: circuit.auth_pk,
+            vk: *COMPRESSED_MULTISIG_AUTH_VK,
+            rcv_pk: circuit.rcv_pk,
+        };
+        let mut resource = circuit.self_resource.get_resource();
+        resource.value = auth.to_value();
+        circuit.self_resource =
+            ResourceExistenceWitness::new(resource, circuit.self_resource.get_path());
+        circuit.auth_resource_logic_vk = auth.vk;
+        circuit
+    };
+    let public_inputs = circuit.get_public_inputs(&mut rng);
+    let prover = MockProver::<pallas::Base>::run(
+        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        &circuit,
+        vec![public_inputs.to_vec()],
+    )
+    .unwrap();
+    assert_eq!(prover.verify(), Ok(()));
 }
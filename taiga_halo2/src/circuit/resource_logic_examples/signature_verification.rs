@@ -7,6 +7,7 @@ use crate::{
             ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
             ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
         },
+        resource_logic_examples::token::{assign_value_data, check_value_encoding},
     },
     constant::{TaigaFixedBasesFull, SETUP_PARAMS_MAP},
     error::TransactionError,
@@ -18,6 +19,7 @@ use crate::{
     utils::{mod_r_p, poseidon_hash_n, read_base_field, read_point, read_scalar_field},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
+use byteorder::{ReadBytesExt, WriteBytesExt};
 use halo2_gadgets::ecc::{chip::EccChip, FixedPoint, NonIdentityPoint, ScalarFixed, ScalarVar};
 use halo2_proofs::{
     arithmetic::Field,
@@ -85,16 +87,45 @@ impl SchnorrSignature {
         let s = z + h * sk;
         Self { pk, r, s }
     }
+
+    pub fn pk(&self) -> pallas::Point {
+        self.pk
+    }
+
+    pub fn r(&self) -> pallas::Point {
+        self.r
+    }
+
+    pub fn s(&self) -> pallas::Scalar {
+        self.s
+    }
 }
 
 // SignatureVerificationResourceLogicCircuit uses the schnorr signature.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SignatureVerificationResourceLogicCircuit {
     pub self_resource: ResourceExistenceWitness,
     pub resource_logic_vk: pallas::Base,
     pub signature: SchnorrSignature,
     pub receiver_resource_logic_vk: pallas::Base,
+    // rcv_pk is only used to check the value encoding
+    pub rcv_pk: pallas::Point,
+    // The optional data committed in the value, e.g. the NFT metadata hash
+    pub value_data: Option<pallas::Base>,
+}
+
+impl Default for SignatureVerificationResourceLogicCircuit {
+    fn default() -> Self {
+        Self {
+            self_resource: ResourceExistenceWitness::default(),
+            resource_logic_vk: pallas::Base::zero(),
+            signature: SchnorrSignature::default(),
+            receiver_resource_logic_vk: pallas::Base::zero(),
+            rcv_pk: pallas::Point::generator(),
+            value_data: None,
+        }
+    }
 }
 
 impl SignatureVerificationResourceLogicCircuit {
@@ -103,12 +134,15 @@ impl SignatureVerificationResourceLogicCircuit {
         resource_logic_vk: pallas::Base,
         signature: SchnorrSignature,
         receiver_resource_logic_vk: pallas::Base,
+        rcv_pk: pallas::Point,
     ) -> Self {
         Self {
             self_resource,
             resource_logic_vk,
             signature,
             receiver_resource_logic_vk,
+            rcv_pk,
+            value_data: None,
         }
     }
 
@@ -118,6 +152,7 @@ impl SignatureVerificationResourceLogicCircuit {
         resource_logic_vk: pallas::Base,
         sk: pallas::Scalar,
         receiver_resource_logic_vk: pallas::Base,
+        rcv_pk: pallas::Point,
     ) -> Self {
         let message = vec![self_resource.get_root()];
         let signature = SchnorrSignature::sign(&mut rng, sk, message);
@@ -126,6 +161,8 @@ impl SignatureVerificationResourceLogicCircuit {
             resource_logic_vk,
             signature,
             receiver_resource_logic_vk,
+            rcv_pk,
+            value_data: None,
         }
     }
 
@@ -154,7 +191,7 @@ impl ResourceLogicCircuit for SignatureVerificationResourceLogicCircuit {
         self_resource: ResourceStatus,
     ) -> Result<(), Error> {
         // Construct an ECC chip
-        let ecc_chip = EccChip::construct(config.ecc_config);
+        let ecc_chip = EccChip::construct(config.ecc_config.clone());
 
         let pk = NonIdentityPoint::new(
             ecc_chip.clone(),
@@ -173,23 +210,24 @@ impl ResourceLogicCircuit for SignatureVerificationResourceLogicCircuit {
             Value::known(self.receiver_resource_logic_vk),
         )?;
 
-        // Decode the value, and check the value encoding
-        let encoded_value = poseidon_hash_gadget(
-            config.poseidon_config.clone(),
-            layouter.namespace(|| "value encoding"),
-            [
-                pk.inner().x(),
-                pk.inner().y(),
-                auth_resource_logic_vk,
-                receiver_resource_logic_vk,
-            ],
+        let rcv_pk = NonIdentityPoint::new(
+            ecc_chip.clone(),
+            layouter.namespace(|| "witness rcv_pk"),
+            Value::known(self.rcv_pk.to_affine()),
         )?;
 
-        layouter.assign_region(
-            || "check value encoding",
-            |mut region| {
-                region.constrain_equal(encoded_value.cell(), self_resource.resource.value.cell())
-            },
+        // Decode the value, and check the value encoding
+        let (has_data, data) = assign_value_data(&mut layouter, &config, self.value_data)?;
+        check_value_encoding(
+            &mut layouter,
+            &config,
+            &self_resource.resource.value,
+            &pk,
+            &auth_resource_logic_vk,
+            &receiver_resource_logic_vk,
+            &rcv_pk,
+            &has_data,
+            &data,
         )?;
 
         let r = NonIdentityPoint::new(
@@ -250,6 +288,7 @@ impl ResourceLogicCircuit for SignatureVerificationResourceLogicCircuit {
             ResourceLogicCommitment::default().to_public_inputs();
         public_inputs.extend(default_resource_logic_cm);
         public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         let padding = ResourceLogicPublicInputs::get_public_input_padding(
             public_inputs.len(),
             &RandomSeed::random(&mut rng),
@@ -272,6 +311,16 @@ impl BorshSerialize for SignatureVerificationResourceLogicCircuit {
         writer.write_all(&self.resource_logic_vk.to_repr())?;
         self.signature.serialize(writer)?;
         writer.write_all(&self.receiver_resource_logic_vk.to_repr())?;
+        writer.write_all(&self.rcv_pk.to_bytes())?;
+        match self.value_data {
+            None => {
+                writer.write_u8(0)?;
+            }
+            Some(data) => {
+                writer.write_u8(1)?;
+                writer.write_all(&data.to_repr())?;
+            }
+        };
 
         Ok(())
     }
@@ -283,11 +332,20 @@ impl BorshDeserialize for SignatureVerificationResourceLogicCircuit {
         let resource_logic_vk = read_base_field(reader)?;
         let signature = SchnorrSignature::deserialize_reader(reader)?;
         let receiver_resource_logic_vk = read_base_field(reader)?;
+        let rcv_pk = read_point(reader)?;
+        let value_data_type = reader.read_u8()?;
+        let value_data = if value_data_type == 0 {
+            None
+        } else {
+            Some(read_base_field(reader)?)
+        };
         Ok(Self {
             self_resource,
             resource_logic_vk,
             signature,
             receiver_resource_logic_vk,
+            rcv_pk,
+            value_data,
         })
     }
 }
@@ -338,6 +396,7 @@ fn test_halo2_sig_verification_resource_logic_circuit() {
             auth_vk,
             sk,
             *COMPRESSED_RECEIVER_VK,
+            auth.rcv_pk,
         )
     };
 
@@ -356,4 +415,42 @@ fn test_halo2_sig_verification_resource_logic_circuit() {
     )
     .unwrap();
     assert_eq!(prover.verify(), Ok(()));
+
+    // The value commits to the value data, e.g. the NFT metadata hash
+    let sk = pallas::Scalar::random(&mut rng);
+    let auth_vk = pallas::Base::random(&mut rng);
+    let auth = TokenAuthorization::from_sk_vk(&sk, &auth_vk);
+    let data = pallas::Base::random(&mut rng);
+    let mut resource = random_resource(&mut rng);
+    resource.value = auth.to_value_with_data(data);
+    let merkle_path = [(pallas::Base::zero(), LR::R); TAIGA_RESOURCE_TREE_DEPTH];
+    let mut circuit = SignatureVerificationResourceLogicCircuit::from_sk_and_sign(
+        &mut rng,
+        ResourceExistenceWitness::new(resource, merkle_path),
+        auth_vk,
+        sk,
+        *COMPRESSED_RECEIVER_VK,
+        auth.rcv_pk,
+    );
+    let verify = |circuit: &SignatureVerificationResourceLogicCircuit| {
+        let circuit = SignatureVerificationResourceLogicCircuit::from_bytes(&circuit.to_bytes());
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            &circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+    };
+
+    // The value data is missing
+    assert!(verify(&circuit).is_err());
+
+    circuit.value_data = Some(data);
+    assert_eq!(verify(&circuit), Ok(()));
+
+    // A wrong value data fails
+    circuit.value_data = Some(pallas::Base::random(&mut rng));
+    assert!(verify(&circuit).is_err());
 }
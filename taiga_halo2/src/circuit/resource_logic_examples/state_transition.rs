@@ -0,0 +1,316 @@
+/// The state transition framework for apps keeping mutable state in resources, e.g. counters,
+/// registries and game boards. The state lives in the value of a state resource with quantity 1,
+/// and the label identifies the app instance. A transition consumes the state resource S_n and
+/// creates S_{n+1} = f(S_n) of the same kind in the same ptx.
+///
+/// An app implements `StateTransition` and reuses `StateTransitionResourceLogicCircuit` as its
+/// resource logic. The circuit of either state resource finds the paired one through the
+/// resource tree:
+///  - the paired resource has the same root, logic and label, and the opposite is_input flag.
+///  - the value of the output state resource is the transition of the value of the input one.
+///  - the state starts from an ephemeral input state resource holding the initial state. Like the
+///    issuance resource of the issued token, the genesis state resource is determined by the
+///    label, so its nullifier is unique and the state of a label can only be started once.
+///  - the output state resource is not ephemeral, so the state is kept in the resource tree.
+///
+/// See `counter` for an example.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::{
+            assign_free_constant,
+            conditional_equal::conditional_equal,
+            issuance::constrain_label_determined_resource,
+            mul::{MulChip, MulInstructions},
+            sub::{SubChip, SubInstructions},
+        },
+        integrity::load_resource,
+        merkle_circuit::MerklePoseidonChip,
+        resource_commitment::ResourceCommitChip,
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+    },
+    nullifier::Nullifier,
+    resource::{RandomSeed, Resource},
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_tree::ResourceExistenceWitness,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    plonk::{Circuit, Error},
+};
+use pasta_curves::pallas;
+use rand::RngCore;
+use std::fmt::Debug;
+
+pub mod counter;
+
+/// The transition function S_{n+1} = f(S_n) over the value of the state resources. The
+/// transition input, if any, is kept in the implementor.
+pub trait StateTransition:
+    Clone + Debug + Default + BorshSerialize + BorshDeserialize + 'static
+{
+    // The value of the ephemeral state resource starting the state. It's a circuit constant.
+    fn initial_state() -> pallas::Base {
+        pallas::Base::zero()
+    }
+
+    fn transition(&self, state: pallas::Base) -> pallas::Base;
+
+    // Constrain the transition function and return the next state
+    fn transition_gadget(
+        &self,
+        config: &ResourceLogicConfig,
+        layouter: impl Layouter<pallas::Base>,
+        state: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error>;
+}
+
+// StateTransitionResourceLogicCircuit
+#[derive(Clone, Debug, Default)]
+pub struct StateTransitionResourceLogicCircuit<T: StateTransition> {
+    pub self_resource: ResourceExistenceWitness,
+    // The output state resource if the self_resource is an input, otherwise the input state resource
+    pub paired_resource: ResourceExistenceWitness,
+    pub transition: T,
+}
+
+impl<T: StateTransition> StateTransitionResourceLogicCircuit<T> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl<T: StateTransition> ResourceLogicCircuit for StateTransitionResourceLogicCircuit<T>
+where
+    Self: Circuit<pallas::Base> + ResourceLogicVerifyingInfoTrait,
+{
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: ResourceLogicConfig,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // load the paired resource
+        let paired_resource = {
+            let merkle_chip = MerklePoseidonChip::construct(config.merkle_config.clone());
+            let resource_commit_chip =
+                ResourceCommitChip::construct(config.resource_commit_config.clone());
+            load_resource(
+                layouter.namespace(|| "load the paired resource"),
+                config.advices,
+                resource_commit_chip,
+                config.conditional_select_config,
+                merkle_chip,
+                &self.paired_resource,
+            )?
+        };
+
+        // check the quantity of the state resource
+        let one = assign_free_constant(
+            layouter.namespace(|| "constant one"),
+            config.advices[0],
+            pallas::Base::one(),
+        )?;
+        layouter.assign_region(
+            || "check quantity",
+            |mut region| region.constrain_equal(one.cell(), self_resource.resource.quantity.cell()),
+        )?;
+
+        // check the paired resource is the other state resource of the transition
+        let sub_chip = SubChip::construct(config.sub_config.clone(), ());
+        let paired_is_input = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "1 - is_input"),
+            &one,
+            &self_resource.is_input,
+        )?;
+        layouter.assign_region(
+            || "check paired resource",
+            |mut region| {
+                region.constrain_equal(paired_is_input.cell(), paired_resource.is_input.cell())?;
+                region.constrain_equal(
+                    self_resource.resource_merkle_root.cell(),
+                    paired_resource.resource_merkle_root.cell(),
+                )?;
+                region.constrain_equal(
+                    self_resource.resource.logic.cell(),
+                    paired_resource.resource.logic.cell(),
+                )?;
+                region.constrain_equal(
+                    self_resource.resource.label.cell(),
+                    paired_resource.resource.label.cell(),
+                )
+            },
+        )?;
+
+        // the genesis state resource is determined by the label
+        let mul_chip = MulChip::construct(config.mul_config.clone());
+        let is_genesis = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "is_input * is_ephemeral"),
+            &self_resource.is_input,
+            &self_resource.resource.is_ephemeral,
+        )?;
+        constrain_label_determined_resource(&mut layouter, &config, &is_genesis, &self_resource)?;
+
+        // the output state resource is not ephemeral
+        let zero = assign_free_constant(
+            layouter.namespace(|| "constant zero"),
+            config.advices[0],
+            pallas::Base::zero(),
+        )?;
+        conditional_equal(
+            &mut layouter,
+            &config.conditional_equal_config,
+            "output is_ephemeral",
+            &paired_is_input,
+            &self_resource.resource.is_ephemeral,
+            &zero,
+        )?;
+
+        // find the input and output state resources
+        let (input_value, input_is_ephemeral, output_value) = layouter.assign_region(
+            || "conditional select: input and output state resources",
+            |mut region| {
+                let input_value = config.conditional_select_config.assign_region(
+                    &self_resource.is_input,
+                    &self_resource.resource.value,
+                    &paired_resource.resource.value,
+                    0,
+                    &mut region,
+                )?;
+                let input_is_ephemeral = config.conditional_select_config.assign_region(
+                    &self_resource.is_input,
+                    &self_resource.resource.is_ephemeral,
+                    &paired_resource.resource.is_ephemeral,
+                    2,
+                    &mut region,
+                )?;
+                let output_value = config.conditional_select_config.assign_region(
+                    &self_resource.is_input,
+                    &paired_resource.resource.value,
+                    &self_resource.resource.value,
+                    4,
+                    &mut region,
+                )?;
+                Ok((input_value, input_is_ephemeral, output_value))
+            },
+        )?;
+
+        // the ephemeral input state resource holds the initial state
+        let initial_state = assign_free_constant(
+            layouter.namespace(|| "initial state"),
+            config.advices[0],
+            T::initial_state(),
+        )?;
+        conditional_equal(
+            &mut layouter,
+            &config.conditional_equal_config,
+            "initial state",
+            &input_is_ephemeral,
+            &input_value,
+            &initial_state,
+        )?;
+
+        // check the transition
+        let next_state = self.transition.transition_gadget(
+            &config,
+            layouter.namespace(|| "transition"),
+            &input_value,
+        )?;
+        layouter.assign_region(
+            || "check transition",
+            |mut region| region.constrain_equal(next_state.cell(), output_value.cell()),
+        )?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+impl<T: StateTransition> BorshSerialize for StateTransitionResourceLogicCircuit<T> {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.paired_resource.serialize(writer)?;
+        self.transition.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl<T: StateTransition> BorshDeserialize for StateTransitionResourceLogicCircuit<T> {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let paired_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let transition = T::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            paired_resource,
+            transition,
+        })
+    }
+}
+
+// The ephemeral input state resource starting the state. All fields are determined by the label.
+pub fn create_genesis_state_resource(
+    logic: pallas::Base,
+    label: pallas::Base,
+    initial_state: pallas::Base,
+) -> Resource {
+    Resource::new_input_resource(
+        logic,
+        label,
+        initial_state,
+        1u64,
+        pallas::Base::zero(),
+        Nullifier::from(label),
+        true,
+        pallas::Base::zero(),
+    )
+}
+
+pub fn create_state_resource<R: RngCore>(
+    mut rng: R,
+    logic: pallas::Base,
+    label: pallas::Base,
+    state: pallas::Base,
+    npk: pallas::Base,
+) -> Resource {
+    let rseed = pallas::Base::random(&mut rng);
+    Resource::new_output_resource(logic, label, state, 1u64, npk, false, rseed)
+}
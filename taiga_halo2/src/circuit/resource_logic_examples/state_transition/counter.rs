@@ -0,0 +1,229 @@
+/// The counter app built on the state transition framework. The counter is a state resource
+/// keeping the count in the value, and every transition increases the count by a step.
+use crate::{
+    circuit::{
+        gadgets::{
+            add::{AddChip, AddInstructions},
+            assign_free_advice,
+        },
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait,
+        },
+        resource_logic_examples::state_transition::{
+            create_genesis_state_resource, create_state_resource, StateTransition,
+            StateTransitionResourceLogicCircuit,
+        },
+    },
+    constant::SETUP_PARAMS_MAP,
+    error::TransactionError,
+    proof::Proof,
+    resource::Resource,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_proofs::{
+    circuit::{floor_planner, AssignedCell, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::pallas;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+lazy_static! {
+    pub static ref COUNTER_VK: ResourceLogicVerifyingKey =
+        CounterResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_COUNTER_VK: pallas::Base = COUNTER_VK.get_compressed();
+}
+
+// The transition increasing the count by the step
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct CounterTransition {
+    pub step: u64,
+}
+
+impl CounterTransition {
+    pub fn new(step: u64) -> Self {
+        Self { step }
+    }
+}
+
+impl StateTransition for CounterTransition {
+    fn transition(&self, state: pallas::Base) -> pallas::Base {
+        state + pallas::Base::from(self.step)
+    }
+
+    fn transition_gadget(
+        &self,
+        config: &ResourceLogicConfig,
+        mut layouter: impl Layouter<pallas::Base>,
+        state: &AssignedCell<pallas::Base, pallas::Base>,
+    ) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+        let step = assign_free_advice(
+            layouter.namespace(|| "witness step"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.step)),
+        )?;
+        let add_chip = AddChip::construct(config.add_config.clone(), ());
+        AddInstructions::add(
+            &add_chip,
+            layouter.namespace(|| "count + step"),
+            state,
+            &step,
+        )
+    }
+}
+
+pub type CounterResourceLogicCircuit = StateTransitionResourceLogicCircuit<CounterTransition>;
+
+impl CounterResourceLogicCircuit {
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(ResourceLogicRepresentation::Counter, self.to_bytes())
+    }
+}
+
+resource_logic_circuit_impl!(CounterResourceLogicCircuit);
+resource_logic_verifying_info_impl!(CounterResourceLogicCircuit);
+
+// The ephemeral counter resource starting the count from zero
+pub fn create_counter_genesis_resource(label: pallas::Base) -> Resource {
+    create_genesis_state_resource(
+        *COMPRESSED_COUNTER_VK,
+        label,
+        CounterTransition::initial_state(),
+    )
+}
+
+pub fn create_counter_resource<R: RngCore>(
+    rng: R,
+    label: pallas::Base,
+    count: u64,
+    npk: pallas::Base,
+) -> Resource {
+    create_state_resource(
+        rng,
+        *COMPRESSED_COUNTER_VK,
+        label,
+        pallas::Base::from(count),
+        npk,
+    )
+}
+
+#[test]
+fn test_halo2_counter_resource_logic_circuit() {
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use crate::nullifier::NullifierKeyContainer;
+    use crate::resource_tree::{ResourceExistenceWitness, ResourceMerkleTreeLeaves};
+    use halo2_proofs::arithmetic::Field;
+    use halo2_proofs::dev::MockProver;
+
+    let mut rng = OsRng;
+    let label = pallas::Base::random(&mut rng);
+    let nk = pallas::Base::random(&mut rng);
+    let npk = NullifierKeyContainer::from_key(nk).get_npk();
+
+    let verify = |circuit: &CounterResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+        .is_ok()
+    };
+    // The circuits of the input and output state resources
+    let transition_circuits = |input_resource: Resource, output_resource: Resource, step: u64| {
+        let input_nf = input_resource.get_nf().unwrap().inner();
+        let output_cm = output_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![input_nf, output_cm]);
+        let input_witness = ResourceExistenceWitness::new(
+            input_resource,
+            resource_merkle_tree.generate_path(input_nf).unwrap(),
+        );
+        let output_witness = ResourceExistenceWitness::new(
+            output_resource,
+            resource_merkle_tree.generate_path(output_cm).unwrap(),
+        );
+        let input_circuit = CounterResourceLogicCircuit {
+            self_resource: input_witness,
+            paired_resource: output_witness,
+            transition: CounterTransition::new(step),
+        };
+        let output_circuit = CounterResourceLogicCircuit {
+            self_resource: output_witness,
+            paired_resource: input_witness,
+            transition: CounterTransition::new(step),
+        };
+        (input_circuit, output_circuit)
+    };
+
+    // Start the counter
+    {
+        let genesis_resource = create_counter_genesis_resource(label);
+        let counter_resource = create_counter_resource(&mut rng, label, 1, npk);
+        let (input_circuit, output_circuit) =
+            transition_circuits(genesis_resource, counter_resource, 1);
+        let input_circuit = {
+            let circuit_bytes = input_circuit.to_bytes();
+            CounterResourceLogicCircuit::from_bytes(&circuit_bytes)
+        };
+        assert!(verify(&input_circuit));
+        assert!(verify(&output_circuit));
+
+        // The counter of a label can only be started once
+        assert_eq!(
+            create_counter_genesis_resource(label).get_nf(),
+            genesis_resource.get_nf()
+        );
+
+        // The genesis resource must be determined by the label
+        let mut invalid_resource = genesis_resource;
+        invalid_resource.rseed = pallas::Base::random(&mut rng);
+        let (input_circuit, _) = transition_circuits(invalid_resource, counter_resource, 1);
+        assert!(!verify(&input_circuit));
+
+        // The output counter resource must not be ephemeral
+        let mut invalid_resource = counter_resource;
+        invalid_resource.is_ephemeral = true;
+        let (_, output_circuit) = transition_circuits(genesis_resource, invalid_resource, 1);
+        assert!(!verify(&output_circuit));
+
+        // The genesis resource can't start from an arbitrary count
+        let mut genesis_resource = genesis_resource;
+        genesis_resource.value = pallas::Base::from(5u64);
+        let counter_resource = create_counter_resource(&mut rng, label, 6, npk);
+        let (input_circuit, output_circuit) =
+            transition_circuits(genesis_resource, counter_resource, 1);
+        assert!(!verify(&input_circuit));
+        assert!(!verify(&output_circuit));
+    }
+
+    // Increase the counter
+    {
+        let mut counter_resource = create_counter_resource(&mut rng, label, 1, npk);
+        counter_resource.nk_container = NullifierKeyContainer::from_key(nk);
+        let next_counter_resource = create_counter_resource(&mut rng, label, 4, npk);
+        let (input_circuit, output_circuit) =
+            transition_circuits(counter_resource, next_counter_resource, 3);
+        assert!(verify(&input_circuit));
+        assert!(verify(&output_circuit));
+
+        // The count must follow the transition
+        let (input_circuit, output_circuit) =
+            transition_circuits(counter_resource, next_counter_resource, 2);
+        assert!(!verify(&input_circuit));
+        assert!(!verify(&output_circuit));
+
+        // The next counter must have the same label
+        let other_label = pallas::Base::random(&mut rng);
+        let other_counter_resource = create_counter_resource(&mut rng, other_label, 4, npk);
+        let (input_circuit, output_circuit) =
+            transition_circuits(counter_resource, other_counter_resource, 3);
+        assert!(!verify(&input_circuit));
+        assert!(!verify(&output_circuit));
+    }
+}
@@ -0,0 +1,790 @@
+/// The sudoku app is a stateful application. A sudoku resource keeps the puzzle in the label
+/// and the current board in the value, so every board of the same puzzle is the same kind.
+/// A move consumes the resource of the previous board and creates the resource of the next
+/// board. The app logic checks:
+///  - the created board is a valid partial solution of the puzzle: the digits are in [0, 9],
+///    the nonzero digits in every row, column and box are distinct, and the puzzle digits are kept.
+///  - the consumed board is only replaced with a board of the same puzzle that keeps its digits.
+///
+/// A player starts the game by consuming an ephemeral resource of the puzzle board. The dealer
+/// intent in `dealer_intent` rewards the player creating a solved board.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::{
+            add::{AddChip, AddInstructions},
+            assign_free_advice, assign_free_constant,
+            conditional_equal::conditional_equal,
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+            range_check_bits,
+            sub::{SubChip, SubInstructions},
+        },
+        integrity::load_resource,
+        merkle_circuit::MerklePoseidonChip,
+        resource_commitment::ResourceCommitChip,
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+    },
+    constant::SETUP_PARAMS_MAP,
+    error::TransactionError,
+    nullifier::Nullifier,
+    proof::Proof,
+    resource::{RandomSeed, Resource},
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    utils::poseidon_hash,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::{
+    circuit::{floor_planner, AssignedCell, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::pallas;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+pub mod dealer_intent;
+
+lazy_static! {
+    pub static ref SUDOKU_VK: ResourceLogicVerifyingKey =
+        SudokuResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_SUDOKU_VK: pallas::Base = SUDOKU_VK.get_compressed();
+}
+
+// The first 40 cells and the last 41 cells are packed into two field elements with 4 bits per cell.
+const SUDOKU_ENCODING_SPLIT: usize = 40;
+const SUDOKU_ENCODING_BASE: u64 = 16;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct SudokuState {
+    pub state: [[u8; 9]; 9],
+}
+
+impl SudokuState {
+    pub fn new(state: [[u8; 9]; 9]) -> Self {
+        Self { state }
+    }
+
+    pub fn cells(&self) -> Vec<u8> {
+        self.state.concat()
+    }
+
+    pub fn encode(&self) -> pallas::Base {
+        let pack = |cells: &[u8]| {
+            cells.iter().fold(pallas::Base::zero(), |acc, cell| {
+                acc * pallas::Base::from(SUDOKU_ENCODING_BASE) + pallas::Base::from(*cell as u64)
+            })
+        };
+        let cells = self.cells();
+        let (first, last) = cells.split_at(SUDOKU_ENCODING_SPLIT);
+        poseidon_hash(pack(first), pack(last))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells().iter().all(|cell| *cell != 0)
+    }
+}
+
+// Witness the cells of a sudoku board
+pub(crate) fn assign_sudoku_state(
+    mut layouter: impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    state: &SudokuState,
+) -> Result<Vec<AssignedCell<pallas::Base, pallas::Base>>, Error> {
+    state
+        .cells()
+        .iter()
+        .map(|cell| {
+            assign_free_advice(
+                layouter.namespace(|| "sudoku cell"),
+                config.advices[0],
+                Value::known(pallas::Base::from(*cell as u64)),
+            )
+        })
+        .collect()
+}
+
+// Encode the cells of a sudoku board in the same way as `SudokuState::encode`
+pub(crate) fn encode_sudoku_state_gadget(
+    mut layouter: impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    cells: &[AssignedCell<pallas::Base, pallas::Base>],
+) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+    let add_chip = AddChip::construct(config.add_config.clone(), ());
+    let mul_chip = MulChip::construct(config.mul_config.clone());
+    let base = assign_free_constant(
+        layouter.namespace(|| "encoding base"),
+        config.advices[0],
+        pallas::Base::from(SUDOKU_ENCODING_BASE),
+    )?;
+    let zero = assign_free_constant(
+        layouter.namespace(|| "zero"),
+        config.advices[0],
+        pallas::Base::zero(),
+    )?;
+
+    let mut pack = |cells: &[AssignedCell<pallas::Base, pallas::Base>]| {
+        cells.iter().try_fold(zero.clone(), |acc, cell| {
+            let acc =
+                MulInstructions::mul(&mul_chip, layouter.namespace(|| "acc * base"), &acc, &base)?;
+            AddInstructions::add(&add_chip, layouter.namespace(|| "acc + cell"), &acc, cell)
+        })
+    };
+    let first = pack(&cells[..SUDOKU_ENCODING_SPLIT])?;
+    let last = pack(&cells[SUDOKU_ENCODING_SPLIT..])?;
+
+    poseidon_hash_gadget(
+        config.poseidon_config.clone(),
+        layouter.namespace(|| "sudoku state encoding"),
+        [first, last],
+    )
+}
+
+// Constrain the value is nonzero
+fn constrain_non_zero(
+    mut layouter: impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    value: &AssignedCell<pallas::Base, pallas::Base>,
+    one: &AssignedCell<pallas::Base, pallas::Base>,
+) -> Result<(), Error> {
+    let mul_chip = MulChip::construct(config.mul_config.clone());
+    let inv = assign_free_advice(
+        layouter.namespace(|| "witness inverse"),
+        config.advices[0],
+        value
+            .value()
+            .map(|v| v.invert().unwrap_or(pallas::Base::zero())),
+    )?;
+    let product = MulInstructions::mul(
+        &mul_chip,
+        layouter.namespace(|| "value * inverse"),
+        value,
+        &inv,
+    )?;
+    layouter.assign_region(
+        || "value * inverse = 1",
+        |mut region| region.constrain_equal(product.cell(), one.cell()),
+    )
+}
+
+// Check the board is a valid partial solution: every cell is in [0, 9] and the nonzero cells in
+// every row, column and box are distinct.
+pub(crate) fn check_sudoku_state(
+    mut layouter: impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    cells: &[AssignedCell<pallas::Base, pallas::Base>],
+) -> Result<(), Error> {
+    let add_chip = AddChip::construct(config.add_config.clone(), ());
+    let sub_chip = SubChip::construct(config.sub_config.clone(), ());
+    let mul_chip = MulChip::construct(config.mul_config.clone());
+    let resource_commit_chip = ResourceCommitChip::construct(config.resource_commit_config.clone());
+    let zero = assign_free_constant(
+        layouter.namespace(|| "zero"),
+        config.advices[0],
+        pallas::Base::zero(),
+    )?;
+    let one = assign_free_constant(
+        layouter.namespace(|| "one"),
+        config.advices[0],
+        pallas::Base::one(),
+    )?;
+    let nine = assign_free_constant(
+        layouter.namespace(|| "nine"),
+        config.advices[0],
+        pallas::Base::from(9u64),
+    )?;
+
+    // The blank cell i is replaced with 10 + i, so blank cells never collide.
+    let mut distinct_cells = vec![];
+    for (i, cell) in cells.iter().enumerate() {
+        // cell and 9 - cell are in [0, 16)
+        range_check_bits(
+            layouter.namespace(|| "cell range check"),
+            resource_commit_chip.get_lookup_config(),
+            cell.clone(),
+            4,
+        )?;
+        let nine_minus_cell =
+            SubInstructions::sub(&sub_chip, layouter.namespace(|| "9 - cell"), &nine, cell)?;
+        range_check_bits(
+            layouter.namespace(|| "9 - cell range check"),
+            resource_commit_chip.get_lookup_config(),
+            nine_minus_cell,
+            4,
+        )?;
+
+        // is_blank = 1 - cell * cell^(-1) and cell * is_blank = 0
+        let inv = assign_free_advice(
+            layouter.namespace(|| "witness cell inverse"),
+            config.advices[0],
+            cell.value()
+                .map(|v| v.invert().unwrap_or(pallas::Base::zero())),
+        )?;
+        let cell_mul_inv = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "cell * inverse"),
+            cell,
+            &inv,
+        )?;
+        let is_blank = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "is_blank"),
+            &one,
+            &cell_mul_inv,
+        )?;
+        let cell_mul_is_blank = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "cell * is_blank"),
+            cell,
+            &is_blank,
+        )?;
+        layouter.assign_region(
+            || "cell * is_blank = 0",
+            |mut region| region.constrain_equal(cell_mul_is_blank.cell(), zero.cell()),
+        )?;
+
+        let blank_replacement = assign_free_constant(
+            layouter.namespace(|| "blank replacement"),
+            config.advices[0],
+            pallas::Base::from(10 + i as u64),
+        )?;
+        let shift = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "is_blank * replacement"),
+            &is_blank,
+            &blank_replacement,
+        )?;
+        distinct_cells.push(AddInstructions::add(
+            &add_chip,
+            layouter.namespace(|| "cell + shift"),
+            cell,
+            &shift,
+        )?);
+    }
+
+    let rows = (0..9).map(|r| (0..9).map(|c| r * 9 + c).collect::<Vec<_>>());
+    let cols = (0..9).map(|c| (0..9).map(|r| r * 9 + c).collect::<Vec<_>>());
+    let boxes = (0..9).map(|b| {
+        (0..9)
+            .map(|i| (b / 3 * 3 + i / 3) * 9 + b % 3 * 3 + i % 3)
+            .collect::<Vec<_>>()
+    });
+    for group in rows.chain(cols).chain(boxes) {
+        // The product of the pairwise differences is nonzero
+        let mut product = one.clone();
+        for i in 0..group.len() {
+            for j in (i + 1)..group.len() {
+                let diff = SubInstructions::sub(
+                    &sub_chip,
+                    layouter.namespace(|| "diff"),
+                    &distinct_cells[group[i]],
+                    &distinct_cells[group[j]],
+                )?;
+                product = MulInstructions::mul(
+                    &mul_chip,
+                    layouter.namespace(|| "product * diff"),
+                    &product,
+                    &diff,
+                )?;
+            }
+        }
+        constrain_non_zero(
+            layouter.namespace(|| "distinct cells"),
+            config,
+            &product,
+            &one,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Check the board keeps the nonzero cells of the base board if the flag is set
+pub(crate) fn check_sudoku_state_extends(
+    mut layouter: impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    flag: &AssignedCell<pallas::Base, pallas::Base>,
+    base_cells: &[AssignedCell<pallas::Base, pallas::Base>],
+    cells: &[AssignedCell<pallas::Base, pallas::Base>],
+) -> Result<(), Error> {
+    let sub_chip = SubChip::construct(config.sub_config.clone(), ());
+    let mul_chip = MulChip::construct(config.mul_config.clone());
+    let zero = assign_free_constant(
+        layouter.namespace(|| "zero"),
+        config.advices[0],
+        pallas::Base::zero(),
+    )?;
+    for (base_cell, cell) in base_cells.iter().zip(cells.iter()) {
+        // base_cell * (base_cell - cell) = 0
+        let diff = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "base_cell - cell"),
+            base_cell,
+            cell,
+        )?;
+        let product = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "base_cell * diff"),
+            base_cell,
+            &diff,
+        )?;
+        conditional_equal(
+            &mut layouter,
+            &config.conditional_equal_config,
+            "keep the base cell",
+            flag,
+            &product,
+            &zero,
+        )?;
+    }
+    Ok(())
+}
+
+// SudokuResourceLogicCircuit
+#[derive(Clone, Debug, Default)]
+pub struct SudokuResourceLogicCircuit {
+    pub self_resource: ResourceExistenceWitness,
+    // The resource of the next board. If the self_resource is an output resource, a dummy one is needed.
+    pub next_resource: ResourceExistenceWitness,
+    pub puzzle: SudokuState,
+    // The board of the consumed resource. If the self_resource is an output resource, it's not used.
+    pub previous_state: SudokuState,
+    // The board of the created resource
+    pub current_state: SudokuState,
+}
+
+impl SudokuResourceLogicCircuit {
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(ResourceLogicRepresentation::Sudoku, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for SudokuResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // load the next resource
+        let next_resource = {
+            let merkle_chip = MerklePoseidonChip::construct(config.merkle_config.clone());
+            let resource_commit_chip =
+                ResourceCommitChip::construct(config.resource_commit_config.clone());
+            load_resource(
+                layouter.namespace(|| "load the next resource"),
+                config.advices,
+                resource_commit_chip,
+                config.conditional_select_config,
+                merkle_chip,
+                &self.next_resource,
+            )?
+        };
+
+        // check the quantity of the sudoku resource
+        let one = assign_free_constant(
+            layouter.namespace(|| "constant one"),
+            config.advices[0],
+            pallas::Base::one(),
+        )?;
+        layouter.assign_region(
+            || "check quantity",
+            |mut region| region.constrain_equal(one.cell(), self_resource.resource.quantity.cell()),
+        )?;
+
+        // check the puzzle is encoded in the label
+        let puzzle_cells = assign_sudoku_state(
+            layouter.namespace(|| "witness puzzle"),
+            &config,
+            &self.puzzle,
+        )?;
+        let encoded_puzzle = encode_sudoku_state_gadget(
+            layouter.namespace(|| "encode puzzle"),
+            &config,
+            &puzzle_cells,
+        )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| {
+                region.constrain_equal(encoded_puzzle.cell(), self_resource.resource.label.cell())
+            },
+        )?;
+
+        // check the previous board is encoded in the value of the consumed resource
+        let previous_cells = assign_sudoku_state(
+            layouter.namespace(|| "witness previous state"),
+            &config,
+            &self.previous_state,
+        )?;
+        let encoded_previous_state = encode_sudoku_state_gadget(
+            layouter.namespace(|| "encode previous state"),
+            &config,
+            &previous_cells,
+        )?;
+        conditional_equal(
+            &mut layouter,
+            &config.conditional_equal_config,
+            "previous state",
+            &self_resource.is_input,
+            &encoded_previous_state,
+            &self_resource.resource.value,
+        )?;
+
+        // check the current board is encoded in the value of the created resource, i.e. the
+        // next resource when consuming the self resource, or the self resource when creating it.
+        let current_cells = assign_sudoku_state(
+            layouter.namespace(|| "witness current state"),
+            &config,
+            &self.current_state,
+        )?;
+        let encoded_current_state = encode_sudoku_state_gadget(
+            layouter.namespace(|| "encode current state"),
+            &config,
+            &current_cells,
+        )?;
+        let created_value = layouter.assign_region(
+            || "conditional select: created value",
+            |mut region| {
+                config.conditional_select_config.assign_region(
+                    &self_resource.is_input,
+                    &next_resource.resource.value,
+                    &self_resource.resource.value,
+                    0,
+                    &mut region,
+                )
+            },
+        )?;
+        layouter.assign_region(
+            || "check current state",
+            |mut region| region.constrain_equal(encoded_current_state.cell(), created_value.cell()),
+        )?;
+
+        // check the next resource is a created resource of the same puzzle
+        let zero = assign_free_constant(
+            layouter.namespace(|| "constant zero"),
+            config.advices[0],
+            pallas::Base::zero(),
+        )?;
+        for (name, lhs, rhs) in [
+            (
+                "next resource root",
+                &self_resource.resource_merkle_root,
+                &next_resource.resource_merkle_root,
+            ),
+            ("next resource is output", &zero, &next_resource.is_input),
+            (
+                "next resource logic",
+                &self_resource.resource.logic,
+                &next_resource.resource.logic,
+            ),
+            (
+                "next resource label",
+                &self_resource.resource.label,
+                &next_resource.resource.label,
+            ),
+        ] {
+            conditional_equal(
+                &mut layouter,
+                &config.conditional_equal_config,
+                name,
+                &self_resource.is_input,
+                lhs,
+                rhs,
+            )?;
+        }
+
+        // the current board is a valid partial solution of the puzzle
+        check_sudoku_state(
+            layouter.namespace(|| "check current state"),
+            &config,
+            &current_cells,
+        )?;
+        check_sudoku_state_extends(
+            layouter.namespace(|| "current state keeps the puzzle"),
+            &config,
+            &one,
+            &puzzle_cells,
+            &current_cells,
+        )?;
+
+        // the current board keeps the previous board when consuming the self resource
+        check_sudoku_state_extends(
+            layouter.namespace(|| "current state keeps the previous state"),
+            &config,
+            &self_resource.is_input,
+            &previous_cells,
+            &current_cells,
+        )?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+resource_logic_circuit_impl!(SudokuResourceLogicCircuit);
+resource_logic_verifying_info_impl!(SudokuResourceLogicCircuit);
+
+impl BorshSerialize for SudokuResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.next_resource.serialize(writer)?;
+        self.puzzle.serialize(writer)?;
+        self.previous_state.serialize(writer)?;
+        self.current_state.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for SudokuResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let next_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let puzzle = SudokuState::deserialize_reader(reader)?;
+        let previous_state = SudokuState::deserialize_reader(reader)?;
+        let current_state = SudokuState::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            next_resource,
+            puzzle,
+            previous_state,
+            current_state,
+        })
+    }
+}
+
+// The ephemeral resource of the puzzle board to start the game
+pub fn create_sudoku_genesis_resource<R: RngCore>(
+    mut rng: R,
+    puzzle: &SudokuState,
+    nk: pallas::Base,
+) -> Resource {
+    let encoded_puzzle = puzzle.encode();
+    let rseed = pallas::Base::random(&mut rng);
+    let nonce = Nullifier::random(&mut rng);
+    Resource::new_input_resource(
+        *COMPRESSED_SUDOKU_VK,
+        encoded_puzzle,
+        encoded_puzzle,
+        1u64,
+        nk,
+        nonce,
+        true,
+        rseed,
+    )
+}
+
+pub fn create_sudoku_resource<R: RngCore>(
+    mut rng: R,
+    puzzle: &SudokuState,
+    state: &SudokuState,
+    npk: pallas::Base,
+) -> Resource {
+    let rseed = pallas::Base::random(&mut rng);
+    Resource::new_output_resource(
+        *COMPRESSED_SUDOKU_VK,
+        puzzle.encode(),
+        state.encode(),
+        1u64,
+        npk,
+        false,
+        rseed,
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use crate::nullifier::NullifierKeyContainer;
+    use crate::resource_tree::ResourceMerkleTreeLeaves;
+    use halo2_proofs::dev::MockProver;
+
+    pub fn puzzle() -> SudokuState {
+        SudokuState::new([
+            [5, 0, 1, 6, 7, 2, 4, 3, 9],
+            [7, 0, 2, 8, 4, 3, 6, 5, 1],
+            [3, 0, 4, 5, 9, 1, 7, 8, 2],
+            [4, 0, 8, 9, 5, 7, 2, 1, 6],
+            [2, 0, 6, 1, 8, 4, 9, 7, 3],
+            [1, 0, 9, 3, 2, 6, 8, 4, 5],
+            [8, 0, 5, 2, 1, 9, 3, 6, 7],
+            [9, 0, 3, 7, 6, 8, 5, 2, 4],
+            [6, 0, 7, 4, 3, 5, 1, 9, 8],
+        ])
+    }
+
+    pub fn partial_solution() -> SudokuState {
+        SudokuState::new([
+            [5, 8, 1, 6, 7, 2, 4, 3, 9],
+            [7, 9, 2, 8, 4, 3, 6, 5, 1],
+            [3, 6, 4, 5, 9, 1, 7, 8, 2],
+            [4, 3, 8, 9, 5, 7, 2, 1, 6],
+            [2, 0, 6, 1, 8, 4, 9, 7, 3],
+            [1, 0, 9, 3, 2, 6, 8, 4, 5],
+            [8, 0, 5, 2, 1, 9, 3, 6, 7],
+            [9, 0, 3, 7, 6, 8, 5, 2, 4],
+            [6, 0, 7, 4, 3, 5, 1, 9, 8],
+        ])
+    }
+
+    pub fn solution() -> SudokuState {
+        SudokuState::new([
+            [5, 8, 1, 6, 7, 2, 4, 3, 9],
+            [7, 9, 2, 8, 4, 3, 6, 5, 1],
+            [3, 6, 4, 5, 9, 1, 7, 8, 2],
+            [4, 3, 8, 9, 5, 7, 2, 1, 6],
+            [2, 5, 6, 1, 8, 4, 9, 7, 3],
+            [1, 7, 9, 3, 2, 6, 8, 4, 5],
+            [8, 4, 5, 2, 1, 9, 3, 6, 7],
+            [9, 1, 3, 7, 6, 8, 5, 2, 4],
+            [6, 2, 7, 4, 3, 5, 1, 9, 8],
+        ])
+    }
+
+    // The circuits of a move consuming the previous board and creating the current board
+    fn move_circuits(
+        mut rng: impl RngCore,
+        previous_resource: Resource,
+        previous_state: &SudokuState,
+        current_state: &SudokuState,
+    ) -> (SudokuResourceLogicCircuit, SudokuResourceLogicCircuit) {
+        let npk = NullifierKeyContainer::from_key(pallas::Base::random(&mut rng)).get_npk();
+        let current_resource = create_sudoku_resource(&mut rng, &puzzle(), current_state, npk);
+        let previous_nf = previous_resource.get_nf().unwrap().inner();
+        let current_cm = current_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![previous_nf, current_cm]);
+        let previous_witness = ResourceExistenceWitness::new(
+            previous_resource,
+            resource_merkle_tree.generate_path(previous_nf).unwrap(),
+        );
+        let current_witness = ResourceExistenceWitness::new(
+            current_resource,
+            resource_merkle_tree.generate_path(current_cm).unwrap(),
+        );
+
+        let input_circuit = SudokuResourceLogicCircuit {
+            self_resource: previous_witness,
+            next_resource: current_witness,
+            puzzle: puzzle(),
+            previous_state: previous_state.clone(),
+            current_state: current_state.clone(),
+        };
+        let output_circuit = SudokuResourceLogicCircuit {
+            self_resource: current_witness,
+            next_resource: ResourceExistenceWitness::default(), // a dummy resource
+            puzzle: puzzle(),
+            previous_state: SudokuState::default(),
+            current_state: current_state.clone(),
+        };
+        (input_circuit, output_circuit)
+    }
+
+    fn verify(circuit: &SudokuResourceLogicCircuit) -> bool {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+        .is_ok()
+    }
+
+    #[test]
+    fn test_halo2_sudoku_resource_logic_circuit() {
+        let mut rng = OsRng;
+        let nk = pallas::Base::random(&mut rng);
+
+        // Start the game
+        let genesis_resource = create_sudoku_genesis_resource(&mut rng, &puzzle(), nk);
+        let (input_circuit, output_circuit) =
+            move_circuits(&mut rng, genesis_resource, &puzzle(), &puzzle());
+        let input_circuit = {
+            let circuit_bytes = input_circuit.to_bytes();
+            SudokuResourceLogicCircuit::from_bytes(&circuit_bytes)
+        };
+        assert!(verify(&input_circuit));
+        assert!(verify(&output_circuit));
+
+        // Fill some cells
+        let mut previous_resource = create_sudoku_resource(
+            &mut rng,
+            &puzzle(),
+            &puzzle(),
+            NullifierKeyContainer::from_key(nk).get_npk(),
+        );
+        previous_resource.nk_container = NullifierKeyContainer::from_key(nk);
+        let (input_circuit, output_circuit) =
+            move_circuits(&mut rng, previous_resource, &puzzle(), &partial_solution());
+        assert!(verify(&input_circuit));
+        assert!(verify(&output_circuit));
+
+        // Solve the puzzle
+        let (input_circuit, output_circuit) =
+            move_circuits(&mut rng, previous_resource, &puzzle(), &solution());
+        assert!(verify(&input_circuit));
+        assert!(verify(&output_circuit));
+
+        // A board with duplicated digits is not valid
+        let mut invalid_state = partial_solution();
+        invalid_state.state[4][1] = 2;
+        let (_, output_circuit) =
+            move_circuits(&mut rng, previous_resource, &puzzle(), &invalid_state);
+        assert!(!verify(&output_circuit));
+
+        // A move can't erase the filled cells
+        let mut previous_state = partial_solution();
+        previous_state.state[4][1] = 5;
+        previous_resource.value = previous_state.encode();
+        let (input_circuit, _) = move_circuits(
+            &mut rng,
+            previous_resource,
+            &previous_state,
+            &partial_solution(),
+        );
+        assert!(!verify(&input_circuit));
+    }
+}
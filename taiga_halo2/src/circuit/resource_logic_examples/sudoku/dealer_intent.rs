@@ -0,0 +1,389 @@
+/// The dealer intent rewards the player who solves a sudoku puzzle.
+/// The dealer creates the ephemeral intent resource in a ptx spending the reward, so the reward is
+/// only balanced when someone consumes the intent. The intent resource can only be consumed
+/// together with creating a solved sudoku resource of the puzzle.
+use crate::{
+    circuit::{
+        blake2s::publicize_default_dynamic_resource_logic_commitments,
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            conditional_equal::conditional_equal,
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+        },
+        integrity::load_resource,
+        merkle_circuit::MerklePoseidonChip,
+        resource_commitment::ResourceCommitChip,
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::sudoku::{
+            assign_sudoku_state, encode_sudoku_state_gadget, SudokuState, COMPRESSED_SUDOKU_VK,
+        },
+    },
+    constant::SETUP_PARAMS_MAP,
+    error::TransactionError,
+    nullifier::Nullifier,
+    proof::Proof,
+    resource::{RandomSeed, Resource},
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    utils::poseidon_hash,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::{
+    circuit::{floor_planner, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::pallas;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+lazy_static! {
+    pub static ref DEALER_INTENT_VK: ResourceLogicVerifyingKey =
+        DealerIntentResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_DEALER_INTENT_VK: pallas::Base = DEALER_INTENT_VK.get_compressed();
+}
+
+// DealerIntentResourceLogicCircuit
+#[derive(Clone, Debug, Default)]
+pub struct DealerIntentResourceLogicCircuit {
+    pub self_resource: ResourceExistenceWitness,
+    // constraints will be enabled only when consuming the intent resource, otherwise it's a dummy one
+    pub puzzle_resource: ResourceExistenceWitness,
+    pub puzzle: SudokuState,
+    // When creating the intent resource, the solution is not needed.
+    pub solution: SudokuState,
+}
+
+impl DealerIntentResourceLogicCircuit {
+    pub fn encode_label(puzzle: &SudokuState) -> pallas::Base {
+        poseidon_hash(puzzle.encode(), *COMPRESSED_SUDOKU_VK)
+    }
+
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(
+            ResourceLogicRepresentation::SudokuDealerIntent,
+            self.to_bytes(),
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+impl ResourceLogicCircuit for DealerIntentResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // load the puzzle resource
+        let puzzle_resource = {
+            let merkle_chip = MerklePoseidonChip::construct(config.merkle_config.clone());
+            let resource_commit_chip =
+                ResourceCommitChip::construct(config.resource_commit_config.clone());
+            load_resource(
+                layouter.namespace(|| "load the puzzle resource"),
+                config.advices,
+                resource_commit_chip,
+                config.conditional_select_config,
+                merkle_chip,
+                &self.puzzle_resource,
+            )?
+        };
+
+        // check the intent resource is ephemeral
+        let one = assign_free_constant(
+            layouter.namespace(|| "constant one"),
+            config.advices[0],
+            pallas::Base::one(),
+        )?;
+        layouter.assign_region(
+            || "check is_ephemeral",
+            |mut region| {
+                region.constrain_equal(one.cell(), self_resource.resource.is_ephemeral.cell())
+            },
+        )?;
+
+        // check the label
+        let sudoku_vk = assign_free_constant(
+            layouter.namespace(|| "sudoku vk"),
+            config.advices[0],
+            *COMPRESSED_SUDOKU_VK,
+        )?;
+        let puzzle_cells = assign_sudoku_state(
+            layouter.namespace(|| "witness puzzle"),
+            &config,
+            &self.puzzle,
+        )?;
+        let encoded_puzzle = encode_sudoku_state_gadget(
+            layouter.namespace(|| "encode puzzle"),
+            &config,
+            &puzzle_cells,
+        )?;
+        let label = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "label encoding"),
+            [encoded_puzzle.clone(), sudoku_vk.clone()],
+        )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| region.constrain_equal(label.cell(), self_resource.resource.label.cell()),
+        )?;
+
+        // check the solution is encoded in the value of the puzzle resource
+        let solution_cells = assign_sudoku_state(
+            layouter.namespace(|| "witness solution"),
+            &config,
+            &self.solution,
+        )?;
+        let encoded_solution = encode_sudoku_state_gadget(
+            layouter.namespace(|| "encode solution"),
+            &config,
+            &solution_cells,
+        )?;
+
+        // check the puzzle resource is a created sudoku resource of the puzzle when consuming the intent resource
+        let zero = assign_free_constant(
+            layouter.namespace(|| "constant zero"),
+            config.advices[0],
+            pallas::Base::zero(),
+        )?;
+        for (name, lhs, rhs) in [
+            (
+                "puzzle resource root",
+                &self_resource.resource_merkle_root,
+                &puzzle_resource.resource_merkle_root,
+            ),
+            (
+                "puzzle resource is output",
+                &zero,
+                &puzzle_resource.is_input,
+            ),
+            (
+                "puzzle resource logic",
+                &sudoku_vk,
+                &puzzle_resource.resource.logic,
+            ),
+            (
+                "puzzle resource label",
+                &encoded_puzzle,
+                &puzzle_resource.resource.label,
+            ),
+            (
+                "puzzle resource value",
+                &encoded_solution,
+                &puzzle_resource.resource.value,
+            ),
+        ] {
+            conditional_equal(
+                &mut layouter,
+                &config.conditional_equal_config,
+                name,
+                &self_resource.is_input,
+                lhs,
+                rhs,
+            )?;
+        }
+
+        // check the solution has no blank cells. The sudoku resource logic checks it's a valid
+        // board of the puzzle.
+        let mul_chip = MulChip::construct(config.mul_config.clone());
+        let mut product = one.clone();
+        for cell in solution_cells.iter() {
+            product = MulInstructions::mul(
+                &mul_chip,
+                layouter.namespace(|| "product * cell"),
+                &product,
+                cell,
+            )?;
+        }
+        let product_inv = assign_free_advice(
+            layouter.namespace(|| "witness product inverse"),
+            config.advices[0],
+            product
+                .value()
+                .map(|v| v.invert().unwrap_or(pallas::Base::zero())),
+        )?;
+        let product_mul_inv = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "product * inverse"),
+            &product,
+            &product_inv,
+        )?;
+        conditional_equal(
+            &mut layouter,
+            &config.conditional_equal_config,
+            "solution is complete",
+            &self_resource.is_input,
+            &product_mul_inv,
+            &one,
+        )?;
+
+        // Publicize the dynamic resource_logic commitments with default value
+        publicize_default_dynamic_resource_logic_commitments(
+            &mut layouter,
+            config.advices[0],
+            config.instances,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, mut rng: impl RngCore) -> ResourceLogicPublicInputs {
+        let mut public_inputs = self.get_mandatory_public_inputs();
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(default_resource_logic_cm);
+        public_inputs.extend(self.get_validity_window_public_inputs());
+        let padding = ResourceLogicPublicInputs::get_public_input_padding(
+            public_inputs.len(),
+            &RandomSeed::random(&mut rng),
+        );
+        public_inputs.extend(padding);
+        public_inputs.into()
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+resource_logic_circuit_impl!(DealerIntentResourceLogicCircuit);
+resource_logic_verifying_info_impl!(DealerIntentResourceLogicCircuit);
+
+impl BorshSerialize for DealerIntentResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.puzzle_resource.serialize(writer)?;
+        self.puzzle.serialize(writer)?;
+        self.solution.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for DealerIntentResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let puzzle_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let puzzle = SudokuState::deserialize_reader(reader)?;
+        let solution = SudokuState::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            puzzle_resource,
+            puzzle,
+            solution,
+        })
+    }
+}
+
+pub fn create_intent_resource<R: RngCore>(
+    mut rng: R,
+    puzzle: &SudokuState,
+    nk: pallas::Base,
+) -> Resource {
+    let label = DealerIntentResourceLogicCircuit::encode_label(puzzle);
+    let rseed = pallas::Base::random(&mut rng);
+    let nonce = Nullifier::random(&mut rng);
+    Resource::new_input_resource(
+        *COMPRESSED_DEALER_INTENT_VK,
+        label,
+        pallas::Base::zero(),
+        1u64,
+        nk,
+        nonce,
+        true,
+        rseed,
+    )
+}
+
+#[test]
+fn test_halo2_sudoku_dealer_intent_resource_logic_circuit() {
+    use crate::circuit::resource_logic_examples::sudoku::{
+        create_sudoku_resource,
+        tests::{partial_solution, puzzle, solution},
+    };
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use crate::nullifier::NullifierKeyContainer;
+    use crate::resource_tree::ResourceMerkleTreeLeaves;
+    use halo2_proofs::dev::MockProver;
+
+    let mut rng = OsRng;
+    let nk = pallas::Base::random(&mut rng);
+    let intent_resource = create_intent_resource(&mut rng, &puzzle(), nk);
+    let npk = NullifierKeyContainer::from_key(pallas::Base::random(&mut rng)).get_npk();
+
+    let verify = |circuit: &DealerIntentResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+        .is_ok()
+    };
+    let consume_intent = |state: &SudokuState| {
+        let puzzle_resource = create_sudoku_resource(OsRng, &puzzle(), state, npk);
+        let intent_nf = intent_resource.get_nf().unwrap().inner();
+        let puzzle_cm = puzzle_resource.commitment().inner();
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(vec![intent_nf, puzzle_cm]);
+        DealerIntentResourceLogicCircuit {
+            self_resource: ResourceExistenceWitness::new(
+                intent_resource,
+                resource_merkle_tree.generate_path(intent_nf).unwrap(),
+            ),
+            puzzle_resource: ResourceExistenceWitness::new(
+                puzzle_resource,
+                resource_merkle_tree.generate_path(puzzle_cm).unwrap(),
+            ),
+            puzzle: puzzle(),
+            solution: state.clone(),
+        }
+    };
+
+    // Create the intent resource
+    {
+        let intent_cm = intent_resource.commitment().inner();
+        let resource_merkle_tree =
+            ResourceMerkleTreeLeaves::new(vec![Nullifier::random(&mut rng).inner(), intent_cm]);
+        let intent_witness = ResourceExistenceWitness::new(
+            intent_resource,
+            resource_merkle_tree.generate_path(intent_cm).unwrap(),
+        );
+        let circuit = DealerIntentResourceLogicCircuit {
+            self_resource: intent_witness,
+            // the puzzle resource won't be checked.
+            puzzle_resource: intent_witness,
+            puzzle: puzzle(),
+            solution: SudokuState::default(),
+        };
+        let circuit = {
+            let circuit_bytes = circuit.to_bytes();
+            DealerIntentResourceLogicCircuit::from_bytes(&circuit_bytes)
+        };
+        assert!(verify(&circuit));
+    }
+
+    // Consume the intent resource with the solution
+    assert!(verify(&consume_intent(&solution())));
+
+    // The intent resource can't be consumed without solving the puzzle
+    assert!(!verify(&consume_intent(&partial_solution())));
+}
@@ -1,12 +1,25 @@
 use crate::{
     circuit::{
         blake2s::{resource_logic_commitment_gadget, Blake2sChip},
-        gadgets::{assign_free_advice, assign_free_constant, poseidon_hash::poseidon_hash_gadget},
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            boolean::{BooleanChip, BooleanInstructions},
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+            sub::{SubChip, SubInstructions},
+        },
         resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
         resource_logic_circuit::{
             ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
             ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
         },
+        resource_logic_examples::allowlist::{
+            AllowlistResourceLogicCircuit, COMPRESSED_ALLOWLIST_VK,
+        },
+        resource_logic_examples::htlc::{HtlcPolicy, HtlcResourceLogicCircuit, HtlcSpend},
+        resource_logic_examples::multisig_verification::{
+            MultisigPolicy, MultisigVerificationResourceLogicCircuit,
+        },
         resource_logic_examples::receiver_resource_logic::{
             ReceiverResourceLogicCircuit, COMPRESSED_RECEIVER_VK,
         },
@@ -15,7 +28,8 @@ use crate::{
         },
     },
     constant::{
-        PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_1_CM_R,
+        TaigaFixedBases, PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_1_CM_R,
+        PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_2_CM_R,
         RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_1,
         RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_2,
         RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1,
@@ -23,21 +37,25 @@ use crate::{
         TAIGA_RESOURCE_TREE_DEPTH,
     },
     error::TransactionError,
-    merkle_tree::LR,
+    keys::{OutgoingViewingKey, PaymentAddress, SpendingKey},
+    merkle_tree::{MerklePath, LR},
     nullifier::Nullifier,
     proof::Proof,
     resource::{RandomSeed, Resource, ResourceLogics},
+    resource_encryption::OutgoingCiphertext,
     resource_logic_commitment::ResourceLogicCommitment,
     resource_logic_vk::ResourceLogicVerifyingKey,
     resource_tree::ResourceExistenceWitness,
-    utils::{poseidon_hash_n, read_base_field, read_point},
+    transaction::ValidityWindow,
+    utils::{poseidon_hash, poseidon_hash_n, read_base_field, read_point},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
+use byteorder::{ReadBytesExt, WriteBytesExt};
 use ff::Field;
 use group::{Curve, Group, GroupEncoding};
 use halo2_gadgets::ecc::{chip::EccChip, NonIdentityPoint};
 use halo2_proofs::{
-    circuit::{floor_planner, Layouter, Value},
+    circuit::{floor_planner, AssignedCell, Layouter, Value},
     plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
 };
 use lazy_static::lazy_static;
@@ -45,6 +63,11 @@ use pasta_curves::arithmetic::CurveAffine;
 use pasta_curves::{group::ff::PrimeField, pallas};
 use rand::{rngs::OsRng, Rng, RngCore};
 
+pub mod issuance;
+pub mod transfer;
+pub use issuance::{IssuedToken, IssuedTokenResource};
+pub use transfer::TransferBuilder;
+
 lazy_static! {
     pub static ref TOKEN_VK: ResourceLogicVerifyingKey =
         TokenResourceLogicCircuit::default().get_resource_logic_vk();
@@ -55,6 +78,10 @@ lazy_static! {
 pub struct TokenName(String);
 
 impl TokenName {
+    pub fn new(name: String) -> Self {
+        Self(name)
+    }
+
     pub fn encode(&self) -> pallas::Base {
         assert!(self.0.len() < 32);
         let mut bytes: [u8; 32] = [0; 32];
@@ -62,6 +89,15 @@ impl TokenName {
         pallas::Base::from_repr(bytes).unwrap()
     }
 
+    // The label of a compliance-gated token fixes the allowlist root as well.
+    // label = poseidon_hash(token_name, allowlist_root)
+    pub fn encode_label(&self, allowlist_root: Option<pallas::Base>) -> pallas::Base {
+        match allowlist_root {
+            Some(root) => poseidon_hash(self.encode(), root),
+            None => self.encode(),
+        }
+    }
+
     pub fn inner(&self) -> String {
         self.0.clone()
     }
@@ -121,6 +157,7 @@ impl Token {
         TokenResource {
             token_name: self.name().clone(),
             resource,
+            allowlist_root: None,
         }
     }
 
@@ -146,14 +183,65 @@ impl Token {
         TokenResource {
             token_name: self.name().clone(),
             resource,
+            allowlist_root: None,
         }
     }
+
+    /// Create an input token resource owned by the spending key.
+    pub fn create_random_input_token_resource_from_key<R: RngCore>(
+        &self,
+        rng: R,
+        sk: &SpendingKey,
+    ) -> TokenResource {
+        let auth = TokenAuthorization::from_address(&sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+        self.create_random_input_token_resource(rng, sk.get_nk(), &auth)
+    }
+
+    /// Create an output token resource sent to the payment address.
+    pub fn create_random_output_token_resource_to_address<R: RngCore>(
+        &self,
+        rng: R,
+        address: &PaymentAddress,
+    ) -> TokenResource {
+        let auth = TokenAuthorization::from_address(address);
+        self.create_random_output_token_resource(rng, address.get_npk(), &auth)
+    }
+
+    /// Create an input resource of the compliance-gated token with the allowlist root.
+    pub fn create_random_input_regulated_token_resource<R: RngCore>(
+        &self,
+        rng: R,
+        nk: pallas::Base,
+        auth: &TokenAuthorization,
+        allowlist_root: pallas::Base,
+    ) -> TokenResource {
+        let mut token_resource = self.create_random_input_token_resource(rng, nk, auth);
+        token_resource.set_allowlist_root(allowlist_root);
+        token_resource
+    }
+
+    /// Create an output resource of the compliance-gated token with the allowlist root. The
+    /// output resource logics must be generated by
+    /// `TokenResource::generate_output_token_resource_logics_with_allowlist`.
+    pub fn create_random_output_regulated_token_resource<R: RngCore>(
+        &self,
+        rng: R,
+        npk: pallas::Base,
+        auth: &TokenAuthorization,
+        allowlist_root: pallas::Base,
+    ) -> TokenResource {
+        let mut token_resource = self.create_random_output_token_resource(rng, npk, auth);
+        token_resource.set_allowlist_root(allowlist_root);
+        token_resource
+    }
 }
 
-#[derive(Clone, Debug, Default, BorshDeserialize, BorshSerialize)]
+#[derive(Clone, Debug, Default)]
 pub struct TokenResource {
     pub token_name: TokenName,
     pub resource: Resource,
+    // The allowlist root of a compliance-gated token, it's fixed in the label.
+    pub allowlist_root: Option<pallas::Base>,
 }
 
 impl std::ops::Deref for TokenResource {
@@ -165,6 +253,31 @@ impl std::ops::Deref for TokenResource {
 }
 
 impl TokenResource {
+    /// Recover the token resource from a resource, e.g. the one found by the wallet.
+    pub fn from_resource(resource: Resource) -> Option<Self> {
+        if resource.get_logic() != *COMPRESSED_TOKEN_VK {
+            return None;
+        }
+
+        let bytes = resource.get_label().to_repr();
+        let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        let token_name = TokenName(String::from_utf8(bytes[..len].to_vec()).ok()?);
+        if len >= 32 || token_name.encode() != resource.get_label() {
+            return None;
+        }
+
+        Some(Self {
+            token_name,
+            resource,
+            allowlist_root: None,
+        })
+    }
+
+    fn set_allowlist_root(&mut self, allowlist_root: pallas::Base) {
+        self.resource.kind.label = self.token_name.encode_label(Some(allowlist_root));
+        self.allowlist_root = Some(allowlist_root);
+    }
+
     pub fn token_name(&self) -> &TokenName {
         &self.token_name
     }
@@ -195,6 +308,7 @@ impl TokenResource {
             token_name: self.token_name.clone(),
             auth,
             receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            allowlist_root: self.allowlist_root,
             rseed: RandomSeed::random(&mut rng),
         };
 
@@ -205,6 +319,7 @@ impl TokenResource {
             auth.vk,
             auth_sk,
             *COMPRESSED_RECEIVER_VK,
+            auth.rcv_pk,
         );
 
         ResourceLogics::new(
@@ -213,18 +328,164 @@ impl TokenResource {
         )
     }
 
-    pub fn generate_output_token_resource_logics<R: RngCore>(
+    /// Generate the input resource logics for the resource controlled by a multisig policy.
+    /// The auth must be created by `MultisigPolicy::to_token_authorization`, and the signers
+    /// must be in the policy.
+    pub fn generate_input_token_resource_logics_with_multisig<R: RngCore>(
+        &self,
+        mut rng: R,
+        auth: TokenAuthorization,
+        policy: MultisigPolicy,
+        signer_sks: &[pallas::Scalar],
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> Result<ResourceLogics, TransactionError> {
+        // token resource logic
+        let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
+        let token_resource_logic = TokenResourceLogicCircuit {
+            self_resource,
+            token_name: self.token_name.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            allowlist_root: self.allowlist_root,
+            rseed: RandomSeed::random(&mut rng),
+        };
+
+        // multisig auth resource logic
+        let token_auth_resource_logic =
+            MultisigVerificationResourceLogicCircuit::from_sks_and_sign(
+                &mut rng,
+                self_resource,
+                auth.vk,
+                policy,
+                signer_sks,
+                *COMPRESSED_RECEIVER_VK,
+                auth.rcv_pk,
+            )?;
+
+        Ok(ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![Box::new(token_auth_resource_logic)],
+        ))
+    }
+
+    /// Generate the input resource logics for the resource locked by a HTLC. The auth must
+    /// be created by `HtlcPolicy::to_token_authorization`, and the window must be the validity
+    /// window of the transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_input_token_resource_logics_with_htlc<R: RngCore>(
         &self,
         mut rng: R,
         auth: TokenAuthorization,
+        policy: HtlcPolicy,
+        spend: HtlcSpend,
+        window: ValidityWindow,
         merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
     ) -> ResourceLogics {
+        // token resource logic
         let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
         let token_resource_logic = TokenResourceLogicCircuit {
             self_resource,
             token_name: self.token_name.clone(),
             auth,
             receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            allowlist_root: self.allowlist_root,
+            rseed: RandomSeed::random(&mut rng),
+        };
+
+        // htlc auth resource logic
+        let token_auth_resource_logic = HtlcResourceLogicCircuit::from_spend_and_sign(
+            &mut rng,
+            self_resource,
+            auth.vk,
+            policy,
+            spend,
+            window,
+            *COMPRESSED_RECEIVER_VK,
+            auth.rcv_pk,
+        );
+
+        ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![Box::new(token_auth_resource_logic)],
+        )
+    }
+
+    pub fn generate_output_token_resource_logics<R: RngCore>(
+        &self,
+        rng: R,
+        auth: TokenAuthorization,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> ResourceLogics {
+        let (token_resource_logic, receiver_resource_logic) =
+            self.create_output_resource_logic_circuits(rng, auth, merkle_path);
+        ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![Box::new(receiver_resource_logic)],
+        )
+    }
+
+    /// Generate the output resource logics together with the outgoing ciphertext, so that
+    /// the sender can recover the output resource with the ovk.
+    pub fn generate_output_token_resource_logics_with_ovk<R: RngCore>(
+        &self,
+        rng: R,
+        auth: TokenAuthorization,
+        ovk: &OutgoingViewingKey,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> (ResourceLogics, OutgoingCiphertext) {
+        let (token_resource_logic, receiver_resource_logic) =
+            self.create_output_resource_logic_circuits(rng, auth, merkle_path);
+        let out_cipher = receiver_resource_logic.get_outgoing_ciphertext(ovk);
+        let resource_logics = ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![Box::new(receiver_resource_logic)],
+        );
+        (resource_logics, out_cipher)
+    }
+
+    /// Generate the output resource logics for a compliance-gated token. The token logic commits
+    /// to the allowlist logic as the second dynamic resource logic, and the allowlist logic
+    /// proves the receiver npk is in the allowlist tree of the root fixed in the label.
+    pub fn generate_output_token_resource_logics_with_allowlist<R: RngCore>(
+        &self,
+        rng: R,
+        auth: TokenAuthorization,
+        allowlist_path: MerklePath,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> ResourceLogics {
+        let allowlist_root = self
+            .allowlist_root
+            .expect("the token is not compliance-gated");
+        let (token_resource_logic, receiver_resource_logic) =
+            self.create_output_resource_logic_circuits(rng, auth, merkle_path);
+        let allowlist_resource_logic = AllowlistResourceLogicCircuit::new(
+            receiver_resource_logic.self_resource,
+            self.token_name.clone(),
+            allowlist_root,
+            allowlist_path,
+        );
+        ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![
+                Box::new(receiver_resource_logic),
+                Box::new(allowlist_resource_logic),
+            ],
+        )
+    }
+
+    fn create_output_resource_logic_circuits<R: RngCore>(
+        &self,
+        mut rng: R,
+        auth: TokenAuthorization,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> (TokenResourceLogicCircuit, ReceiverResourceLogicCircuit) {
+        let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
+        let token_resource_logic = TokenResourceLogicCircuit {
+            self_resource,
+            token_name: self.token_name.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            allowlist_root: self.allowlist_root,
             rseed: RandomSeed::random(&mut rng),
         };
 
@@ -234,14 +495,13 @@ impl TokenResource {
             resource_logic_vk: *COMPRESSED_RECEIVER_VK,
             encrypt_nonce: pallas::Base::from_u128(rng.gen()),
             sk: pallas::Base::random(&mut rng),
-            rcv_pk: auth.pk,
-            auth_resource_logic_vk: *COMPRESSED_TOKEN_AUTH_VK,
+            rcv_pk: auth.rcv_pk,
+            auth_pk: auth.pk,
+            auth_resource_logic_vk: auth.vk,
+            value_data: None,
         };
 
-        ResourceLogics::new(
-            Box::new(token_resource_logic),
-            vec![Box::new(receiver_resource_logic)],
-        )
+        (token_resource_logic, receiver_resource_logic)
     }
 }
 
@@ -254,6 +514,9 @@ pub struct TokenResourceLogicCircuit {
     // The auth goes to value and defines how to consume and create the resource.
     pub auth: TokenAuthorization,
     pub receiver_resource_logic_vk: pallas::Base,
+    // The allowlist root of a compliance-gated token goes to label, and the output resources
+    // have to be checked by the allowlist resource logic.
+    pub allowlist_root: Option<pallas::Base>,
     // rseed is to generate the randomness for resource_logic commitment
     pub rseed: RandomSeed,
 }
@@ -262,6 +525,8 @@ pub struct TokenResourceLogicCircuit {
 pub struct TokenAuthorization {
     pub pk: pallas::Point,
     pub vk: pallas::Base,
+    // The output resources are encrypted to rcv_pk in the receiver resource logic.
+    pub rcv_pk: pallas::Point,
 }
 
 impl Default for TokenAuthorization {
@@ -269,6 +534,7 @@ impl Default for TokenAuthorization {
         Self {
             pk: pallas::Point::generator(),
             vk: pallas::Base::one(),
+            rcv_pk: pallas::Point::generator(),
         }
     }
 }
@@ -294,6 +560,7 @@ impl Default for TokenResourceLogicCircuit {
             token_name: TokenName("Token_name".to_string()),
             auth: TokenAuthorization::default(),
             receiver_resource_logic_vk: pallas::Base::zero(),
+            allowlist_root: None,
             rseed: RandomSeed::default(),
         }
     }
@@ -315,19 +582,51 @@ impl ResourceLogicCircuit for TokenResourceLogicCircuit {
 
         // We can add more constraints on token_property or extend the token_properties.
 
-        // check label
-        layouter.assign_region(
-            || "check label",
+        // Witness the allowlist root, the has_allowlist flag is constrained to be a bit.
+        let has_allowlist = assign_free_advice(
+            layouter.namespace(|| "witness has_allowlist"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.allowlist_root.is_some())),
+        )?;
+        let boolean_chip = BooleanChip::construct(config.boolean_config.clone());
+        boolean_chip.assert_bit(
+            layouter.namespace(|| "has_allowlist is a bit"),
+            &has_allowlist,
+        )?;
+        let allowlist_root = assign_free_advice(
+            layouter.namespace(|| "witness allowlist root"),
+            config.advices[0],
+            Value::known(self.allowlist_root.unwrap_or(pallas::Base::zero())),
+        )?;
+
+        // check label, see `TokenName::encode_label`
+        let regulated_label = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "regulated label encoding"),
+            [token_property.clone(), allowlist_root],
+        )?;
+        let label = layouter.assign_region(
+            || "conditional select: label",
             |mut region| {
-                region.constrain_equal(token_property.cell(), self_resource.resource.label.cell())
+                config.conditional_select_config.assign_region(
+                    &has_allowlist,
+                    &regulated_label,
+                    &token_property,
+                    0,
+                    &mut region,
+                )
             },
         )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| region.constrain_equal(label.cell(), self_resource.resource.label.cell()),
+        )?;
 
         // Construct an ECC chip
         let ecc_chip = EccChip::construct(config.ecc_config);
 
         let pk = NonIdentityPoint::new(
-            ecc_chip,
+            ecc_chip.clone(),
             layouter.namespace(|| "witness pk"),
             Value::known(self.auth.pk.to_affine()),
         )?;
@@ -344,6 +643,12 @@ impl ResourceLogicCircuit for TokenResourceLogicCircuit {
             Value::known(self.receiver_resource_logic_vk),
         )?;
 
+        let rcv_pk = NonIdentityPoint::new(
+            ecc_chip,
+            layouter.namespace(|| "witness rcv_pk"),
+            Value::known(self.auth.rcv_pk.to_affine()),
+        )?;
+
         // Decode the value, and check the value encoding
         let encoded_value = poseidon_hash_gadget(
             config.poseidon_config,
@@ -353,6 +658,8 @@ impl ResourceLogicCircuit for TokenResourceLogicCircuit {
                 pk.inner().y(),
                 auth_resource_logic_vk.clone(),
                 receiver_resource_logic_vk.clone(),
+                rcv_pk.inner().x(),
+                rcv_pk.inner().y(),
             ],
         )?;
 
@@ -425,31 +732,82 @@ impl ResourceLogicCircuit for TokenResourceLogicCircuit {
             RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_2,
         )?;
 
-        // Publicize the second dynamic resource_logic commitment with default value
-        let resource_logic_cm_fields: [pallas::Base; 2] =
-            ResourceLogicCommitment::default().to_public_inputs();
-        let resource_logic_cm_1 = assign_free_advice(
-            layouter.namespace(|| "resource_logic_cm 1"),
+        // Commit the allowlist resource_logic as the second dynamic resource_logic if it's an
+        // output resource of a compliance-gated token; publicize the default value otherwise.
+        let constant_one = assign_free_constant(
+            layouter.namespace(|| "one"),
             config.advices[0],
-            Value::known(resource_logic_cm_fields[0]),
+            pallas::Base::one(),
         )?;
-        let resource_logic_cm_2 = assign_free_advice(
-            layouter.namespace(|| "resource_logic_cm 2"),
-            config.advices[0],
-            Value::known(resource_logic_cm_fields[1]),
+        let sub_chip = SubChip::construct(config.sub_config, ());
+        let is_output = SubInstructions::sub(
+            &sub_chip,
+            layouter.namespace(|| "is_output = 1 - is_input"),
+            &constant_one,
+            &self_resource.is_input,
+        )?;
+        let mul_chip = MulChip::construct(config.mul_config);
+        let is_regulated_output = MulInstructions::mul(
+            &mul_chip,
+            layouter.namespace(|| "is_regulated_output = has_allowlist * is_output"),
+            &has_allowlist,
+            &is_output,
         )?;
 
-        layouter.constrain_instance(
-            resource_logic_cm_1.cell(),
-            config.instances,
-            RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1,
+        let allowlist_resource_logic_vk = assign_free_constant(
+            layouter.namespace(|| "allowlist resource_logic vk"),
+            config.advices[0],
+            *COMPRESSED_ALLOWLIST_VK,
         )?;
-        layouter.constrain_instance(
-            resource_logic_cm_2.cell(),
-            config.instances,
-            RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2,
+        let second_resource_logic_cm_r = assign_free_advice(
+            layouter.namespace(|| "second resource_logic_cm_r"),
+            config.advices[0],
+            Value::known(
+                self.rseed
+                    .get_resource_logic_cm_r(PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_2_CM_R),
+            ),
+        )?;
+        let allowlist_resource_logic_cm = resource_logic_commitment_gadget(
+            &mut layouter,
+            &blake2s_chip,
+            allowlist_resource_logic_vk,
+            second_resource_logic_cm_r,
         )?;
 
+        let default_resource_logic_cm: [pallas::Base; 2] =
+            ResourceLogicCommitment::default().to_public_inputs();
+        for (i, (cm, instance_idx)) in allowlist_resource_logic_cm
+            .iter()
+            .zip([
+                RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1,
+                RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2,
+            ])
+            .enumerate()
+        {
+            let default_cm = assign_free_constant(
+                layouter.namespace(|| format!("default resource_logic_cm {}", i + 1)),
+                config.advices[0],
+                default_resource_logic_cm[i],
+            )?;
+            let second_dynamic_resource_logic_cm = layouter.assign_region(
+                || format!("conditional select: second resource_logic_cm {}", i + 1),
+                |mut region| {
+                    config.conditional_select_config.assign_region(
+                        &is_regulated_output,
+                        cm,
+                        &default_cm,
+                        0,
+                        &mut region,
+                    )
+                },
+            )?;
+            layouter.constrain_instance(
+                second_dynamic_resource_logic_cm.cell(),
+                config.instances,
+                instance_idx,
+            )?;
+        }
+
         Ok(())
     }
 
@@ -469,9 +827,18 @@ impl ResourceLogicCircuit for TokenResourceLogicCircuit {
                 .to_public_inputs();
 
         public_inputs.extend(resource_logic_com);
-        let default_resource_logic_cm: [pallas::Base; 2] =
-            ResourceLogicCommitment::default().to_public_inputs();
-        public_inputs.extend(default_resource_logic_cm);
+        let second_resource_logic_com: [pallas::Base; 2] =
+            if self.allowlist_root.is_some() && !self.get_self_resource().is_input() {
+                let resource_logic_com_r = self
+                    .rseed
+                    .get_resource_logic_cm_r(PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_2_CM_R);
+                ResourceLogicCommitment::commit(&*COMPRESSED_ALLOWLIST_VK, &resource_logic_com_r)
+                    .to_public_inputs()
+            } else {
+                ResourceLogicCommitment::default().to_public_inputs()
+            };
+        public_inputs.extend(second_resource_logic_com);
+        public_inputs.extend(self.get_validity_window_public_inputs());
         let padding = ResourceLogicPublicInputs::get_public_input_padding(
             public_inputs.len(),
             &RandomSeed::random(&mut rng),
@@ -494,6 +861,7 @@ impl BorshSerialize for TokenResourceLogicCircuit {
         self.token_name.serialize(writer)?;
         self.auth.serialize(writer)?;
         writer.write_all(&self.receiver_resource_logic_vk.to_repr())?;
+        write_allowlist_root(writer, &self.allowlist_root)?;
         self.rseed.serialize(writer)?;
 
         Ok(())
@@ -506,21 +874,66 @@ impl BorshDeserialize for TokenResourceLogicCircuit {
         let token_name = TokenName::deserialize_reader(reader)?;
         let auth = TokenAuthorization::deserialize_reader(reader)?;
         let receiver_resource_logic_vk = read_base_field(reader)?;
+        let allowlist_root = read_allowlist_root(reader)?;
         let rseed = RandomSeed::deserialize_reader(reader)?;
         Ok(Self {
             self_resource,
             token_name,
             auth,
             receiver_resource_logic_vk,
+            allowlist_root,
             rseed,
         })
     }
 }
 
+impl BorshSerialize for TokenResource {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.token_name.serialize(writer)?;
+        self.resource.serialize(writer)?;
+        write_allowlist_root(writer, &self.allowlist_root)?;
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for TokenResource {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let token_name = TokenName::deserialize_reader(reader)?;
+        let resource = Resource::deserialize_reader(reader)?;
+        let allowlist_root = read_allowlist_root(reader)?;
+        Ok(Self {
+            token_name,
+            resource,
+            allowlist_root,
+        })
+    }
+}
+
+fn write_allowlist_root<W: std::io::Write>(
+    writer: &mut W,
+    allowlist_root: &Option<pallas::Base>,
+) -> std::io::Result<()> {
+    match allowlist_root {
+        None => writer.write_u8(0),
+        Some(root) => {
+            writer.write_u8(1)?;
+            writer.write_all(&root.to_repr())
+        }
+    }
+}
+
+fn read_allowlist_root<R: std::io::Read>(reader: &mut R) -> std::io::Result<Option<pallas::Base>> {
+    match reader.read_u8()? {
+        0 => Ok(None),
+        _ => Ok(Some(read_base_field(reader)?)),
+    }
+}
+
 impl BorshSerialize for TokenAuthorization {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
         writer.write_all(&self.pk.to_bytes())?;
         writer.write_all(&self.vk.to_repr())?;
+        writer.write_all(&self.rcv_pk.to_bytes())?;
         Ok(())
     }
 }
@@ -529,38 +942,138 @@ impl BorshDeserialize for TokenAuthorization {
     fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let pk = read_point(reader)?;
         let vk = read_base_field(reader)?;
+        let rcv_pk = read_point(reader)?;
 
-        Ok(Self { pk, vk })
+        Ok(Self { pk, vk, rcv_pk })
     }
 }
 
 impl TokenAuthorization {
+    // The resources are encrypted to the authorization pk by default.
     pub fn new(pk: pallas::Point, vk: pallas::Base) -> Self {
-        Self { pk, vk }
+        Self { pk, vk, rcv_pk: pk }
     }
 
     pub fn random<R: RngCore>(mut rng: R) -> Self {
+        let pk = pallas::Point::random(&mut rng);
         Self {
-            pk: pallas::Point::random(&mut rng),
+            pk,
             vk: *COMPRESSED_TOKEN_AUTH_VK,
+            rcv_pk: pk,
         }
     }
 
     pub fn to_value(&self) -> pallas::Base {
         let pk_coord = self.pk.to_affine().coordinates().unwrap();
-        poseidon_hash_n::<4>([
+        let rcv_pk_coord = self.rcv_pk.to_affine().coordinates().unwrap();
+        poseidon_hash_n::<6>([
             *pk_coord.x(),
             *pk_coord.y(),
             self.vk,
             *COMPRESSED_RECEIVER_VK,
+            *rcv_pk_coord.x(),
+            *rcv_pk_coord.y(),
         ])
     }
 
+    // The value can also commit to extra data, e.g. the NFT metadata hash.
+    // value = poseidon_hash(auth_value, data)
+    pub fn to_value_with_data(&self, data: pallas::Base) -> pallas::Base {
+        poseidon_hash(self.to_value(), data)
+    }
+
     pub fn from_sk_vk(sk: &pallas::Scalar, vk: &pallas::Base) -> Self {
         let generator = pallas::Point::generator().to_affine();
         let pk = generator * sk;
-        Self { pk, vk: *vk }
+        Self::new(pk, *vk)
     }
+
+    pub fn from_address(address: &PaymentAddress) -> Self {
+        Self {
+            pk: address.get_auth_pk(),
+            vk: address.get_auth_vk(),
+            rcv_pk: address.get_encryption_pk(),
+        }
+    }
+}
+
+// Witness the optional value data, the has_data flag is constrained to be a bit.
+pub fn assign_value_data(
+    layouter: &mut impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    value_data: Option<pallas::Base>,
+) -> Result<
+    (
+        AssignedCell<pallas::Base, pallas::Base>,
+        AssignedCell<pallas::Base, pallas::Base>,
+    ),
+    Error,
+> {
+    let has_data = assign_free_advice(
+        layouter.namespace(|| "witness has_data"),
+        config.advices[0],
+        Value::known(pallas::Base::from(value_data.is_some())),
+    )?;
+    let boolean_chip = BooleanChip::construct(config.boolean_config.clone());
+    boolean_chip.assert_bit(layouter.namespace(|| "has_data is a bit"), &has_data)?;
+    let data = assign_free_advice(
+        layouter.namespace(|| "witness value data"),
+        config.advices[0],
+        Value::known(value_data.unwrap_or(pallas::Base::zero())),
+    )?;
+
+    Ok((has_data, data))
+}
+
+// Check the value encoding of the token authorization, see `TokenAuthorization::to_value`.
+// The value commits to the data as well if has_data is set, see
+// `TokenAuthorization::to_value_with_data`.
+#[allow(clippy::too_many_arguments)]
+pub fn check_value_encoding(
+    layouter: &mut impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    value: &AssignedCell<pallas::Base, pallas::Base>,
+    pk: &NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>,
+    auth_resource_logic_vk: &AssignedCell<pallas::Base, pallas::Base>,
+    receiver_resource_logic_vk: &AssignedCell<pallas::Base, pallas::Base>,
+    rcv_pk: &NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>,
+    has_data: &AssignedCell<pallas::Base, pallas::Base>,
+    data: &AssignedCell<pallas::Base, pallas::Base>,
+) -> Result<(), Error> {
+    let auth_value = poseidon_hash_gadget(
+        config.poseidon_config.clone(),
+        layouter.namespace(|| "auth value encoding"),
+        [
+            pk.inner().x(),
+            pk.inner().y(),
+            auth_resource_logic_vk.clone(),
+            receiver_resource_logic_vk.clone(),
+            rcv_pk.inner().x(),
+            rcv_pk.inner().y(),
+        ],
+    )?;
+    let value_with_data = poseidon_hash_gadget(
+        config.poseidon_config.clone(),
+        layouter.namespace(|| "value encoding with data"),
+        [auth_value.clone(), data.clone()],
+    )?;
+    let encoded_value = layouter.assign_region(
+        || "conditional select: value encoding",
+        |mut region| {
+            config.conditional_select_config.assign_region(
+                has_data,
+                &value_with_data,
+                &auth_value,
+                0,
+                &mut region,
+            )
+        },
+    )?;
+
+    layouter.assign_region(
+        || "check value encoding",
+        |mut region| region.constrain_equal(encoded_value.cell(), value.cell()),
+    )
 }
 
 #[test]
@@ -585,6 +1098,7 @@ fn test_halo2_token_resource_logic_circuit() {
             token_name,
             auth,
             receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            allowlist_root: None,
             rseed: RandomSeed::random(&mut rng),
         }
     };
@@ -605,3 +1119,126 @@ fn test_halo2_token_resource_logic_circuit() {
     .unwrap();
     assert_eq!(prover.verify(), Ok(()));
 }
+
+#[test]
+fn test_halo2_regulated_token_resource_logic_circuit() {
+    use crate::circuit::resource_logic_examples::allowlist::ALLOWLIST_TREE_DEPTH;
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use crate::merkle_tree::Node;
+    use halo2_proofs::dev::MockProver;
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    let token = Token::new("regulated_token".to_string(), 1u64);
+    let auth = TokenAuthorization::random(&mut rng);
+    let npk = pallas::Base::random(&mut rng);
+    let allowlist_path = MerklePath::random(&mut rng, ALLOWLIST_TREE_DEPTH);
+    let allowlist_root = allowlist_path.root(Node::from(npk)).inner();
+    let merkle_path = [(pallas::Base::zero(), LR::L); TAIGA_RESOURCE_TREE_DEPTH];
+
+    // Create an output resource to an allowlisted recipient
+    let token_resource =
+        token.create_random_output_regulated_token_resource(&mut rng, npk, &auth, allowlist_root);
+    let (token_resource_logic, receiver_resource_logic) =
+        token_resource.create_output_resource_logic_circuits(&mut rng, auth, merkle_path);
+
+    // Test serialization
+    let token_resource_logic = {
+        let circuit_bytes = token_resource_logic.to_bytes();
+        TokenResourceLogicCircuit::from_bytes(&circuit_bytes)
+    };
+
+    // The token logic commits to the allowlist logic as the second dynamic resource logic
+    let public_inputs = token_resource_logic.get_public_inputs(&mut rng);
+    let allowlist_resource_logic_cm: [pallas::Base; 2] = ResourceLogicCommitment::commit(
+        &*COMPRESSED_ALLOWLIST_VK,
+        &token_resource_logic
+            .rseed
+            .get_resource_logic_cm_r(PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_2_CM_R),
+    )
+    .to_public_inputs();
+    assert_eq!(
+        public_inputs.get_from_index(RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1),
+        allowlist_resource_logic_cm[0]
+    );
+    assert_eq!(
+        public_inputs.get_from_index(RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2),
+        allowlist_resource_logic_cm[1]
+    );
+    let prover = MockProver::<pallas::Base>::run(
+        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        &token_resource_logic,
+        vec![public_inputs.to_vec()],
+    )
+    .unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // The allowlist logic can't be skipped by publishing the default commitment
+    let mut skipped_public_inputs = public_inputs.to_vec();
+    let default_resource_logic_cm: [pallas::Base; 2] =
+        ResourceLogicCommitment::default().to_public_inputs();
+    skipped_public_inputs[RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1] =
+        default_resource_logic_cm[0];
+    skipped_public_inputs[RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2] =
+        default_resource_logic_cm[1];
+    let prover = MockProver::<pallas::Base>::run(
+        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        &token_resource_logic,
+        vec![skipped_public_inputs],
+    )
+    .unwrap();
+    assert!(prover.verify().is_err());
+
+    // The regulated resource can't be created as a plain token
+    let mut plain_token_resource_logic = token_resource_logic.clone();
+    plain_token_resource_logic.allowlist_root = None;
+    let public_inputs = plain_token_resource_logic.get_public_inputs(&mut rng);
+    let prover = MockProver::<pallas::Base>::run(
+        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        &plain_token_resource_logic,
+        vec![public_inputs.to_vec()],
+    )
+    .unwrap();
+    assert!(prover.verify().is_err());
+
+    // The allowlist logic accepts the allowlisted recipient
+    let allowlist_resource_logic = AllowlistResourceLogicCircuit::new(
+        receiver_resource_logic.self_resource,
+        token.name().clone(),
+        allowlist_root,
+        allowlist_path.clone(),
+    );
+    let public_inputs = allowlist_resource_logic.get_public_inputs(&mut rng);
+    let prover = MockProver::<pallas::Base>::run(
+        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        &allowlist_resource_logic,
+        vec![public_inputs.to_vec()],
+    )
+    .unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // The allowlist logic rejects a recipient not in the allowlist
+    let other_npk = pallas::Base::random(&mut rng);
+    let other_token_resource = token.create_random_output_regulated_token_resource(
+        &mut rng,
+        other_npk,
+        &auth,
+        allowlist_root,
+    );
+    let (_, other_receiver_resource_logic) =
+        other_token_resource.create_output_resource_logic_circuits(&mut rng, auth, merkle_path);
+    let allowlist_resource_logic = AllowlistResourceLogicCircuit::new(
+        other_receiver_resource_logic.self_resource,
+        token.name().clone(),
+        allowlist_root,
+        allowlist_path,
+    );
+    let public_inputs = allowlist_resource_logic.get_public_inputs(&mut rng);
+    let prover = MockProver::<pallas::Base>::run(
+        RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+        &allowlist_resource_logic,
+        vec![public_inputs.to_vec()],
+    )
+    .unwrap();
+    assert!(prover.verify().is_err());
+}
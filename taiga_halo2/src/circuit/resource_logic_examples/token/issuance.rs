@@ -0,0 +1,748 @@
+/// The issued token is a token variant with an issuer and a supply cap encoded in the label.
+/// The supply is created by consuming the issuance resource, an ephemeral input resource
+/// authorized by the issuer key. The issuance resource is determined by the label, so its
+/// nullifier can only be revealed once and the total supply never exceeds the cap.
+/// Burning is explicit: the tokens are burned by creating an ephemeral output resource.
+use crate::{
+    circuit::{
+        blake2s::{resource_logic_commitment_gadget, Blake2sChip},
+        gadgets::{
+            assign_free_advice, assign_free_constant,
+            conditional_equal::conditional_equal,
+            issuance::{constrain_issuer_authorization, constrain_label_determined_resource},
+            mul::{MulChip, MulInstructions},
+            poseidon_hash::poseidon_hash_gadget,
+        },
+        resource_logic_bytecode::{ResourceLogicByteCode, ResourceLogicRepresentation},
+        resource_logic_circuit::{
+            ResourceLogicCircuit, ResourceLogicConfig, ResourceLogicPublicInputs,
+            ResourceLogicVerifyingInfo, ResourceLogicVerifyingInfoTrait, ResourceStatus,
+        },
+        resource_logic_examples::{
+            receiver_resource_logic::{ReceiverResourceLogicCircuit, COMPRESSED_RECEIVER_VK},
+            signature_verification::{
+                SignatureVerificationResourceLogicCircuit, COMPRESSED_TOKEN_AUTH_VK,
+            },
+            token::{check_value_encoding, TokenAuthorization, TokenName},
+        },
+    },
+    constant::{
+        TaigaFixedBases, PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_1_CM_R,
+        RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_1,
+        RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_2,
+        RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1,
+        RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2, SETUP_PARAMS_MAP,
+        TAIGA_RESOURCE_TREE_DEPTH,
+    },
+    error::TransactionError,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::LR,
+    nullifier::Nullifier,
+    proof::Proof,
+    resource::{RandomSeed, Resource, ResourceLogics},
+    resource_logic_commitment::ResourceLogicCommitment,
+    resource_logic_vk::ResourceLogicVerifyingKey,
+    resource_tree::ResourceExistenceWitness,
+    utils::{poseidon_hash_n, read_base_field, read_point},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use ff::Field;
+use group::{Curve, Group, GroupEncoding};
+use halo2_gadgets::ecc::{chip::EccChip, NonIdentityPoint};
+use halo2_proofs::{
+    circuit::{floor_planner, AssignedCell, Layouter, Value},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+use lazy_static::lazy_static;
+use pasta_curves::arithmetic::CurveAffine;
+use pasta_curves::{group::ff::PrimeField, pallas};
+use rand::{rngs::OsRng, Rng, RngCore};
+
+lazy_static! {
+    pub static ref ISSUED_TOKEN_VK: ResourceLogicVerifyingKey =
+        IssuedTokenResourceLogicCircuit::default().get_resource_logic_vk();
+    pub static ref COMPRESSED_ISSUED_TOKEN_VK: pallas::Base = ISSUED_TOKEN_VK.get_compressed();
+}
+
+#[derive(Clone, Debug)]
+pub struct IssuedToken {
+    name: TokenName,
+    issuer_pk: pallas::Point,
+    // The cap of the total supply
+    cap: u64,
+}
+
+impl IssuedToken {
+    pub fn new(name: String, issuer_pk: pallas::Point, cap: u64) -> Self {
+        Self {
+            name: TokenName(name),
+            issuer_pk,
+            cap,
+        }
+    }
+
+    /// The issuer authorizes the issuance with the authorization key of the spending key.
+    pub fn from_issuer_key(name: String, issuer_sk: &SpendingKey, cap: u64) -> Self {
+        Self::new(name, issuer_sk.get_auth_pk(), cap)
+    }
+
+    pub fn name(&self) -> &TokenName {
+        &self.name
+    }
+
+    pub fn issuer_pk(&self) -> pallas::Point {
+        self.issuer_pk
+    }
+
+    pub fn cap(&self) -> u64 {
+        self.cap
+    }
+
+    // label = poseidon_hash(name || issuer_pk || cap)
+    pub fn encode_label(&self) -> pallas::Base {
+        let issuer_pk_coord = self.issuer_pk.to_affine().coordinates().unwrap();
+        poseidon_hash_n([
+            self.name.encode(),
+            *issuer_pk_coord.x(),
+            *issuer_pk_coord.y(),
+            pallas::Base::from(self.cap),
+        ])
+    }
+
+    /// The issuance resource is consumed by the issuer's signature, and the outputs
+    /// encrypted to the issuer auth pk.
+    pub fn issuer_auth(&self) -> TokenAuthorization {
+        TokenAuthorization::new(self.issuer_pk, *COMPRESSED_TOKEN_AUTH_VK)
+    }
+
+    /// Create the issuance resource. All fields are determined by the label.
+    pub fn create_issuance_resource(&self) -> IssuedTokenResource {
+        let label = self.encode_label();
+        let resource = Resource::new_input_resource(
+            *COMPRESSED_ISSUED_TOKEN_VK,
+            label,
+            self.issuer_auth().to_value(),
+            self.cap,
+            pallas::Base::zero(),
+            Nullifier::from(label),
+            true,
+            pallas::Base::zero(),
+        );
+
+        IssuedTokenResource {
+            token: self.clone(),
+            resource,
+        }
+    }
+
+    pub fn create_random_input_resource_from_key<R: RngCore>(
+        &self,
+        mut rng: R,
+        quantity: u64,
+        sk: &SpendingKey,
+    ) -> IssuedTokenResource {
+        let auth = TokenAuthorization::from_address(&sk.get_address(*COMPRESSED_TOKEN_AUTH_VK));
+        let resource = Resource::new_input_resource(
+            *COMPRESSED_ISSUED_TOKEN_VK,
+            self.encode_label(),
+            auth.to_value(),
+            quantity,
+            sk.get_nk(),
+            Nullifier::random(&mut rng),
+            false,
+            pallas::Base::random(&mut rng),
+        );
+
+        IssuedTokenResource {
+            token: self.clone(),
+            resource,
+        }
+    }
+
+    pub fn create_random_output_resource_to_address<R: RngCore>(
+        &self,
+        rng: R,
+        quantity: u64,
+        address: &PaymentAddress,
+    ) -> IssuedTokenResource {
+        self.create_random_output_resource(rng, quantity, address, false)
+    }
+
+    /// Create an ephemeral output resource to burn the tokens. The resource is still
+    /// encrypted to the address so that the burner can track it.
+    pub fn create_random_burn_resource<R: RngCore>(
+        &self,
+        rng: R,
+        quantity: u64,
+        address: &PaymentAddress,
+    ) -> IssuedTokenResource {
+        self.create_random_output_resource(rng, quantity, address, true)
+    }
+
+    fn create_random_output_resource<R: RngCore>(
+        &self,
+        mut rng: R,
+        quantity: u64,
+        address: &PaymentAddress,
+        is_ephemeral: bool,
+    ) -> IssuedTokenResource {
+        let auth = TokenAuthorization::from_address(address);
+        let resource = Resource::new_output_resource(
+            *COMPRESSED_ISSUED_TOKEN_VK,
+            self.encode_label(),
+            auth.to_value(),
+            quantity,
+            address.get_npk(),
+            is_ephemeral,
+            pallas::Base::random(&mut rng),
+        );
+
+        IssuedTokenResource {
+            token: self.clone(),
+            resource,
+        }
+    }
+}
+
+impl Default for IssuedToken {
+    fn default() -> Self {
+        Self {
+            name: TokenName("Token_name".to_string()),
+            issuer_pk: pallas::Point::generator(),
+            cap: 0,
+        }
+    }
+}
+
+impl BorshSerialize for IssuedToken {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.name.serialize(writer)?;
+        writer.write_all(&self.issuer_pk.to_bytes())?;
+        self.cap.serialize(writer)?;
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for IssuedToken {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let name = TokenName::deserialize_reader(reader)?;
+        let issuer_pk = read_point(reader)?;
+        let cap = u64::deserialize_reader(reader)?;
+        Ok(Self {
+            name,
+            issuer_pk,
+            cap,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IssuedTokenResource {
+    pub token: IssuedToken,
+    pub resource: Resource,
+}
+
+impl std::ops::Deref for IssuedTokenResource {
+    type Target = Resource;
+
+    fn deref(&self) -> &Self::Target {
+        &self.resource
+    }
+}
+
+impl IssuedTokenResource {
+    pub fn token(&self) -> &IssuedToken {
+        &self.token
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn generate_input_resource_logics<R: RngCore>(
+        &self,
+        mut rng: R,
+        auth: TokenAuthorization,
+        auth_sk: pallas::Scalar,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> ResourceLogics {
+        let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
+        let token_resource_logic = IssuedTokenResourceLogicCircuit {
+            self_resource,
+            token: self.token.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            rseed: RandomSeed::random(&mut rng),
+        };
+
+        let token_auth_resource_logic =
+            create_issued_resource_auth_logic(&mut rng, self_resource, auth, auth_sk, None);
+
+        ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![Box::new(token_auth_resource_logic)],
+        )
+    }
+
+    pub fn generate_output_resource_logics<R: RngCore>(
+        &self,
+        mut rng: R,
+        auth: TokenAuthorization,
+        merkle_path: [(pallas::Base, LR); TAIGA_RESOURCE_TREE_DEPTH],
+    ) -> ResourceLogics {
+        let self_resource = ResourceExistenceWitness::new(self.resource, merkle_path);
+        let token_resource_logic = IssuedTokenResourceLogicCircuit {
+            self_resource,
+            token: self.token.clone(),
+            auth,
+            receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+            rseed: RandomSeed::random(&mut rng),
+        };
+
+        let receiver_resource_logic =
+            create_issued_resource_receiver_logic(&mut rng, self_resource, auth, None);
+
+        ResourceLogics::new(
+            Box::new(token_resource_logic),
+            vec![Box::new(receiver_resource_logic)],
+        )
+    }
+}
+
+// IssuedTokenResourceLogicCircuit
+#[derive(Clone, Debug, Default)]
+pub struct IssuedTokenResourceLogicCircuit {
+    self_resource: ResourceExistenceWitness,
+    // The token goes to label.
+    pub token: IssuedToken,
+    // The auth goes to value and defines how to consume and create the resource.
+    pub auth: TokenAuthorization,
+    pub receiver_resource_logic_vk: pallas::Base,
+    // rseed is to generate the randomness for resource_logic commitment
+    pub rseed: RandomSeed,
+}
+
+impl IssuedTokenResourceLogicCircuit {
+    pub fn to_bytecode(&self) -> ResourceLogicByteCode {
+        ResourceLogicByteCode::new(ResourceLogicRepresentation::IssuedToken, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(&self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &Vec<u8>) -> Self {
+        BorshDeserialize::deserialize(&mut bytes.as_ref()).unwrap()
+    }
+}
+
+/// The constraints shared by the resources issued by a key, e.g. the issued tokens and the NFTs.
+/// The value is the token authorization. The ephemeral input resource is the issuance resource:
+/// it's authorized by the issuer and determined by the label. An ephemeral output resource burns
+/// the tokens and needs no more constraints. Returns the flag of the issuance resource.
+pub(crate) fn constrain_issued_resource(
+    layouter: &mut impl Layouter<pallas::Base>,
+    config: &ResourceLogicConfig,
+    self_resource: &ResourceStatus,
+    issuer_pk: &NonIdentityPoint<pallas::Affine, EccChip<TaigaFixedBases>>,
+    auth: &TokenAuthorization,
+    receiver_resource_logic_vk: pallas::Base,
+    value_data: Option<&AssignedCell<pallas::Base, pallas::Base>>,
+    rseed: &RandomSeed,
+) -> Result<AssignedCell<pallas::Base, pallas::Base>, Error> {
+    // Construct an ECC chip
+    let ecc_chip = EccChip::construct(config.ecc_config.clone());
+
+    let pk = NonIdentityPoint::new(
+        ecc_chip.clone(),
+        layouter.namespace(|| "witness pk"),
+        Value::known(auth.pk.to_affine()),
+    )?;
+
+    let auth_resource_logic_vk = assign_free_advice(
+        layouter.namespace(|| "witness auth resource_logic vk"),
+        config.advices[0],
+        Value::known(auth.vk),
+    )?;
+
+    let receiver_resource_logic_vk = assign_free_advice(
+        layouter.namespace(|| "witness receiver resource_logic vk"),
+        config.advices[0],
+        Value::known(receiver_resource_logic_vk),
+    )?;
+
+    let rcv_pk = NonIdentityPoint::new(
+        ecc_chip,
+        layouter.namespace(|| "witness rcv_pk"),
+        Value::known(auth.rcv_pk.to_affine()),
+    )?;
+
+    // Decode the value, and check the value encoding. The value data, if any, is always
+    // committed in the value.
+    let has_data = assign_free_constant(
+        layouter.namespace(|| "constant has_data"),
+        config.advices[0],
+        pallas::Base::from(value_data.is_some()),
+    )?;
+    let data = match value_data {
+        Some(data) => data.clone(),
+        None => assign_free_constant(
+            layouter.namespace(|| "zero"),
+            config.advices[0],
+            pallas::Base::zero(),
+        )?,
+    };
+    check_value_encoding(
+        layouter,
+        config,
+        &self_resource.resource.value,
+        &pk,
+        &auth_resource_logic_vk,
+        &receiver_resource_logic_vk,
+        &rcv_pk,
+        &has_data,
+        &data,
+    )?;
+
+    // The ephemeral input resource is the issuance resource
+    let mul_chip = MulChip::construct(config.mul_config.clone());
+    let is_issuance = MulInstructions::mul(
+        &mul_chip,
+        layouter.namespace(|| "is_input * is_ephemeral"),
+        &self_resource.is_input,
+        &self_resource.resource.is_ephemeral,
+    )?;
+
+    // The issuance resource is authorized by the issuer
+    constrain_issuer_authorization(
+        layouter,
+        config,
+        &is_issuance,
+        issuer_pk,
+        &pk,
+        &rcv_pk,
+        &auth_resource_logic_vk,
+        &receiver_resource_logic_vk,
+        *COMPRESSED_TOKEN_AUTH_VK,
+        *COMPRESSED_RECEIVER_VK,
+    )?;
+
+    // The other fields of the issuance resource are determined by the label, so that
+    // the issuance resource and its nullifier are unique.
+    constrain_label_determined_resource(layouter, config, &is_issuance, self_resource)?;
+
+    // Resource Logic Commitment
+    // Commt the sender(authorization method included) resource_logic if it's an input resource;
+    // Commit the receiver(resource encryption constraints included) resource_logic if it's an output resource.
+    let first_dynamic_resource_logic = {
+        layouter.assign_region(
+            || "conditional select: ",
+            |mut region| {
+                config.conditional_select_config.assign_region(
+                    &self_resource.is_input,
+                    &auth_resource_logic_vk,
+                    &receiver_resource_logic_vk,
+                    0,
+                    &mut region,
+                )
+            },
+        )?
+    };
+
+    // Construct a blake2s chip
+    let blake2s_chip = Blake2sChip::construct(config.blake2s_config.clone());
+    let resource_logic_cm_r = assign_free_advice(
+        layouter.namespace(|| "resource_logic_cm_r"),
+        config.advices[0],
+        Value::known(rseed.get_resource_logic_cm_r(PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_1_CM_R)),
+    )?;
+    let first_dynamic_resource_logic_cm = resource_logic_commitment_gadget(
+        layouter,
+        &blake2s_chip,
+        first_dynamic_resource_logic,
+        resource_logic_cm_r,
+    )?;
+
+    layouter.constrain_instance(
+        first_dynamic_resource_logic_cm[0].cell(),
+        config.instances,
+        RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_1,
+    )?;
+    layouter.constrain_instance(
+        first_dynamic_resource_logic_cm[1].cell(),
+        config.instances,
+        RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_2,
+    )?;
+
+    // Publicize the second dynamic resource_logic commitment with default value
+    let resource_logic_cm_fields: [pallas::Base; 2] =
+        ResourceLogicCommitment::default().to_public_inputs();
+    let resource_logic_cm_1 = assign_free_advice(
+        layouter.namespace(|| "resource_logic_cm 1"),
+        config.advices[0],
+        Value::known(resource_logic_cm_fields[0]),
+    )?;
+    let resource_logic_cm_2 = assign_free_advice(
+        layouter.namespace(|| "resource_logic_cm 2"),
+        config.advices[0],
+        Value::known(resource_logic_cm_fields[1]),
+    )?;
+
+    layouter.constrain_instance(
+        resource_logic_cm_1.cell(),
+        config.instances,
+        RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1,
+    )?;
+    layouter.constrain_instance(
+        resource_logic_cm_2.cell(),
+        config.instances,
+        RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2,
+    )?;
+
+    Ok(is_issuance)
+}
+
+// The public inputs of the issued resource logics, see `constrain_issued_resource`
+pub(crate) fn get_issued_resource_public_inputs(
+    circuit: &impl ResourceLogicCircuit,
+    auth: &TokenAuthorization,
+    receiver_resource_logic_vk: pallas::Base,
+    rseed: &RandomSeed,
+    mut rng: impl RngCore,
+) -> ResourceLogicPublicInputs {
+    let mut public_inputs = circuit.get_mandatory_public_inputs();
+    let dynamic_resource_logic = if circuit.get_self_resource().is_input() {
+        auth.vk
+    } else {
+        receiver_resource_logic_vk
+    };
+
+    let resource_logic_com_r =
+        rseed.get_resource_logic_cm_r(PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_1_CM_R);
+    let resource_logic_com: [pallas::Base; 2] =
+        ResourceLogicCommitment::commit(&dynamic_resource_logic, &resource_logic_com_r)
+            .to_public_inputs();
+
+    public_inputs.extend(resource_logic_com);
+    let default_resource_logic_cm: [pallas::Base; 2] =
+        ResourceLogicCommitment::default().to_public_inputs();
+    public_inputs.extend(default_resource_logic_cm);
+    public_inputs.extend(circuit.get_validity_window_public_inputs());
+    let padding = ResourceLogicPublicInputs::get_public_input_padding(
+        public_inputs.len(),
+        &RandomSeed::random(&mut rng),
+    );
+    public_inputs.extend(padding);
+    public_inputs.into()
+}
+
+// The input issued resources are authorized by the signature
+pub(crate) fn create_issued_resource_auth_logic<R: RngCore>(
+    rng: R,
+    self_resource: ResourceExistenceWitness,
+    auth: TokenAuthorization,
+    auth_sk: pallas::Scalar,
+    value_data: Option<pallas::Base>,
+) -> SignatureVerificationResourceLogicCircuit {
+    let mut circuit = SignatureVerificationResourceLogicCircuit::from_sk_and_sign(
+        rng,
+        self_resource,
+        auth.vk,
+        auth_sk,
+        *COMPRESSED_RECEIVER_VK,
+        auth.rcv_pk,
+    );
+    circuit.value_data = value_data;
+    circuit
+}
+
+// The output issued resources are encrypted to the receiver
+pub(crate) fn create_issued_resource_receiver_logic<R: RngCore>(
+    mut rng: R,
+    self_resource: ResourceExistenceWitness,
+    auth: TokenAuthorization,
+    value_data: Option<pallas::Base>,
+) -> ReceiverResourceLogicCircuit {
+    ReceiverResourceLogicCircuit {
+        self_resource,
+        resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+        encrypt_nonce: pallas::Base::from_u128(rng.gen()),
+        sk: pallas::Base::random(&mut rng),
+        rcv_pk: auth.rcv_pk,
+        auth_pk: auth.pk,
+        auth_resource_logic_vk: auth.vk,
+        value_data,
+    }
+}
+
+impl ResourceLogicCircuit for IssuedTokenResourceLogicCircuit {
+    // Add custom constraints
+    fn custom_constraints(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<pallas::Base>,
+        self_resource: ResourceStatus,
+    ) -> Result<(), Error> {
+        // Construct an ECC chip
+        let ecc_chip = EccChip::construct(config.ecc_config.clone());
+
+        // Decode the label, and check the label encoding
+        let token_name = assign_free_advice(
+            layouter.namespace(|| "witness token name"),
+            config.advices[0],
+            Value::known(self.token.name.encode()),
+        )?;
+        let issuer_pk = NonIdentityPoint::new(
+            ecc_chip,
+            layouter.namespace(|| "witness issuer pk"),
+            Value::known(self.token.issuer_pk.to_affine()),
+        )?;
+        let cap = assign_free_advice(
+            layouter.namespace(|| "witness cap"),
+            config.advices[0],
+            Value::known(pallas::Base::from(self.token.cap)),
+        )?;
+        let encoded_label = poseidon_hash_gadget(
+            config.poseidon_config.clone(),
+            layouter.namespace(|| "label encoding"),
+            [
+                token_name,
+                issuer_pk.inner().x(),
+                issuer_pk.inner().y(),
+                cap.clone(),
+            ],
+        )?;
+        layouter.assign_region(
+            || "check label",
+            |mut region| {
+                region.constrain_equal(encoded_label.cell(), self_resource.resource.label.cell())
+            },
+        )?;
+
+        let is_issuance = constrain_issued_resource(
+            &mut layouter,
+            &config,
+            &self_resource,
+            &issuer_pk,
+            &self.auth,
+            self.receiver_resource_logic_vk,
+            None,
+            &self.rseed,
+        )?;
+
+        // The issuance resource mints the cap
+        conditional_equal(
+            &mut layouter,
+            &config.conditional_equal_config,
+            "issuance quantity",
+            &is_issuance,
+            &self_resource.resource.quantity,
+            &cap,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_public_inputs(&self, rng: impl RngCore) -> ResourceLogicPublicInputs {
+        get_issued_resource_public_inputs(
+            self,
+            &self.auth,
+            self.receiver_resource_logic_vk,
+            &self.rseed,
+            rng,
+        )
+    }
+
+    fn get_self_resource(&self) -> ResourceExistenceWitness {
+        self.self_resource
+    }
+}
+
+resource_logic_circuit_impl!(IssuedTokenResourceLogicCircuit);
+resource_logic_verifying_info_impl!(IssuedTokenResourceLogicCircuit);
+
+impl BorshSerialize for IssuedTokenResourceLogicCircuit {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.self_resource.serialize(writer)?;
+        self.token.serialize(writer)?;
+        self.auth.serialize(writer)?;
+        writer.write_all(&self.receiver_resource_logic_vk.to_repr())?;
+        self.rseed.serialize(writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for IssuedTokenResourceLogicCircuit {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let self_resource = ResourceExistenceWitness::deserialize_reader(reader)?;
+        let token = IssuedToken::deserialize_reader(reader)?;
+        let auth = TokenAuthorization::deserialize_reader(reader)?;
+        let receiver_resource_logic_vk = read_base_field(reader)?;
+        let rseed = RandomSeed::deserialize_reader(reader)?;
+        Ok(Self {
+            self_resource,
+            token,
+            auth,
+            receiver_resource_logic_vk,
+            rseed,
+        })
+    }
+}
+
+#[test]
+fn test_halo2_issued_token_resource_logic_circuit() {
+    use crate::constant::RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE;
+    use halo2_proofs::dev::MockProver;
+    use rand::rngs::OsRng;
+
+    let mut rng = OsRng;
+    let issuer_sk = SpendingKey::random(&mut rng);
+    let token = IssuedToken::from_issuer_key("btc".to_string(), &issuer_sk, 21);
+    let merkle_path = [(pallas::Base::zero(), LR::R); TAIGA_RESOURCE_TREE_DEPTH];
+    let create_circuit = |resource: Resource| IssuedTokenResourceLogicCircuit {
+        self_resource: ResourceExistenceWitness::new(resource, merkle_path),
+        token: token.clone(),
+        auth: token.issuer_auth(),
+        receiver_resource_logic_vk: *COMPRESSED_RECEIVER_VK,
+        rseed: RandomSeed::random(&mut OsRng),
+    };
+    let verify = |circuit: &IssuedTokenResourceLogicCircuit| {
+        let public_inputs = circuit.get_public_inputs(OsRng);
+        MockProver::<pallas::Base>::run(
+            RESOURCE_LOGIC_CIRCUIT_PARAMS_SIZE,
+            circuit,
+            vec![public_inputs.to_vec()],
+        )
+        .unwrap()
+        .verify()
+    };
+
+    // The issuance resource
+    let issuance_resource = *token.create_issuance_resource().resource();
+    let circuit = {
+        let circuit_bytes = create_circuit(issuance_resource).to_bytes();
+        IssuedTokenResourceLogicCircuit::from_bytes(&circuit_bytes)
+    };
+    assert_eq!(verify(&circuit), Ok(()));
+
+    // Minting more than the cap fails
+    let mut invalid_resource = issuance_resource;
+    invalid_resource.quantity = 22;
+    assert!(verify(&create_circuit(invalid_resource)).is_err());
+
+    // A different issuance resource fails
+    let mut invalid_resource = issuance_resource;
+    invalid_resource.rseed = pallas::Base::random(&mut rng);
+    assert!(verify(&create_circuit(invalid_resource)).is_err());
+
+    // The burn resource
+    let burn_resource = *token
+        .create_random_burn_resource(
+            &mut rng,
+            5,
+            &issuer_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK),
+        )
+        .resource();
+    assert_eq!(verify(&create_circuit(burn_resource)), Ok(()));
+}
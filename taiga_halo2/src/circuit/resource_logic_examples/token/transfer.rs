@@ -0,0 +1,398 @@
+/// TransferBuilder creates a balanced token transfer partial transaction. It selects
+/// the input resources for each token, returns the change to the sender, and pads
+/// the inputs and outputs to the same number of compliance pairs.
+use crate::{
+    circuit::resource_logic_examples::{
+        signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+        token::{Token, TokenAuthorization, TokenName, TokenResource},
+    },
+    compliance::ComplianceInfo,
+    constant::{TAIGA_COMMITMENT_TREE_DEPTH, TAIGA_RESOURCE_TREE_LEAVES_NUM},
+    error::TransactionError,
+    keys::{PaymentAddress, SpendingKey},
+    merkle_tree::{Anchor, MerklePath},
+    resource::{Resource, ResourceLogics},
+    resource_tree::ResourceMerkleTreeLeaves,
+    shielded_ptx::ShieldedPartialTransaction,
+};
+use ff::Field;
+use pasta_curves::pallas;
+use rand::RngCore;
+
+#[derive(Clone, Debug)]
+pub struct TransferBuilder {
+    sender: SpendingKey,
+    // The owned resources and their merkle paths in the commitment tree
+    spendable_resources: Vec<(TokenResource, MerklePath)>,
+    outputs: Vec<(PaymentAddress, Token)>,
+}
+
+impl TransferBuilder {
+    pub fn new(sender: SpendingKey) -> Self {
+        Self {
+            sender,
+            spendable_resources: vec![],
+            outputs: vec![],
+        }
+    }
+
+    /// Add a resource owned by the sender that can be selected as input.
+    pub fn add_spendable_resource(
+        &mut self,
+        resource: TokenResource,
+        merkle_path: MerklePath,
+    ) -> &mut Self {
+        self.spendable_resources.push((resource, merkle_path));
+        self
+    }
+
+    /// Send the token to the recipient address.
+    pub fn add_output(&mut self, recipient: PaymentAddress, token: Token) -> &mut Self {
+        self.outputs.push((recipient, token));
+        self
+    }
+
+    pub fn build<R: RngCore>(
+        &self,
+        mut rng: R,
+    ) -> Result<ShieldedPartialTransaction, TransactionError> {
+        // Reject the resources the sender can't nullify before selecting any of them
+        let sender_npk = self.sender.get_npk();
+        if self
+            .spendable_resources
+            .iter()
+            .any(|(resource, _)| resource.resource().get_npk() != sender_npk)
+        {
+            return Err(TransactionError::InvalidResourceOwner);
+        }
+
+        let sender_address = self.sender.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+        let sender_auth = TokenAuthorization::from_address(&sender_address);
+
+        // Select the input resources and create the output resources for each token
+        let mut inputs: Vec<(TokenResource, MerklePath)> = vec![];
+        let mut outputs: Vec<(TokenResource, TokenAuthorization)> = vec![];
+        for (token_name, quantity) in self.get_transfer_quantities()? {
+            let (selected, selected_quantity) = self.select_resources(&token_name, quantity)?;
+            inputs.extend(selected);
+
+            self.outputs
+                .iter()
+                .filter(|(_, token)| *token.name() == token_name)
+                .for_each(|(recipient, token)| {
+                    let resource =
+                        token.create_random_output_token_resource_to_address(&mut rng, recipient);
+                    outputs.push((resource, TokenAuthorization::from_address(recipient)));
+                });
+
+            // Return the change to the sender
+            if selected_quantity > quantity {
+                let change = Token::new(token_name.inner(), selected_quantity - quantity);
+                let resource = change
+                    .create_random_output_token_resource_to_address(&mut rng, &sender_address);
+                outputs.push((resource, sender_auth));
+            }
+        }
+
+        let pair_num = std::cmp::max(inputs.len(), outputs.len());
+        if pair_num * 2 > TAIGA_RESOURCE_TREE_LEAVES_NUM {
+            return Err(TransactionError::TooManyResources);
+        }
+
+        // Pad the inputs and outputs with padding resources and create the compliance pairs.
+        // The padding input resources are ephemeral, so the compliance circuit doesn't check
+        // their merkle paths against the anchor.
+        let anchor = Anchor::from(pallas::Base::random(&mut rng));
+        let mut input_resources = vec![];
+        let mut output_resources = vec![];
+        let mut compliances = vec![];
+        for i in 0..pair_num {
+            let (input_resource, merkle_path, custom_anchor) = match inputs.get(i) {
+                Some((resource, merkle_path)) => (*resource.resource(), merkle_path.clone(), None),
+                None => (
+                    Resource::random_padding_resource(&mut rng),
+                    MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH),
+                    Some(anchor),
+                ),
+            };
+            let mut output_resource = match outputs.get(i) {
+                Some((resource, _)) => *resource.resource(),
+                None => Resource::random_padding_resource(&mut rng),
+            };
+            compliances.push(ComplianceInfo::new(
+                input_resource,
+                merkle_path,
+                custom_anchor,
+                &mut output_resource,
+                &mut rng,
+            ));
+            input_resources.push(input_resource);
+            output_resources.push(output_resource);
+        }
+
+        // Collect resource merkle leaves
+        let mut leaves = vec![];
+        input_resources
+            .iter()
+            .zip(output_resources.iter())
+            .for_each(|(input, output)| {
+                leaves.push(input.get_nf().unwrap().inner());
+                leaves.push(output.commitment().inner());
+            });
+        let resource_merkle_tree = ResourceMerkleTreeLeaves::new(leaves);
+
+        // Create resource logics for the input resources
+        let input_resource_logics = input_resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| {
+                let merkle_path = resource_merkle_tree
+                    .generate_path(resource.get_nf().unwrap().inner())
+                    .unwrap();
+                match inputs.get(i) {
+                    Some((token_resource, _)) => token_resource
+                        .generate_input_token_resource_logics(
+                            &mut rng,
+                            sender_auth,
+                            self.sender.get_auth_sk(),
+                            merkle_path,
+                        ),
+                    None => ResourceLogics::create_padding_resource_resource_logics(
+                        *resource,
+                        merkle_path,
+                    ),
+                }
+            })
+            .collect();
+
+        // Create resource logics for the output resources, and the outgoing ciphertexts so
+        // that the sender can recover the sent resources with the ovk
+        let ovk = self.sender.get_ovk();
+        let mut out_ciphertexts = vec![];
+        let output_resource_logics = output_resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| {
+                let merkle_path = resource_merkle_tree
+                    .generate_path(resource.commitment().inner())
+                    .unwrap();
+                match outputs.get(i) {
+                    Some((token_resource, auth)) => {
+                        let (resource_logics, out_cipher) = TokenResource {
+                            token_name: token_resource.token_name().clone(),
+                            resource: *resource,
+                            allowlist_root: None,
+                        }
+                        .generate_output_token_resource_logics_with_ovk(
+                            &mut rng,
+                            *auth,
+                            &ovk,
+                            merkle_path,
+                        );
+                        out_ciphertexts.push(out_cipher);
+                        resource_logics
+                    }
+                    None => ResourceLogics::create_padding_resource_resource_logics(
+                        *resource,
+                        merkle_path,
+                    ),
+                }
+            })
+            .collect();
+
+        let mut ptx = ShieldedPartialTransaction::build(
+            compliances,
+            input_resource_logics,
+            output_resource_logics,
+            vec![],
+            &mut rng,
+        )?;
+        ptx.set_out_ciphertexts(out_ciphertexts);
+        Ok(ptx)
+    }
+
+    // Sum up the output quantities of each token
+    fn get_transfer_quantities(&self) -> Result<Vec<(TokenName, u64)>, TransactionError> {
+        let mut quantities: Vec<(TokenName, u64)> = vec![];
+        for (_, token) in self.outputs.iter() {
+            match quantities
+                .iter_mut()
+                .find(|(token_name, _)| token_name == token.name())
+            {
+                Some((_, quantity)) => {
+                    *quantity = quantity
+                        .checked_add(token.quantity())
+                        .ok_or(TransactionError::QuantityOverflow)?
+                }
+                None => quantities.push((token.name().clone(), token.quantity())),
+            }
+        }
+        Ok(quantities)
+    }
+
+    // Select the resources with the largest quantities first until the quantity is covered.
+    fn select_resources(
+        &self,
+        token_name: &TokenName,
+        quantity: u64,
+    ) -> Result<(Vec<(TokenResource, MerklePath)>, u64), TransactionError> {
+        let mut candidates: Vec<&(TokenResource, MerklePath)> = self
+            .spendable_resources
+            .iter()
+            .filter(|(resource, _)| resource.token_name() == token_name)
+            .collect();
+        candidates.sort_by(|a, b| b.0.quantity.cmp(&a.0.quantity));
+
+        let mut selected = vec![];
+        let mut selected_quantity = 0u64;
+        for candidate in candidates {
+            if selected_quantity >= quantity {
+                break;
+            }
+            selected_quantity = selected_quantity
+                .checked_add(candidate.0.quantity)
+                .ok_or(TransactionError::QuantityOverflow)?;
+            // The sender nullifies the selected resources with its nullifier key
+            let (mut resource, merkle_path) = candidate.clone();
+            resource.resource.nk_container = self.sender.get_nk_container();
+            selected.push((resource, merkle_path));
+        }
+
+        if selected_quantity < quantity {
+            return Err(TransactionError::InsufficientFunds);
+        }
+        Ok((selected, selected_quantity))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::TransferBuilder;
+    use crate::{
+        circuit::resource_logic_examples::{
+            signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+            token::{Token, TokenResource},
+        },
+        constant::{TAIGA_COMMITMENT_TREE_DEPTH, TAIGA_RESOURCE_TREE_LEAVES_NUM},
+        error::TransactionError,
+        keys::SpendingKey,
+        merkle_tree::MerklePath,
+        transaction::{ShieldedPartialTxBundle, Transaction, TransparentPartialTxBundle},
+        wallet::Wallet,
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_token_transfer_builder() {
+        let mut rng = OsRng;
+        let alice_sk = SpendingKey::random(&mut rng);
+        let bob_sk = SpendingKey::random(&mut rng);
+        let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+        // Alice owns 3 btc and 4 btc, and sends 5 btc to Bob
+        let mut builder = TransferBuilder::new(alice_sk);
+        for quantity in [3, 4] {
+            let resource = Token::new("btc".to_string(), quantity)
+                .create_random_input_token_resource_from_key(&mut rng, &alice_sk);
+            // Recover the token resource as it would be from the wallet
+            let resource = TokenResource::from_resource(*resource.resource()).unwrap();
+            builder.add_spendable_resource(
+                resource,
+                MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH),
+            );
+        }
+        builder.add_output(bob_address, Token::new("btc".to_string(), 5));
+        let ptx = builder.build(&mut rng).unwrap();
+
+        // Bob receives 5 btc and Alice receives the change
+        let mut bob_wallet = Wallet::new(bob_sk.get_full_viewing_key());
+        let bob_resources = bob_wallet.scan_shielded_ptx(&ptx);
+        assert_eq!(bob_resources.len(), 1);
+        assert_eq!(bob_resources[0].resource.quantity, 5);
+
+        let mut alice_wallet = Wallet::new(alice_sk.get_full_viewing_key());
+        let alice_resources = alice_wallet.scan_shielded_ptx(&ptx);
+        assert_eq!(alice_resources.len(), 1);
+        assert_eq!(alice_resources[0].resource.quantity, 2);
+
+        // Alice recovers the sent resources with the ovk
+        let sent_resources = alice_wallet.recover_sent_resources(&ptx);
+        assert_eq!(sent_resources.len(), 2);
+        assert_eq!(sent_resources[0].quantity, 5);
+        assert_eq!(sent_resources[0].get_npk(), bob_address.get_npk());
+        assert_eq!(sent_resources[1].quantity, 2);
+        assert!(bob_wallet.recover_sent_resources(&ptx).is_empty());
+
+        // The transfer is balanced
+        let tx = Transaction::build(
+            &mut rng,
+            ShieldedPartialTxBundle::new(vec![ptx]),
+            TransparentPartialTxBundle::default(),
+        )
+        .unwrap();
+        tx.execute().unwrap();
+
+        // Insufficient funds
+        builder.add_output(bob_address, Token::new("btc".to_string(), 3));
+        assert!(matches!(
+            builder.build(&mut rng),
+            Err(TransactionError::InsufficientFunds)
+        ));
+    }
+
+    #[test]
+    fn test_token_transfer_builder_errors() {
+        let mut rng = OsRng;
+        let alice_sk = SpendingKey::random(&mut rng);
+        let bob_address = SpendingKey::random(&mut rng).get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+        // The selected quantities overflow
+        let mut builder = TransferBuilder::new(alice_sk);
+        for quantity in [u64::MAX - 1, 2] {
+            let resource = Token::new("btc".to_string(), quantity)
+                .create_random_input_token_resource_from_key(&mut rng, &alice_sk);
+            builder.add_spendable_resource(
+                resource,
+                MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH),
+            );
+        }
+        builder.add_output(bob_address, Token::new("btc".to_string(), u64::MAX));
+        assert!(matches!(
+            builder.build(&mut rng),
+            Err(TransactionError::QuantityOverflow)
+        ));
+
+        // The output quantities overflow
+        builder.add_output(bob_address, Token::new("btc".to_string(), 1));
+        assert!(matches!(
+            builder.build(&mut rng),
+            Err(TransactionError::QuantityOverflow)
+        ));
+
+        // The resources owned by others are rejected even if they wouldn't be selected
+        let mut builder = TransferBuilder::new(alice_sk);
+        for sk in [alice_sk, SpendingKey::random(&mut rng)] {
+            let resource = Token::new("btc".to_string(), 1)
+                .create_random_input_token_resource_from_key(&mut rng, &sk);
+            builder.add_spendable_resource(
+                resource,
+                MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH),
+            );
+        }
+        builder.add_output(bob_address, Token::new("btc".to_string(), 1));
+        assert!(matches!(
+            builder.build(&mut rng),
+            Err(TransactionError::InvalidResourceOwner)
+        ));
+
+        // Too many outputs for the resource tree
+        let mut builder = TransferBuilder::new(alice_sk);
+        for _ in 0..=TAIGA_RESOURCE_TREE_LEAVES_NUM / 2 {
+            builder.add_output(bob_address, Token::new("btc".to_string(), 0));
+        }
+        assert!(matches!(
+            builder.build(&mut rng),
+            Err(TransactionError::TooManyResources)
+        ));
+    }
+}
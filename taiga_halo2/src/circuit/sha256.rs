@@ -0,0 +1,739 @@
+/// The SHA-256 chip checks the digest of a byte message, e.g. the preimage of a hash lock shared
+/// with an external chain. The message bytes are range checked in the word decomposition, and
+/// the padding is fixed by the message length. One block takes about 9k rows.
+use crate::circuit::gadgets::assign_free_constant;
+use ff::PrimeField;
+use halo2_gadgets::utilities::bool_check;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas;
+
+// Initial hash value
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// Round constants
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const BLOCK_BYTES: usize = 64;
+// The number of operands of the add gate
+const ADD_OPERANDS: usize = 7;
+
+type Cell = AssignedCell<pallas::Base, pallas::Base>;
+
+#[derive(Clone, Debug)]
+pub struct Sha256Chip {
+    config: Sha256Config,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sha256Config {
+    advices: [Column<Advice>; 10],
+    s_word: Selector,
+    s_xor3: Selector,
+    s_ch: Selector,
+    s_maj: Selector,
+    s_add: Selector,
+    s_encode: Selector,
+}
+
+// A word with the little-endian bytes and bits
+#[derive(Clone, Debug)]
+struct Sha256Word {
+    word: Cell,
+    bytes: Vec<Cell>,
+    bits: Vec<Cell>,
+}
+
+// The witnessed part of a word decomposition, the others are derived from it
+enum WordSource<'a> {
+    Word(&'a Cell),
+    Bytes(&'a [Cell]),
+    Bits(&'a [Cell]),
+}
+
+impl WordSource<'_> {
+    fn value(&self) -> Value<u32> {
+        let compose = |cells: &[Cell], shift: usize| {
+            cells
+                .iter()
+                .enumerate()
+                .fold(Value::known(0u32), |acc, (i, cell)| {
+                    acc.zip(cell.value())
+                        .map(|(acc, v)| acc | (field_to_u64(v) as u32) << (shift * i))
+                })
+        };
+        match self {
+            WordSource::Word(word) => word.value().map(|v| field_to_u64(v) as u32),
+            WordSource::Bytes(bytes) => compose(bytes, 8),
+            WordSource::Bits(bits) => compose(bits, 1),
+        }
+    }
+}
+
+impl Sha256Chip {
+    pub fn construct(config: Sha256Config) -> Self {
+        Self { config }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<pallas::Base>,
+        advices: [Column<Advice>; 10],
+    ) -> Sha256Config {
+        let s_word = meta.selector();
+        let s_xor3 = meta.selector();
+        let s_ch = meta.selector();
+        let s_maj = meta.selector();
+        let s_add = meta.selector();
+        let s_encode = meta.selector();
+
+        // The bits are in the first four rows, eight bits per row. The word and the bytes are in
+        // the fifth row.
+        meta.create_gate("sha256 word decompose", |meta| {
+            let s_word = meta.query_selector(s_word);
+            let bits: Vec<Vec<Expression<pallas::Base>>> = (0..4)
+                .map(|row| {
+                    (0..8)
+                        .map(|col| meta.query_advice(advices[col], Rotation(row)))
+                        .collect()
+                })
+                .collect();
+            let word = meta.query_advice(advices[0], Rotation(4));
+            let bytes: Vec<Expression<pallas::Base>> = (0..4)
+                .map(|i| meta.query_advice(advices[i + 1], Rotation(4)))
+                .collect();
+
+            let bool_checks = bits.iter().flatten().map(|bit| bool_check(bit.clone()));
+            let byte_checks = bits
+                .iter()
+                .zip(bytes.iter())
+                .map(|(bits, byte)| compose(bits, 2) - byte.clone());
+            let word_check = compose(&bytes, 1 << 8) - word;
+
+            Constraints::with_selector(
+                s_word,
+                bool_checks
+                    .chain(byte_checks)
+                    .chain(std::iter::once(word_check))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        let one = || Expression::Constant(pallas::Base::one());
+        let two = || Expression::Constant(pallas::Base::from(2));
+        configure_bitwise_gate(meta, advices, s_xor3, "sha256 xor3", |a, b, c| {
+            let a_xor_b = a.clone() + b.clone() - two() * a * b;
+            a_xor_b.clone() + c.clone() - two() * a_xor_b * c
+        });
+        configure_bitwise_gate(meta, advices, s_ch, "sha256 ch", |a, b, c| {
+            a.clone() * b + (one() - a) * c
+        });
+        configure_bitwise_gate(meta, advices, s_maj, "sha256 maj", |a, b, c| {
+            a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone()
+                - two() * a * b * c
+        });
+
+        // The operands are in the first row, and the sum and the carry bits are in the second row
+        meta.create_gate("sha256 add", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let sum = (0..ADD_OPERANDS)
+                .map(|i| meta.query_advice(advices[i], Rotation::cur()))
+                .fold(Expression::Constant(pallas::Base::zero()), |acc, x| acc + x);
+            let out = meta.query_advice(advices[0], Rotation::next());
+            let carry_bits: Vec<Expression<pallas::Base>> = (0..3)
+                .map(|i| meta.query_advice(advices[i + 1], Rotation::next()))
+                .collect();
+            let carry = compose(&carry_bits, 2);
+
+            Constraints::with_selector(
+                s_add,
+                carry_bits
+                    .iter()
+                    .map(|bit| bool_check(bit.clone()))
+                    .chain(std::iter::once(
+                        sum - out - carry * pallas::Base::from_u128(1 << 32),
+                    ))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        // Ten bytes are in the first row, and six bytes and the field element are in the second row
+        meta.create_gate("sha256 encode", |meta| {
+            let s_encode = meta.query_selector(s_encode);
+            let bytes: Vec<Expression<pallas::Base>> = (0..16)
+                .map(|i| meta.query_advice(advices[i % 10], Rotation((i / 10) as i32)))
+                .collect();
+            let field = meta.query_advice(advices[6], Rotation::next());
+
+            Constraints::with_selector(s_encode, [compose(&bytes, 1 << 8) - field])
+        });
+
+        Sha256Config {
+            advices,
+            s_word,
+            s_xor3,
+            s_ch,
+            s_maj,
+            s_add,
+            s_encode,
+        }
+    }
+
+    /// Returns the SHA-256 digest of the message bytes. The message bytes are range checked.
+    pub fn digest(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        message: &[Cell],
+    ) -> Result<[Cell; 32], Error> {
+        // Pad the message: message || 0x80 || 0x00.. || bit length(big-endian u64)
+        let padding = {
+            let padded_len = (message.len() + 9 + BLOCK_BYTES - 1) / BLOCK_BYTES * BLOCK_BYTES;
+            let mut padding = vec![0u8; padded_len - message.len()];
+            padding[0] = 0x80;
+            let len = padding.len();
+            padding[len - 8..].copy_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+            padding
+                .into_iter()
+                .map(|byte| {
+                    assign_free_constant(
+                        layouter.namespace(|| "padding byte"),
+                        self.config.advices[0],
+                        pallas::Base::from(byte as u64),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+        };
+        let padded_message = [message, &padding].concat();
+
+        let mut state = IV
+            .iter()
+            .map(|&iv| {
+                let iv = assign_free_constant(
+                    layouter.namespace(|| "iv"),
+                    self.config.advices[0],
+                    pallas::Base::from(iv as u64),
+                )?;
+                self.decompose_word(layouter.namespace(|| "iv word"), WordSource::Word(&iv))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        for block in padded_message.chunks(BLOCK_BYTES) {
+            // The message words are big-endian
+            let block = block
+                .chunks(4)
+                .map(|bytes| {
+                    let bytes: Vec<Cell> = bytes.iter().rev().cloned().collect();
+                    self.decompose_word(
+                        layouter.namespace(|| "message word"),
+                        WordSource::Bytes(&bytes),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            state = self.compress(layouter.namespace(|| "compress"), &state, block)?;
+        }
+
+        // The digest is the big-endian state words
+        let digest = state
+            .iter()
+            .flat_map(|word| word.bytes.iter().rev().cloned())
+            .collect::<Vec<_>>();
+        Ok(digest.try_into().unwrap())
+    }
+
+    /// Encodes the digest to two field elements, see `utils::digest_to_fields`.
+    pub fn encode_digest(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        digest: &[Cell; 32],
+    ) -> Result<[Cell; 2], Error> {
+        let fields = digest
+            .chunks(16)
+            .map(|bytes| {
+                layouter.assign_region(
+                    || "sha256 encode",
+                    |mut region| {
+                        self.config.s_encode.enable(&mut region, 0)?;
+                        for (i, byte) in bytes.iter().enumerate() {
+                            byte.copy_advice(
+                                || "byte",
+                                &mut region,
+                                self.config.advices[i % 10],
+                                i / 10,
+                            )?;
+                        }
+                        let field = bytes.iter().rev().fold(
+                            Value::known(pallas::Base::zero()),
+                            |acc, byte| {
+                                acc.zip(byte.value())
+                                    .map(|(acc, byte)| acc * pallas::Base::from(1 << 8) + byte)
+                            },
+                        );
+                        region.assign_advice(|| "field", self.config.advices[6], 1, || field)
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(fields.try_into().unwrap())
+    }
+
+    fn compress(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        state: &[Sha256Word],
+        block: Vec<Sha256Word>,
+    ) -> Result<Vec<Sha256Word>, Error> {
+        let zero = assign_free_constant(
+            layouter.namespace(|| "zero"),
+            self.config.advices[0],
+            pallas::Base::zero(),
+        )?;
+
+        // Message schedule
+        let mut w = block;
+        for t in 16..64 {
+            // σ0(x) = (x >>> 7) ^ (x >>> 18) ^ (x >> 3)
+            let s0 = self.bitwise_word(
+                layouter.namespace(|| "σ0"),
+                self.config.s_xor3,
+                |a, b, c| a ^ b ^ c,
+                [
+                    rotr(&w[t - 15].bits, 7),
+                    rotr(&w[t - 15].bits, 18),
+                    shr(&w[t - 15].bits, 3, &zero),
+                ],
+            )?;
+            // σ1(x) = (x >>> 17) ^ (x >>> 19) ^ (x >> 10)
+            let s1 = self.bitwise_word(
+                layouter.namespace(|| "σ1"),
+                self.config.s_xor3,
+                |a, b, c| a ^ b ^ c,
+                [
+                    rotr(&w[t - 2].bits, 17),
+                    rotr(&w[t - 2].bits, 19),
+                    shr(&w[t - 2].bits, 10, &zero),
+                ],
+            )?;
+            let sum = self.add(
+                layouter.namespace(|| "w[t]"),
+                &[&s1.word, &w[t - 7].word, &s0.word, &w[t - 16].word],
+            )?;
+            w.push(self.decompose_word(layouter.namespace(|| "w[t]"), WordSource::Word(&sum))?);
+        }
+
+        // Rounds
+        let mut v = state.to_vec();
+        for (t, w) in w.iter().enumerate() {
+            let [a, b, c, d, e, f, g, h]: [Sha256Word; 8] = v.try_into().unwrap();
+            // Σ1(e) = (e >>> 6) ^ (e >>> 11) ^ (e >>> 25)
+            let big_s1 = self.bitwise_word(
+                layouter.namespace(|| "Σ1"),
+                self.config.s_xor3,
+                |a, b, c| a ^ b ^ c,
+                [rotr(&e.bits, 6), rotr(&e.bits, 11), rotr(&e.bits, 25)],
+            )?;
+            // Ch(e, f, g) = (e & f) ^ (!e & g)
+            let ch = self.bitwise_word(
+                layouter.namespace(|| "Ch"),
+                self.config.s_ch,
+                |e, f, g| (e & f) ^ (!e & g),
+                [e.bits.clone(), f.bits.clone(), g.bits.clone()],
+            )?;
+            // Σ0(a) = (a >>> 2) ^ (a >>> 13) ^ (a >>> 22)
+            let big_s0 = self.bitwise_word(
+                layouter.namespace(|| "Σ0"),
+                self.config.s_xor3,
+                |a, b, c| a ^ b ^ c,
+                [rotr(&a.bits, 2), rotr(&a.bits, 13), rotr(&a.bits, 22)],
+            )?;
+            // Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)
+            let maj = self.bitwise_word(
+                layouter.namespace(|| "Maj"),
+                self.config.s_maj,
+                |a, b, c| (a & b) ^ (a & c) ^ (b & c),
+                [a.bits.clone(), b.bits.clone(), c.bits.clone()],
+            )?;
+            let k = assign_free_constant(
+                layouter.namespace(|| "k[t]"),
+                self.config.advices[0],
+                pallas::Base::from(K[t] as u64),
+            )?;
+
+            // T1 = h + Σ1(e) + Ch(e, f, g) + k[t] + w[t], T2 = Σ0(a) + Maj(a, b, c)
+            // e = d + T1
+            let new_e = {
+                let sum = self.add(
+                    layouter.namespace(|| "d + T1"),
+                    &[&d.word, &h.word, &big_s1.word, &ch.word, &k, &w.word],
+                )?;
+                self.decompose_word(layouter.namespace(|| "e"), WordSource::Word(&sum))?
+            };
+            // a = T1 + T2
+            let new_a = {
+                let sum = self.add(
+                    layouter.namespace(|| "T1 + T2"),
+                    &[
+                        &h.word,
+                        &big_s1.word,
+                        &ch.word,
+                        &k,
+                        &w.word,
+                        &big_s0.word,
+                        &maj.word,
+                    ],
+                )?;
+                self.decompose_word(layouter.namespace(|| "a"), WordSource::Word(&sum))?
+            };
+            v = vec![new_a, a, b, c, new_e, e, f, g];
+        }
+
+        // Add the compressed chunk to the current hash value
+        state
+            .iter()
+            .zip(v.iter())
+            .map(|(h, v)| {
+                let sum = self.add(layouter.namespace(|| "h + v"), &[&h.word, &v.word])?;
+                self.decompose_word(layouter.namespace(|| "h"), WordSource::Word(&sum))
+            })
+            .collect()
+    }
+
+    // Decompose the word to bytes and bits, or compose it from the bytes or bits
+    fn decompose_word(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        source: WordSource,
+    ) -> Result<Sha256Word, Error> {
+        let value = source.value();
+        layouter.assign_region(
+            || "sha256 word decompose",
+            |mut region| {
+                self.config.s_word.enable(&mut region, 0)?;
+                let bits = (0..32)
+                    .map(|i| {
+                        let (row, col) = (i / 8, self.config.advices[i % 8]);
+                        match source {
+                            WordSource::Bits(bits) => {
+                                bits[i].copy_advice(|| "bit", &mut region, col, row)
+                            }
+                            _ => region.assign_advice(
+                                || "bit",
+                                col,
+                                row,
+                                || value.map(|v| pallas::Base::from(((v >> i) & 1) as u64)),
+                            ),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let bytes = (0..4)
+                    .map(|i| {
+                        let col = self.config.advices[i + 1];
+                        match source {
+                            WordSource::Bytes(bytes) => {
+                                bytes[i].copy_advice(|| "byte", &mut region, col, 4)
+                            }
+                            _ => region.assign_advice(
+                                || "byte",
+                                col,
+                                4,
+                                || {
+                                    value
+                                        .map(|v| pallas::Base::from(((v >> (8 * i)) & 0xff) as u64))
+                                },
+                            ),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let word = match source {
+                    WordSource::Word(word) => {
+                        word.copy_advice(|| "word", &mut region, self.config.advices[0], 4)?
+                    }
+                    _ => region.assign_advice(
+                        || "word",
+                        self.config.advices[0],
+                        4,
+                        || value.map(|v| pallas::Base::from(v as u64)),
+                    )?,
+                };
+                Ok(Sha256Word { word, bytes, bits })
+            },
+        )
+    }
+
+    // Apply the bitwise operation to every bit and compose the result word
+    fn bitwise_word(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        selector: Selector,
+        op: fn(bool, bool, bool) -> bool,
+        [a, b, c]: [Vec<Cell>; 3],
+    ) -> Result<Sha256Word, Error> {
+        let mut bits = Vec::with_capacity(32);
+        for ((a, b), c) in a.chunks(8).zip(b.chunks(8)).zip(c.chunks(8)) {
+            let chunk = layouter.assign_region(
+                || "sha256 bitwise",
+                |mut region| {
+                    selector.enable(&mut region, 0)?;
+                    (0..8)
+                        .map(|i| {
+                            let col = self.config.advices[i];
+                            a[i].copy_advice(|| "a", &mut region, col, 0)?;
+                            b[i].copy_advice(|| "b", &mut region, col, 1)?;
+                            c[i].copy_advice(|| "c", &mut region, col, 2)?;
+                            let out = a[i].value().zip(b[i].value()).zip(c[i].value()).map(
+                                |((a, b), c)| {
+                                    pallas::Base::from(op(is_one(a), is_one(b), is_one(c)) as u64)
+                                },
+                            );
+                            region.assign_advice(|| "out", col, 3, || out)
+                        })
+                        .collect::<Result<Vec<_>, Error>>()
+                },
+            )?;
+            bits.extend(chunk);
+        }
+        self.decompose_word(layouter.namespace(|| "compose"), WordSource::Bits(&bits))
+    }
+
+    // Add the words modulo 2^32. The result must be decomposed to be range checked.
+    fn add(
+        &self,
+        mut layouter: impl Layouter<pallas::Base>,
+        operands: &[&Cell],
+    ) -> Result<Cell, Error> {
+        assert!(operands.len() <= ADD_OPERANDS);
+        layouter.assign_region(
+            || "sha256 add",
+            |mut region| {
+                self.config.s_add.enable(&mut region, 0)?;
+                let mut sum = Value::known(0u64);
+                for (i, operand) in operands.iter().enumerate() {
+                    operand.copy_advice(|| "operand", &mut region, self.config.advices[i], 0)?;
+                    sum = sum
+                        .zip(operand.value())
+                        .map(|(sum, v)| sum + field_to_u64(v));
+                }
+                for i in operands.len()..ADD_OPERANDS {
+                    region.assign_advice_from_constant(
+                        || "zero operand",
+                        self.config.advices[i],
+                        0,
+                        pallas::Base::zero(),
+                    )?;
+                }
+                for i in 0..3 {
+                    region.assign_advice(
+                        || "carry bit",
+                        self.config.advices[i + 1],
+                        1,
+                        || sum.map(|sum| pallas::Base::from((sum >> (32 + i)) & 1)),
+                    )?;
+                }
+                region.assign_advice(
+                    || "sum mod 2^32",
+                    self.config.advices[0],
+                    1,
+                    || sum.map(|sum| pallas::Base::from(sum & 0xffff_ffff)),
+                )
+            },
+        )
+    }
+}
+
+// The bitwise operation on the bits in the first three rows, and the result in the fourth row
+fn configure_bitwise_gate(
+    meta: &mut ConstraintSystem<pallas::Base>,
+    advices: [Column<Advice>; 10],
+    selector: Selector,
+    name: &'static str,
+    op: impl Fn(
+        Expression<pallas::Base>,
+        Expression<pallas::Base>,
+        Expression<pallas::Base>,
+    ) -> Expression<pallas::Base>,
+) {
+    meta.create_gate(name, |meta| {
+        let selector = meta.query_selector(selector);
+        let constraints = advices[0..8]
+            .iter()
+            .map(|&col| {
+                let a = meta.query_advice(col, Rotation::cur());
+                let b = meta.query_advice(col, Rotation::next());
+                let c = meta.query_advice(col, Rotation(2));
+                let out = meta.query_advice(col, Rotation(3));
+                op(a, b, c) - out
+            })
+            .collect::<Vec<_>>();
+        Constraints::with_selector(selector, constraints)
+    });
+}
+
+// Compose the little-endian digits
+fn compose(digits: &[Expression<pallas::Base>], base: u64) -> Expression<pallas::Base> {
+    digits
+        .iter()
+        .rev()
+        .fold(Expression::Constant(pallas::Base::zero()), |acc, digit| {
+            acc * pallas::Base::from(base) + digit.clone()
+        })
+}
+
+fn rotr(bits: &[Cell], n: usize) -> Vec<Cell> {
+    (0..32).map(|i| bits[(i + n) % 32].clone()).collect()
+}
+
+fn shr(bits: &[Cell], n: usize, zero: &Cell) -> Vec<Cell> {
+    (0..32)
+        .map(|i| bits.get(i + n).unwrap_or(zero).clone())
+        .collect()
+}
+
+fn is_one(value: &pallas::Base) -> bool {
+    *value == pallas::Base::one()
+}
+
+// The witness of a value out of the u64 range is truncated, and the constraints fail anyway.
+fn field_to_u64(value: &pallas::Base) -> u64 {
+    u64::from_le_bytes(value.to_repr()[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sha256Chip, Sha256Config};
+    use crate::circuit::gadgets::assign_free_advice;
+    use crate::utils::digest_to_fields;
+    use halo2_proofs::{
+        circuit::{floor_planner, Layouter, Value},
+        dev::MockProver,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+    use pasta_curves::pallas;
+    use rand::{rngs::OsRng, RngCore};
+    use sha2::{Digest, Sha256};
+
+    struct MyCircuit {
+        message: Vec<u8>,
+        expected_digest: [u8; 32],
+    }
+
+    impl Circuit<pallas::Base> for MyCircuit {
+        type Config = Sha256Config;
+        type FloorPlanner = floor_planner::V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                message: vec![0; self.message.len()],
+                expected_digest: [0; 32],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<pallas::Base>) -> Self::Config {
+            let advices: [Column<Advice>; 10] = (0..10)
+                .map(|_| meta.advice_column())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+            for advice in advices.iter() {
+                meta.enable_equality(*advice);
+            }
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+            Sha256Chip::configure(meta, advices)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<pallas::Base>,
+        ) -> Result<(), Error> {
+            let advice = config.advices[0];
+            let chip = Sha256Chip::construct(config);
+            let message = self
+                .message
+                .iter()
+                .map(|&byte| {
+                    assign_free_advice(
+                        layouter.namespace(|| "message byte"),
+                        advice,
+                        Value::known(pallas::Base::from(byte as u64)),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let digest = chip.digest(layouter.namespace(|| "sha256"), &message)?;
+            let fields = chip.encode_digest(layouter.namespace(|| "encode digest"), &digest)?;
+
+            let expected_fields = digest_to_fields(&self.expected_digest);
+            for (field, expected_field) in fields.iter().zip(expected_fields) {
+                let expected_field = assign_free_advice(
+                    layouter.namespace(|| "expected field"),
+                    advice,
+                    Value::known(expected_field),
+                )?;
+                layouter.assign_region(
+                    || "check digest",
+                    |mut region| region.constrain_equal(field.cell(), expected_field.cell()),
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    fn verify(message: &[u8], expected_digest: [u8; 32]) -> bool {
+        let circuit = MyCircuit {
+            message: message.to_vec(),
+            expected_digest,
+        };
+        MockProver::run(15, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    fn from_hex(hex: &str) -> [u8; 32] {
+        (0..32)
+            .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_halo2_sha256_chip() {
+        // Test vectors from FIPS 180-2
+        assert!(verify(
+            b"abc",
+            from_hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        ));
+        assert!(verify(
+            b"",
+            from_hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        ));
+        // Two blocks
+        assert!(verify(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            from_hex("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+        ));
+
+        // A random preimage against the reference implementation
+        let mut message = [0u8; 40];
+        OsRng.fill_bytes(&mut message);
+        let expected_digest: [u8; 32] = Sha256::digest(message).into();
+        assert!(verify(&message, expected_digest));
+
+        // A wrong preimage
+        message[0] ^= 1;
+        assert!(!verify(&message, expected_digest));
+    }
+}
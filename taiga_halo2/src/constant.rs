@@ -27,6 +27,9 @@ pub const TRANSACTION_BINDING_HASH_PERSONALIZATION: &[u8; 16] = b"TxBindingSigHa
 pub const RESOURCE_LOGIC_COMMITMENT_PERSONALIZATION: &[u8; 8] = b"VPCommit";
 
 pub const PRF_EXPAND_PERSONALIZATION: &[u8; 16] = b"Taiga_ExpandSeed";
+
+/// The personalization to derive the outgoing cipher key from the outgoing viewing key
+pub const OUTGOING_CIPHER_KEY_PERSONALIZATION: &[u8; 16] = b"Taiga_OutCiphKey";
 lazy_static! {
     pub static ref PRF_EXPAND_PERSONALIZATION_TO_FIELD: pallas::Base =
         to_field_elements(PRF_EXPAND_PERSONALIZATION)[0];
@@ -40,6 +43,10 @@ pub const PRF_EXPAND_INPUT_RESOURCE_LOGIC_CM_R: u8 = 4;
 pub const PRF_EXPAND_OUTPUT_RESOURCE_LOGIC_CM_R: u8 = 5;
 pub const PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_1_CM_R: u8 = 6;
 pub const PRF_EXPAND_DYNAMIC_RESOURCE_LOGIC_2_CM_R: u8 = 7;
+pub const PRF_EXPAND_NK: u8 = 8;
+pub const PRF_EXPAND_AUTH_SK: u8 = 9;
+pub const PRF_EXPAND_IVK: u8 = 10;
+pub const PRF_EXPAND_OVK: u8 = 11;
 
 /// Commitment merkle tree depth
 pub const TAIGA_COMMITMENT_TREE_DEPTH: usize = 32;
@@ -65,14 +72,14 @@ pub const POSEIDON_TO_CURVE_INPUT_LEN: usize = 3;
 pub const CURVE_ID: &str = "pallas";
 pub const VALUE_BASE_DOMAIN_POSTFIX: &str = "Taiga-NoteType";
 
+// The default number of resource logic public inputs. The resource logics that encrypt longer
+// messages declare more public inputs, see `resource_logic_public_input_num`.
 pub const RESOURCE_LOGIC_CIRCUIT_PUBLIC_INPUT_NUM: usize =
-    RESOURCE_LOGIC_CIRCUIT_MANDATORY_PUBLIC_INPUT_NUM
-        + RESOURCE_LOGIC_CIRCUIT_CUSTOM_PUBLIC_INPUT_NUM
-        + RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_NUM;
-pub const RESOURCE_LOGIC_CIRCUIT_MANDATORY_PUBLIC_INPUT_NUM: usize = 6;
+    resource_logic_public_input_num(RESOURCE_ENCRYPTION_PLAINTEXT_NUM);
+pub const RESOURCE_LOGIC_CIRCUIT_MANDATORY_PUBLIC_INPUT_NUM: usize = 8;
 pub const RESOURCE_LOGIC_CIRCUIT_CUSTOM_PUBLIC_INPUT_NUM: usize = 2;
 pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_NUM: usize =
-    RESOURCE_ENCRYPTION_CIPHERTEXT_NUM + 2; // ciphertext(12) + public_key(2)
+    RESOURCE_ENCRYPTION_CIPHERTEXT_NUM + 2; // public_key(2) + ciphertext(12)
 
 // resource logic public input index
 pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_MERKLE_ROOT_IDX: usize = 0;
@@ -81,19 +88,31 @@ pub const RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_1: usize = 2;
 pub const RESOURCE_LOGIC_CIRCUIT_FIRST_DYNAMIC_RESOURCE_LOGIC_CM_2: usize = 3;
 pub const RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_1: usize = 4;
 pub const RESOURCE_LOGIC_CIRCUIT_SECOND_DYNAMIC_RESOURCE_LOGIC_CM_2: usize = 5;
+// The transaction validity window, see `ValidityWindow`. It follows the dynamic resource logic
+// commitments so that the logics declaring the first 6 public inputs keep their layout.
+pub const RESOURCE_LOGIC_CIRCUIT_NOT_BEFORE_IDX: usize = 6;
+pub const RESOURCE_LOGIC_CIRCUIT_EXPIRES_AT_IDX: usize = 7;
 pub const RESOURCE_LOGIC_CIRCUIT_CUSTOM_PUBLIC_INPUT_BEGIN_IDX: usize =
     RESOURCE_LOGIC_CIRCUIT_MANDATORY_PUBLIC_INPUT_NUM;
 pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PUBLIC_INPUT_BEGIN_IDX: usize =
     RESOURCE_LOGIC_CIRCUIT_MANDATORY_PUBLIC_INPUT_NUM
         + RESOURCE_LOGIC_CIRCUIT_CUSTOM_PUBLIC_INPUT_NUM;
-pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_NONCE_IDX: usize = 18;
-pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_MAC_IDX: usize = 19;
-pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_X_IDX: usize = 20;
-pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_Y_IDX: usize = 21;
+pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_X_IDX: usize = 10;
+pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_ENCRYPTION_PK_Y_IDX: usize = 11;
+// The ciphertext takes the rest of the public inputs: msg(n) + NONCE(1) + MAC(1)
+pub const RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX: usize = 12;
+
+/// The number of public inputs of a resource logic that encrypts `plaintext_num` field elements.
+pub const fn resource_logic_public_input_num(plaintext_num: usize) -> usize {
+    RESOURCE_LOGIC_CIRCUIT_RESOURCE_CIPHERTEXT_BEGIN_IDX + plaintext_num + 2
+}
 
 // Resource encryption
+// The plaintext is padded to the default length, and longer messages are allowed.
 pub const RESOURCE_ENCRYPTION_PLAINTEXT_NUM: usize = 10;
 pub const RESOURCE_ENCRYPTION_CIPHERTEXT_NUM: usize = RESOURCE_ENCRYPTION_PLAINTEXT_NUM + 2; // msg(10) + MAC(1) + NOUNCE(1)
+/// The number of bytes packed into one field element
+pub const RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT: usize = 31;
 
 // Poseidon parameters
 pub const POSEIDON_RATE: usize = 2;
@@ -28,6 +28,22 @@ pub enum TransactionError {
     InvalidResourceLogicRepresentation,
     /// Resource merkle root is inconsistent between the compliance and the resource logic.
     InconsistentResourceMerkleRoot,
+    /// The owned resources are not enough to cover the outputs.
+    InsufficientFunds,
+    /// The token quantities overflow u64.
+    QuantityOverflow,
+    /// The compliance pairs exceed the resource tree capacity.
+    TooManyResources,
+    /// The multisig threshold or signer public keys are not valid.
+    InvalidMultisigPolicy,
+    /// The signer is not in the multisig policy.
+    InvalidMultisigSigner,
+    /// The resource logic validity window doesn't contain the transaction validity window.
+    InvalidValidityWindow,
+    /// The offer doesn't match the intent.
+    InvalidOffer,
+    /// The spendable resource is not owned by the sender.
+    InvalidResourceOwner,
 }
 
 impl Display for TransactionError {
@@ -62,6 +78,14 @@ impl Display for TransactionError {
             InconsistentResourceMerkleRoot => {
                 f.write_str("Resource merkle root is not consistent between the compliance and the resource logic")
             }
+            InsufficientFunds => f.write_str("The owned resources are not enough to cover the outputs"),
+            QuantityOverflow => f.write_str("The token quantities overflow u64"),
+            TooManyResources => f.write_str("The compliance pairs exceed the resource tree capacity"),
+            InvalidMultisigPolicy => f.write_str("The multisig threshold or signer public keys are not valid"),
+            InvalidMultisigSigner => f.write_str("The signer is not in the multisig policy"),
+            InvalidValidityWindow => f.write_str("The resource logic validity window doesn't contain the transaction validity window"),
+            InvalidOffer => f.write_str("The offer doesn't match the intent"),
+            InvalidResourceOwner => f.write_str("The spendable resource is not owned by the sender"),
         }
     }
 }
@@ -0,0 +1,229 @@
+/// The indexed Merkle tree keeps its leaves in a linked list sorted by value. The low leaf of a
+/// value, i.e. the leaf with the largest smaller value, proves the value is not in the tree, e.g.
+/// a revoked nullifier set or a blocklist. The tree starts with the sentinel leaves 0 and -1,
+/// which are always members.
+use crate::merkle_tree::{Anchor, MerklePath, Node, LR};
+use crate::utils::poseidon_hash_n;
+use ff::PrimeField;
+use pasta_curves::pallas;
+
+#[cfg(feature = "serde")]
+use serde;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// A leaf of the indexed Merkle tree, linked to the leaf with the next larger value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexedLeaf {
+    pub value: pallas::Base,
+    pub next_index: u64,
+    pub next_value: pallas::Base,
+}
+
+impl IndexedLeaf {
+    pub fn hash(&self) -> Node {
+        Node::from(poseidon_hash_n([
+            self.value,
+            pallas::Base::from(self.next_index),
+            self.next_value,
+        ]))
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for IndexedLeaf {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.value.to_repr())?;
+        self.next_index.serialize(writer)?;
+        writer.write_all(&self.next_value.to_repr())?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for IndexedLeaf {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let value = crate::utils::read_base_field(reader)?;
+        let next_index = u64::deserialize_reader(reader)?;
+        let next_value = crate::utils::read_base_field(reader)?;
+        Ok(Self {
+            value,
+            next_index,
+            next_value,
+        })
+    }
+}
+
+/// A leaf with its Merkle path, used to prove membership or non-membership of a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexedMerkleWitness {
+    pub leaf: IndexedLeaf,
+    pub merkle_path: MerklePath,
+}
+
+impl IndexedMerkleWitness {
+    pub fn root(&self) -> Anchor {
+        self.merkle_path.root(self.leaf.hash())
+    }
+
+    /// Returns true if the leaf value is the largest one smaller than the value in the tree.
+    pub fn is_low_leaf_of(&self, value: &pallas::Base) -> bool {
+        less_than(&self.leaf.value, value) && less_than(value, &self.leaf.next_value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedMerkleTree {
+    depth: usize,
+    leaves: Vec<IndexedLeaf>,
+}
+
+impl IndexedMerkleTree {
+    /// Creates a tree with the sentinel leaves 0 and -1.
+    pub fn new(depth: usize) -> Self {
+        assert!(depth >= 1);
+        let max = -pallas::Base::one();
+        let leaves = vec![
+            IndexedLeaf {
+                value: pallas::Base::zero(),
+                next_index: 1,
+                next_value: max,
+            },
+            IndexedLeaf {
+                value: max,
+                next_index: 0,
+                next_value: pallas::Base::zero(),
+            },
+        ];
+        Self { depth, leaves }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn contains(&self, value: &pallas::Base) -> bool {
+        self.leaves.iter().any(|leaf| leaf.value == *value)
+    }
+
+    /// Inserts the value and returns its leaf index, or None if the value is already in the tree
+    /// or the tree is full.
+    pub fn insert(&mut self, value: pallas::Base) -> Option<usize> {
+        if self.leaves.len() == 1 << self.depth || self.contains(&value) {
+            return None;
+        }
+        let index = self.leaves.len();
+        let low_leaf_index = self.low_leaf_index(&value)?;
+        let low_leaf = &mut self.leaves[low_leaf_index];
+        let leaf = IndexedLeaf {
+            value,
+            next_index: low_leaf.next_index,
+            next_value: low_leaf.next_value,
+        };
+        low_leaf.next_index = index as u64;
+        low_leaf.next_value = value;
+        self.leaves.push(leaf);
+        Some(index)
+    }
+
+    pub fn root(&self) -> Anchor {
+        self.witness(0).root()
+    }
+
+    /// Returns the witness of the leaf of the value, or None if the value is not in the tree.
+    pub fn membership_witness(&self, value: &pallas::Base) -> Option<IndexedMerkleWitness> {
+        self.leaves
+            .iter()
+            .position(|leaf| leaf.value == *value)
+            .map(|index| self.witness(index))
+    }
+
+    /// Returns the witness of the low leaf of the value, or None if the value is in the tree.
+    pub fn non_membership_witness(&self, value: &pallas::Base) -> Option<IndexedMerkleWitness> {
+        self.low_leaf_index(value).map(|index| self.witness(index))
+    }
+
+    fn low_leaf_index(&self, value: &pallas::Base) -> Option<usize> {
+        self.leaves
+            .iter()
+            .position(|leaf| less_than(&leaf.value, value) && less_than(value, &leaf.next_value))
+    }
+
+    fn witness(&self, index: usize) -> IndexedMerkleWitness {
+        let mut nodes: Vec<Node> = self.leaves.iter().map(|leaf| leaf.hash()).collect();
+        let mut empty = Node::from(pallas::Base::zero());
+        let mut position = index;
+        let mut merkle_path = Vec::with_capacity(self.depth);
+        for _ in 0..self.depth {
+            let sibling = nodes.get(position ^ 1).copied().unwrap_or(empty);
+            merkle_path.push((sibling, LR::from(position % 2 == 1)));
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| Node::combine(&pair[0], pair.get(1).unwrap_or(&empty)))
+                .collect();
+            empty = Node::combine(&empty, &empty);
+            position /= 2;
+        }
+        IndexedMerkleWitness {
+            leaf: self.leaves[index],
+            merkle_path: MerklePath::from_path(merkle_path),
+        }
+    }
+}
+
+// Compare the field elements as canonical integers
+fn less_than(a: &pallas::Base, b: &pallas::Base) -> bool {
+    let mut a = a.to_repr();
+    let mut b = b.to_repr();
+    a.reverse();
+    b.reverse();
+    a < b
+}
+
+#[test]
+fn test_indexed_merkle_tree() {
+    use halo2_proofs::arithmetic::Field;
+    use rand::rngs::OsRng;
+
+    let mut tree = IndexedMerkleTree::new(8);
+    let values: Vec<pallas::Base> = (0..10).map(|_| pallas::Base::random(OsRng)).collect();
+    for (i, value) in values.iter().enumerate() {
+        assert_eq!(tree.insert(*value), Some(i + 2));
+    }
+    assert_eq!(tree.insert(values[0]), None);
+
+    let root = tree.root();
+    for value in values.iter() {
+        let witness = tree.membership_witness(value).unwrap();
+        assert_eq!(witness.root(), root);
+        assert!(tree.non_membership_witness(value).is_none());
+    }
+
+    let absent = pallas::Base::random(OsRng);
+    assert!(tree.membership_witness(&absent).is_none());
+    let witness = tree.non_membership_witness(&absent).unwrap();
+    assert!(witness.is_low_leaf_of(&absent));
+    assert_eq!(witness.root(), root);
+
+    // The sentinel leaves are always members
+    assert!(tree.non_membership_witness(&pallas::Base::zero()).is_none());
+    assert!(tree.non_membership_witness(&-pallas::Base::one()).is_none());
+
+    // The tree is full
+    let mut tree = IndexedMerkleTree::new(2);
+    assert_eq!(tree.insert(pallas::Base::from(2)), Some(2));
+    assert_eq!(tree.insert(pallas::Base::from(1)), Some(3));
+    assert_eq!(tree.insert(pallas::Base::from(3)), None);
+}
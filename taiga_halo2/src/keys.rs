@@ -0,0 +1,366 @@
+use crate::{
+    constant::{
+        GENERATOR, PRF_EXPAND_AUTH_SK, PRF_EXPAND_IVK, PRF_EXPAND_NK, PRF_EXPAND_OVK,
+        PRF_EXPAND_PERSONALIZATION,
+    },
+    nullifier::NullifierKeyContainer,
+    utils::mod_r_p,
+};
+use blake2b_simd::Params as Blake2bParams;
+use ff::FromUniformBytes;
+use pasta_curves::{group::Curve, pallas};
+use rand::RngCore;
+
+#[cfg(feature = "serde")]
+use serde;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "borsh")]
+use pasta_curves::group::{ff::PrimeField, GroupEncoding};
+
+/// The spending key is the root seed of the key hierarchy. Every key of a user
+/// is derived from it:
+///
+/// ```text
+/// SpendingKey
+///   ├── nk (nullifier key) ──────────────> npk = prf_nf(nk, 0)
+///   ├── auth_sk (authorization key) ─────> auth_pk = auth_sk * G
+///   ├── ivk (incoming viewing key) ──────> encryption_pk = ivk * G
+///   └── ovk (outgoing viewing key)
+/// ```
+///
+/// The `FullViewingKey` contains everything except the secrets that authorize
+/// spending (`auth_sk`), and the `PaymentAddress` only contains public keys.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct SpendingKey([u8; 32]);
+
+/// The incoming viewing key decrypts the resources sent to the address.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IncomingViewingKey(pallas::Base);
+
+/// The outgoing viewing key recovers the resources sent by the address.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+pub struct OutgoingViewingKey([u8; 32]);
+
+/// The full viewing key can detect both the received and the spent resources,
+/// but it can not authorize spending.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullViewingKey {
+    nk: pallas::Base,
+    auth_pk: pallas::Point,
+    ivk: IncomingViewingKey,
+    ovk: OutgoingViewingKey,
+}
+
+/// The payment address is shared with senders. It contains everything needed to
+/// create an output resource owned by the address.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaymentAddress {
+    // The npk goes to the output resource.
+    npk: pallas::Base,
+    // The resources are encrypted to encryption_pk.
+    encryption_pk: pallas::Point,
+    // The authorization public key checked when the resource is consumed.
+    auth_pk: pallas::Point,
+    // The compressed vk of the authorization resource logic.
+    auth_vk: pallas::Base,
+}
+
+fn prf_expand(sk: &[u8; 32], tag: u8) -> [u8; 64] {
+    let mut h = Blake2bParams::new()
+        .hash_length(64)
+        .personal(PRF_EXPAND_PERSONALIZATION)
+        .to_state();
+    h.update(&[tag]);
+    h.update(sk);
+    *h.finalize().as_array()
+}
+
+impl SpendingKey {
+    pub fn random<R: RngCore>(mut rng: R) -> Self {
+        let mut sk = [0; 32];
+        rng.fill_bytes(&mut sk);
+        Self(sk)
+    }
+
+    pub fn from_bytes(sk: [u8; 32]) -> Self {
+        Self(sk)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    pub fn get_nk(&self) -> pallas::Base {
+        pallas::Base::from_uniform_bytes(&prf_expand(&self.0, PRF_EXPAND_NK))
+    }
+
+    pub fn get_nk_container(&self) -> NullifierKeyContainer {
+        NullifierKeyContainer::from_key(self.get_nk())
+    }
+
+    pub fn get_npk(&self) -> pallas::Base {
+        self.get_nk_container().get_npk()
+    }
+
+    pub fn get_auth_sk(&self) -> pallas::Scalar {
+        pallas::Scalar::from_uniform_bytes(&prf_expand(&self.0, PRF_EXPAND_AUTH_SK))
+    }
+
+    pub fn get_auth_pk(&self) -> pallas::Point {
+        GENERATOR.to_curve() * self.get_auth_sk()
+    }
+
+    pub fn get_ivk(&self) -> IncomingViewingKey {
+        IncomingViewingKey(pallas::Base::from_uniform_bytes(&prf_expand(
+            &self.0,
+            PRF_EXPAND_IVK,
+        )))
+    }
+
+    pub fn get_ovk(&self) -> OutgoingViewingKey {
+        let mut ovk = [0; 32];
+        ovk.copy_from_slice(&prf_expand(&self.0, PRF_EXPAND_OVK)[..32]);
+        OutgoingViewingKey(ovk)
+    }
+
+    pub fn get_full_viewing_key(&self) -> FullViewingKey {
+        FullViewingKey {
+            nk: self.get_nk(),
+            auth_pk: self.get_auth_pk(),
+            ivk: self.get_ivk(),
+            ovk: self.get_ovk(),
+        }
+    }
+
+    /// Derive the payment address. `auth_vk` is the compressed vk of the
+    /// resource logic checking the authorization, e.g. the signature verification.
+    pub fn get_address(&self, auth_vk: pallas::Base) -> PaymentAddress {
+        self.get_full_viewing_key().get_address(auth_vk)
+    }
+}
+
+impl IncomingViewingKey {
+    pub fn from_inner(ivk: pallas::Base) -> Self {
+        Self(ivk)
+    }
+
+    pub fn inner(&self) -> pallas::Base {
+        self.0
+    }
+
+    pub fn get_encryption_pk(&self) -> pallas::Point {
+        GENERATOR.to_curve() * mod_r_p(self.0)
+    }
+}
+
+impl OutgoingViewingKey {
+    pub fn from_bytes(ovk: [u8; 32]) -> Self {
+        Self(ovk)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl FullViewingKey {
+    pub fn get_nk(&self) -> pallas::Base {
+        self.nk
+    }
+
+    pub fn get_npk(&self) -> pallas::Base {
+        NullifierKeyContainer::from_key(self.nk).get_npk()
+    }
+
+    pub fn get_auth_pk(&self) -> pallas::Point {
+        self.auth_pk
+    }
+
+    pub fn get_ivk(&self) -> IncomingViewingKey {
+        self.ivk
+    }
+
+    pub fn get_ovk(&self) -> OutgoingViewingKey {
+        self.ovk
+    }
+
+    pub fn get_address(&self, auth_vk: pallas::Base) -> PaymentAddress {
+        PaymentAddress {
+            npk: self.get_npk(),
+            encryption_pk: self.ivk.get_encryption_pk(),
+            auth_pk: self.auth_pk,
+            auth_vk,
+        }
+    }
+}
+
+impl PaymentAddress {
+    pub fn new(
+        npk: pallas::Base,
+        encryption_pk: pallas::Point,
+        auth_pk: pallas::Point,
+        auth_vk: pallas::Base,
+    ) -> Self {
+        Self {
+            npk,
+            encryption_pk,
+            auth_pk,
+            auth_vk,
+        }
+    }
+
+    pub fn get_npk(&self) -> pallas::Base {
+        self.npk
+    }
+
+    pub fn get_encryption_pk(&self) -> pallas::Point {
+        self.encryption_pk
+    }
+
+    pub fn get_auth_pk(&self) -> pallas::Point {
+        self.auth_pk
+    }
+
+    pub fn get_auth_vk(&self) -> pallas::Base {
+        self.auth_vk
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for IncomingViewingKey {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.0.to_repr())?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for IncomingViewingKey {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let ivk = crate::utils::read_base_field(reader)?;
+        Ok(Self(ivk))
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for FullViewingKey {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.nk.to_repr())?;
+        writer.write_all(&self.auth_pk.to_bytes())?;
+        self.ivk.serialize(writer)?;
+        self.ovk.serialize(writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for FullViewingKey {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let nk = crate::utils::read_base_field(reader)?;
+        let auth_pk = crate::utils::read_point(reader)?;
+        let ivk = IncomingViewingKey::deserialize_reader(reader)?;
+        let ovk = OutgoingViewingKey::deserialize_reader(reader)?;
+        Ok(Self {
+            nk,
+            auth_pk,
+            ivk,
+            ovk,
+        })
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for PaymentAddress {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.npk.to_repr())?;
+        writer.write_all(&self.encryption_pk.to_bytes())?;
+        writer.write_all(&self.auth_pk.to_bytes())?;
+        writer.write_all(&self.auth_vk.to_repr())?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for PaymentAddress {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let npk = crate::utils::read_base_field(reader)?;
+        let encryption_pk = crate::utils::read_point(reader)?;
+        let auth_pk = crate::utils::read_point(reader)?;
+        let auth_vk = crate::utils::read_base_field(reader)?;
+        Ok(Self {
+            npk,
+            encryption_pk,
+            auth_pk,
+            auth_vk,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{PaymentAddress, SpendingKey};
+    use ff::Field;
+    use pasta_curves::pallas;
+    use rand::RngCore;
+
+    pub fn random_spending_key<R: RngCore>(rng: R) -> SpendingKey {
+        SpendingKey::random(rng)
+    }
+
+    pub fn random_address<R: RngCore>(mut rng: R) -> PaymentAddress {
+        random_spending_key(&mut rng).get_address(pallas::Base::random(&mut rng))
+    }
+
+    #[test]
+    fn test_key_derivation() {
+        use crate::constant::GENERATOR;
+        use crate::utils::{mod_r_p, prf_nf};
+        use pasta_curves::group::Curve;
+        use rand::rngs::OsRng;
+
+        let mut rng = OsRng;
+        let sk = random_spending_key(&mut rng);
+        // The derivation is deterministic
+        assert_eq!(sk, SpendingKey::from_bytes(sk.to_bytes()));
+        assert_eq!(sk.get_nk(), SpendingKey::from_bytes(sk.to_bytes()).get_nk());
+
+        // The keys are independent
+        assert_ne!(sk.get_nk(), sk.get_ivk().inner());
+        assert_ne!(sk.get_ovk(), SpendingKey::random(&mut rng).get_ovk());
+
+        let auth_vk = pallas::Base::random(&mut rng);
+        let address = sk.get_address(auth_vk);
+        assert_eq!(address.get_npk(), prf_nf(sk.get_nk(), pallas::Base::zero()));
+        assert_eq!(
+            address.get_encryption_pk(),
+            GENERATOR.to_curve() * mod_r_p(sk.get_ivk().inner())
+        );
+        assert_eq!(
+            address.get_auth_pk(),
+            GENERATOR.to_curve() * sk.get_auth_sk()
+        );
+        assert_eq!(address.get_auth_vk(), auth_vk);
+        assert_eq!(address, sk.get_full_viewing_key().get_address(auth_vk));
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_address_serialization() {
+        use borsh::BorshDeserialize;
+        use rand::rngs::OsRng;
+
+        let address = random_address(OsRng);
+        let bytes = borsh::to_vec(&address).unwrap();
+        let de_address = PaymentAddress::deserialize(&mut bytes.as_ref()).unwrap();
+        assert_eq!(address, de_address);
+    }
+}
@@ -8,6 +8,8 @@ pub mod constant;
 pub mod delta_commitment;
 pub mod error;
 mod executable;
+pub mod indexed_merkle_tree;
+pub mod keys;
 pub mod merkle_tree;
 pub mod nullifier;
 pub mod proof;
@@ -21,3 +23,4 @@ pub mod taiga_api;
 pub mod transaction;
 pub mod transparent_ptx;
 pub mod utils;
+pub mod wallet;
@@ -79,6 +79,19 @@ impl Hash for ResourceCommitment {
     }
 }
 
+// Order the commitments by their bytes, so that the sets of commitments serialize deterministically
+impl PartialOrd for ResourceCommitment {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ResourceCommitment {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.to_bytes().cmp(&other.to_bytes())
+    }
+}
+
 /// A resource
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "nif", derive(NifStruct))]
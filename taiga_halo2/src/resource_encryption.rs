@@ -1,24 +1,48 @@
-use crate::constant::{
-    POSEIDON_RATE, POSEIDON_WIDTH, RESOURCE_ENCRYPTION_CIPHERTEXT_NUM,
-    RESOURCE_ENCRYPTION_PLAINTEXT_NUM,
+use crate::{
+    constant::{
+        GENERATOR, OUTGOING_CIPHER_KEY_PERSONALIZATION, POSEIDON_RATE, POSEIDON_WIDTH,
+        RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT, RESOURCE_ENCRYPTION_CIPHERTEXT_NUM,
+        RESOURCE_ENCRYPTION_PLAINTEXT_NUM,
+    },
+    keys::OutgoingViewingKey,
+    resource::ResourceCommitment,
+    utils::mod_r_p,
 };
-use ff::PrimeField;
-use group::Curve;
+use blake2b_simd::Params as Blake2bParams;
+use ff::{FromUniformBytes, PrimeField};
+use group::{Curve, GroupEncoding};
 use halo2_gadgets::poseidon::primitives as poseidon;
 use halo2_proofs::arithmetic::CurveAffine;
 use pasta_curves::pallas;
+#[cfg(feature = "nif")]
+use rustler::NifTuple;
 
+#[cfg(feature = "serde")]
+use serde;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+// The ciphertext contains the encrypted message, the encrypt_nonce and the MAC.
 #[derive(Debug, Clone)]
-pub struct ResourceCiphertext([pallas::Base; RESOURCE_ENCRYPTION_CIPHERTEXT_NUM]);
+pub struct ResourceCiphertext(Vec<pallas::Base>);
 
 #[derive(Debug, Clone)]
-pub struct ResourcePlaintext([pallas::Base; RESOURCE_ENCRYPTION_PLAINTEXT_NUM]);
+pub struct ResourcePlaintext(Vec<pallas::Base>);
 
 #[derive(Debug, Clone)]
 pub struct SecretKey(pallas::Point);
 
+/// The outgoing ciphertext encrypts the receiver pk and the ephemeral sk of a resource
+/// ciphertext under the sender's outgoing viewing key, so that the sender can recover
+/// the resources it sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifTuple))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutgoingCiphertext(Vec<pallas::Base>);
+
 impl ResourceCiphertext {
-    pub fn inner(&self) -> &[pallas::Base; RESOURCE_ENCRYPTION_CIPHERTEXT_NUM] {
+    pub fn inner(&self) -> &[pallas::Base] {
         &self.0
     }
 
@@ -60,6 +84,9 @@ impl ResourceCiphertext {
 
     pub fn decrypt(&self, secret_key: &SecretKey) -> Option<Vec<pallas::Base>> {
         let cipher_len = self.0.len();
+        if cipher_len < 2 {
+            return None;
+        }
         let mac = self.0[cipher_len - 1];
         let encrypt_nonce = self.0[cipher_len - 2];
         // Init poseidon sponge state
@@ -115,39 +142,87 @@ impl ResourceCiphertext {
 
 impl From<Vec<pallas::Base>> for ResourceCiphertext {
     fn from(input_vec: Vec<pallas::Base>) -> Self {
-        ResourceCiphertext(
-            input_vec
-                .try_into()
-                .expect("public input with incorrect length"),
-        )
+        ResourceCiphertext(input_vec)
     }
 }
 
 impl ResourcePlaintext {
-    pub fn inner(&self) -> &[pallas::Base; RESOURCE_ENCRYPTION_PLAINTEXT_NUM] {
+    pub fn inner(&self) -> &[pallas::Base] {
         &self.0
     }
 
     pub fn to_vec(&self) -> Vec<pallas::Base> {
-        self.0.to_vec()
+        self.0.clone()
     }
 
+    /// Pad the message with zeros to the default plaintext length. Longer messages are kept as they are.
     pub fn padding(msg: &[pallas::Base]) -> Self {
         let mut plaintext = msg.to_owned();
         let padding = std::iter::repeat(pallas::Base::zero())
-            .take(RESOURCE_ENCRYPTION_PLAINTEXT_NUM - msg.len());
+            .take(RESOURCE_ENCRYPTION_PLAINTEXT_NUM.saturating_sub(msg.len()));
         plaintext.extend(padding);
         plaintext.into()
     }
+
+    /// Pack the bytes into field elements. The first element is the byte length, and each of
+    /// the following elements packs 31 bytes in little-endian order. The length is encrypted
+    /// and authenticated together with the bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut msg = vec![pallas::Base::from(bytes.len() as u64)];
+        msg.extend(
+            bytes
+                .chunks(RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT)
+                .map(|chunk| {
+                    let mut repr = [0u8; 32];
+                    repr[..chunk.len()].copy_from_slice(chunk);
+                    pallas::Base::from_repr(repr).unwrap()
+                }),
+        );
+        Self::padding(&msg)
+    }
+
+    /// Unpack the bytes from the decrypted message created by `from_bytes`.
+    pub fn to_bytes(msg: &[pallas::Base]) -> Option<Vec<u8>> {
+        let (len, elements) = msg.split_first()?;
+        let len = {
+            let repr = len.to_repr();
+            if repr[8..].iter().any(|b| *b != 0) {
+                return None;
+            }
+            u64::from_le_bytes(repr[..8].try_into().unwrap())
+        };
+        // Bound the length by the message before using it, it is untrusted decrypted data.
+        if len > (elements.len() * RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT) as u64 {
+            return None;
+        }
+        let len = len as usize;
+        let element_num = len.div_ceil(RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT);
+
+        let mut bytes = vec![];
+        for element in elements[..element_num].iter() {
+            let repr = element.to_repr();
+            if repr[RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT] != 0 {
+                return None;
+            }
+            bytes.extend_from_slice(&repr[..RESOURCE_ENCRYPTION_BYTES_PER_ELEMENT]);
+        }
+
+        // The bytes beyond the length and the padding elements must be zero.
+        if bytes[len..].iter().any(|b| *b != 0)
+            || elements[element_num..]
+                .iter()
+                .any(|element| *element != pallas::Base::zero())
+        {
+            return None;
+        }
+        bytes.truncate(len);
+        Some(bytes)
+    }
 }
 
 impl From<Vec<pallas::Base>> for ResourcePlaintext {
     fn from(input_vec: Vec<pallas::Base>) -> Self {
-        ResourcePlaintext(
-            input_vec
-                .try_into()
-                .expect("public input with incorrect length"),
-        )
+        ResourcePlaintext(input_vec)
     }
 }
 
@@ -156,6 +231,23 @@ impl SecretKey {
         Self(pk * sk)
     }
 
+    /// Derive the outgoing cipher key: ock = Blake2b(ovk || cm || sender_pk) * G
+    pub fn from_ovk(
+        ovk: &OutgoingViewingKey,
+        cm: &ResourceCommitment,
+        sender_pk: &pallas::Point,
+    ) -> Self {
+        let mut h = Blake2bParams::new()
+            .hash_length(64)
+            .personal(OUTGOING_CIPHER_KEY_PERSONALIZATION)
+            .to_state();
+        h.update(&ovk.to_bytes());
+        h.update(&cm.to_bytes());
+        h.update(&sender_pk.to_bytes());
+        let ock = pallas::Scalar::from_uniform_bytes(h.finalize().as_array());
+        Self(GENERATOR.to_curve() * ock)
+    }
+
     pub fn inner(&self) -> pallas::Point {
         self.0
     }
@@ -166,6 +258,77 @@ impl SecretKey {
     }
 }
 
+impl OutgoingCiphertext {
+    pub fn inner(&self) -> &[pallas::Base] {
+        &self.0
+    }
+
+    /// Encrypt the receiver pk and the ephemeral sk used to encrypt the resource with
+    /// commitment `cm`. `sender_pk` is the ephemeral pk published with the resource ciphertext.
+    pub fn encrypt(
+        ovk: &OutgoingViewingKey,
+        cm: &ResourceCommitment,
+        sender_pk: &pallas::Point,
+        rcv_pk: &pallas::Point,
+        sk: &pallas::Base,
+    ) -> Self {
+        let rcv_pk_coord = rcv_pk.to_affine().coordinates().unwrap();
+        let plaintext = ResourcePlaintext::padding(&[*rcv_pk_coord.x(), *rcv_pk_coord.y(), *sk]);
+        let key = SecretKey::from_ovk(ovk, cm, sender_pk);
+        // The key is unique to the resource ciphertext, so a fixed nonce is fine.
+        let cipher = ResourceCiphertext::encrypt(&plaintext, &key, &pallas::Base::zero());
+        Self(cipher.inner().to_vec())
+    }
+
+    /// Recover the receiver pk and the ephemeral sk. Returns None if the ciphertext is not
+    /// created with the ovk for the resource.
+    pub fn decrypt(
+        &self,
+        ovk: &OutgoingViewingKey,
+        cm: &ResourceCommitment,
+        sender_pk: &pallas::Point,
+    ) -> Option<(pallas::Point, pallas::Base)> {
+        if self.0.len() != RESOURCE_ENCRYPTION_CIPHERTEXT_NUM {
+            return None;
+        }
+        let key = SecretKey::from_ovk(ovk, cm, sender_pk);
+        let msg = ResourceCiphertext::from(self.0.clone()).decrypt(&key)?;
+        let rcv_pk: Option<pallas::Affine> = pallas::Affine::from_xy(msg[0], msg[1]).into();
+        let rcv_pk = rcv_pk?.to_curve();
+        let sk = msg[2];
+
+        // Check the ephemeral sk matches the published sender pk
+        if GENERATOR.to_curve() * mod_r_p(sk) != *sender_pk {
+            return None;
+        }
+        Some((rcv_pk, sk))
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for OutgoingCiphertext {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        use byteorder::{LittleEndian, WriteBytesExt};
+        writer.write_u32::<LittleEndian>(self.0.len() as u32)?;
+        for element in self.0.iter() {
+            writer.write_all(&element.to_repr())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for OutgoingCiphertext {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        use byteorder::{LittleEndian, ReadBytesExt};
+        let len = reader.read_u32::<LittleEndian>()?;
+        let cipher: std::io::Result<Vec<_>> = (0..len)
+            .map(|_| crate::utils::read_base_field(reader))
+            .collect();
+        Ok(Self(cipher?))
+    }
+}
+
 #[test]
 fn test_halo2_resource_encryption() {
     use ff::Field;
@@ -193,3 +356,50 @@ fn test_halo2_resource_encryption() {
     let decryption = cipher.decrypt(&key).unwrap();
     assert_eq!(plaintext.to_vec(), decryption);
 }
+
+#[test]
+fn test_halo2_resource_encryption_variable_length() {
+    use ff::Field;
+    use group::Group;
+    use rand::{rngs::OsRng, RngCore};
+
+    let mut rng = OsRng;
+    let sk = pallas::Scalar::random(&mut rng);
+    let pk = pallas::Point::random(&mut rng);
+    let key = SecretKey::from_dh_exchange(&pk, &sk);
+    let encrypt_nonce = pallas::Base::random(&mut rng);
+
+    // Messages longer than the default plaintext length
+    for len in [RESOURCE_ENCRYPTION_PLAINTEXT_NUM + 1, 25] {
+        let message: Vec<_> = (0..len).map(|_| pallas::Base::random(&mut rng)).collect();
+        let plaintext = ResourcePlaintext::padding(&message);
+        assert_eq!(plaintext.inner().len(), len);
+        let cipher = ResourceCiphertext::encrypt(&plaintext, &key, &encrypt_nonce);
+        assert_eq!(cipher.inner().len(), len + 2);
+        assert_eq!(cipher.decrypt(&key).unwrap(), message);
+
+        // Truncating the ciphertext breaks the MAC
+        let mut truncated = cipher.inner().to_vec();
+        truncated.remove(0);
+        assert!(ResourceCiphertext::from(truncated).decrypt(&key).is_none());
+    }
+
+    // Byte packing
+    for len in [0, 1, 31, 32, 200] {
+        let mut bytes = vec![0u8; len];
+        rng.fill_bytes(&mut bytes);
+        let plaintext = ResourcePlaintext::from_bytes(&bytes);
+        let cipher = ResourceCiphertext::encrypt(&plaintext, &key, &encrypt_nonce);
+        let msg = cipher.decrypt(&key).unwrap();
+        assert_eq!(ResourcePlaintext::to_bytes(&msg).unwrap(), bytes);
+    }
+
+    // The length must be consistent with the packed bytes
+    let mut msg = ResourcePlaintext::from_bytes(&[1u8; 40]).to_vec();
+    msg[0] = pallas::Base::from(20u64);
+    assert!(ResourcePlaintext::to_bytes(&msg).is_none());
+
+    // A length beyond the message is rejected without overflowing
+    msg[0] = pallas::Base::from(u64::MAX);
+    assert!(ResourcePlaintext::to_bytes(&msg).is_none());
+}
@@ -1,4 +1,6 @@
-use crate::circuit::resource_logic_circuit::{ResourceLogic, ResourceLogicVerifyingInfo};
+use crate::circuit::resource_logic_circuit::{
+    ResourceLogic, ResourceLogicPublicInputs, ResourceLogicVerifyingInfo,
+};
 use crate::compliance::{ComplianceInfo, CompliancePublicInputs};
 use crate::constant::{
     COMPLIANCE_CIRCUIT_PARAMS_SIZE, COMPLIANCE_PROVING_KEY, COMPLIANCE_VERIFYING_KEY,
@@ -11,6 +13,8 @@ use crate::merkle_tree::Anchor;
 use crate::nullifier::Nullifier;
 use crate::proof::Proof;
 use crate::resource::{ResourceCommitment, ResourceLogics};
+use crate::resource_encryption::OutgoingCiphertext;
+use crate::transaction::ValidityWindow;
 use halo2_proofs::plonk::Error;
 use pasta_curves::pallas;
 use rand::RngCore;
@@ -37,6 +41,8 @@ pub struct ShieldedPartialTransaction {
     outputs: Vec<ResourceLogicVerifyingInfoSet>,
     binding_sig_r: Option<pallas::Scalar>,
     hints: Vec<u8>,
+    // The outgoing ciphertexts let the sender recover the output resources with the ovk.
+    out_ciphertexts: Vec<OutgoingCiphertext>,
 }
 
 #[derive(Debug, Clone)]
@@ -92,6 +98,7 @@ impl ShieldedPartialTransaction {
             outputs: outputs?,
             binding_sig_r: Some(rcv_sum),
             hints,
+            out_ciphertexts: vec![],
         })
     }
 
@@ -130,6 +137,7 @@ impl ShieldedPartialTransaction {
             outputs,
             binding_sig_r: Some(rcv_sum),
             hints,
+            out_ciphertexts: vec![],
         })
     }
 
@@ -216,6 +224,19 @@ impl ShieldedPartialTransaction {
         Ok(())
     }
 
+    // check the resource logics are proved against windows that contain the transaction validity window
+    pub fn check_validity_window(&self, window: &ValidityWindow) -> Result<(), TransactionError> {
+        for resource_logic_info in self.inputs.iter().chain(self.outputs.iter()) {
+            for public_inputs in resource_logic_info.get_public_inputs() {
+                match public_inputs.get_validity_window() {
+                    Some(logic_window) if logic_window.contains(window) => {}
+                    _ => return Err(TransactionError::InvalidValidityWindow),
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_binding_sig_r(&self) -> Option<pallas::Scalar> {
         self.binding_sig_r
     }
@@ -224,6 +245,19 @@ impl ShieldedPartialTransaction {
         self.hints.clone()
     }
 
+    pub fn get_outputs(&self) -> &[ResourceLogicVerifyingInfoSet] {
+        &self.outputs
+    }
+
+    pub fn get_out_ciphertexts(&self) -> &[OutgoingCiphertext] {
+        &self.out_ciphertexts
+    }
+
+    /// Attach the outgoing ciphertexts of the output resources. They are covered by the binding signature.
+    pub fn set_out_ciphertexts(&mut self, out_ciphertexts: Vec<OutgoingCiphertext>) {
+        self.out_ciphertexts = out_ciphertexts;
+    }
+
     pub fn clean_private_info(&mut self) {
         self.binding_sig_r = None;
         self.hints = vec![];
@@ -288,6 +322,7 @@ impl BorshSerialize for ShieldedPartialTransaction {
         };
 
         self.hints.serialize(writer)?;
+        self.out_ciphertexts.serialize(writer)?;
 
         Ok(())
     }
@@ -309,12 +344,14 @@ impl BorshDeserialize for ShieldedPartialTransaction {
         };
 
         let hints = Vec::<u8>::deserialize_reader(reader)?;
+        let out_ciphertexts = Vec::<OutgoingCiphertext>::deserialize_reader(reader)?;
         Ok(ShieldedPartialTransaction {
             compliances,
             inputs,
             outputs,
             binding_sig_r,
             hints,
+            out_ciphertexts,
         })
     }
 }
@@ -397,6 +434,17 @@ impl ResourceLogicVerifyingInfoSet {
         Ok(())
     }
 
+    // Get the public inputs of the application and dynamic resource logics
+    pub fn get_public_inputs(&self) -> Vec<&ResourceLogicPublicInputs> {
+        let mut public_inputs = vec![&self.app_resource_logic_verifying_info.public_inputs];
+        public_inputs.extend(
+            self.app_dynamic_resource_logic_verifying_info
+                .iter()
+                .map(|info| &info.public_inputs),
+        );
+        public_inputs
+    }
+
     pub fn get_resource_merkle_roots(&self) -> Vec<pallas::Base> {
         let mut roots: Vec<pallas::Base> = self
             .app_dynamic_resource_logic_verifying_info
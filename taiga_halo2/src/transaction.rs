@@ -6,10 +6,14 @@ use crate::executable::Executable;
 use crate::merkle_tree::Anchor;
 use crate::nullifier::Nullifier;
 use crate::resource::ResourceCommitment;
+use crate::resource_encryption::OutgoingCiphertext;
 use crate::shielded_ptx::ShieldedPartialTransaction;
 use crate::transparent_ptx::TransparentPartialTransaction;
 use blake2b_simd::Params as Blake2bParams;
-use pasta_curves::{group::Group, pallas};
+use pasta_curves::{
+    group::{ff::PrimeField, Group},
+    pallas,
+};
 use rand::{CryptoRng, RngCore};
 
 #[cfg(feature = "nif")]
@@ -28,10 +32,23 @@ pub struct Transaction {
     // TODO: Other parameters to be added.
     shielded_ptx_bundle: ShieldedPartialTxBundle,
     transparent_ptx_bundle: TransparentPartialTxBundle,
+    // the block heights the transaction is valid in
+    validity_window: ValidityWindow,
     // binding signature to check balance
     signature: BindingSignature,
 }
 
+/// The transaction is valid from the `not_before` height up to and including the `expires_at` height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "nif", derive(NifStruct))]
+#[cfg_attr(feature = "nif", module = "Taiga.Transaction.ValidityWindow")]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidityWindow {
+    pub not_before: u64,
+    pub expires_at: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "nif", derive(NifStruct))]
 #[cfg_attr(feature = "nif", module = "Taiga.Transaction.Result")]
@@ -41,6 +58,7 @@ pub struct TransactionResult {
     pub anchors: Vec<Anchor>,
     pub nullifiers: Vec<Nullifier>,
     pub output_cms: Vec<ResourceCommitment>,
+    pub validity_window: ValidityWindow,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -55,23 +73,71 @@ pub struct ShieldedPartialTxBundle(Vec<ShieldedPartialTransaction>);
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransparentPartialTxBundle(Vec<TransparentPartialTransaction>);
 
+impl ValidityWindow {
+    pub fn new(not_before: u64, expires_at: u64) -> Self {
+        Self {
+            not_before,
+            expires_at,
+        }
+    }
+
+    pub fn contains(&self, window: &ValidityWindow) -> bool {
+        self.not_before <= window.not_before && window.expires_at <= self.expires_at
+    }
+
+    pub fn contains_height(&self, height: u64) -> bool {
+        self.not_before <= height && height <= self.expires_at
+    }
+}
+
+impl Default for ValidityWindow {
+    // The default window is unbounded.
+    fn default() -> Self {
+        Self {
+            not_before: 0,
+            expires_at: u64::MAX,
+        }
+    }
+}
+
 impl Transaction {
-    // Generate the transaction
+    // Generate the transaction without validity bounds
     pub fn build<R: RngCore + CryptoRng>(
+        rng: R,
+        shielded_ptx_bundle: ShieldedPartialTxBundle,
+        transparent_ptx_bundle: TransparentPartialTxBundle,
+    ) -> Result<Self, TransactionError> {
+        Self::build_with_validity_window(
+            rng,
+            shielded_ptx_bundle,
+            transparent_ptx_bundle,
+            ValidityWindow::default(),
+        )
+    }
+
+    // Generate the transaction valid in the window. The resource logics must be proved against a
+    // window that contains it.
+    pub fn build_with_validity_window<R: RngCore + CryptoRng>(
         rng: R,
         mut shielded_ptx_bundle: ShieldedPartialTxBundle,
         transparent_ptx_bundle: TransparentPartialTxBundle,
+        validity_window: ValidityWindow,
     ) -> Result<Self, TransactionError> {
         assert!(!(shielded_ptx_bundle.is_empty() && transparent_ptx_bundle.is_empty()));
         let shielded_sk = shielded_ptx_bundle.get_binding_sig_r()?;
         let binding_sk = BindingSigningKey::from(shielded_sk);
-        let sig_hash = Self::digest(&shielded_ptx_bundle, &transparent_ptx_bundle);
+        let sig_hash = Self::digest(
+            &shielded_ptx_bundle,
+            &transparent_ptx_bundle,
+            &validity_window,
+        );
         let signature = binding_sk.sign(rng, &sig_hash);
         shielded_ptx_bundle.clean_private_info();
 
         Ok(Self {
             shielded_ptx_bundle,
             transparent_ptx_bundle,
+            validity_window,
             signature,
         })
     }
@@ -79,18 +145,39 @@ impl Transaction {
     #[allow(clippy::type_complexity)]
     pub fn execute(&self) -> Result<TransactionResult, TransactionError> {
         let mut result = self.shielded_ptx_bundle.execute()?;
-        let mut transparent_result = self.transparent_ptx_bundle.execute()?;
+        let mut transparent_result = self.transparent_ptx_bundle.execute(&self.validity_window)?;
         result.append(&mut transparent_result);
 
+        // check the resource logics are proved against the validity window
+        self.shielded_ptx_bundle
+            .check_validity_window(&self.validity_window)?;
+        result.validity_window = self.validity_window;
+
         // check balance
         self.verify_binding_sig()?;
 
         Ok(result)
     }
 
+    pub fn get_validity_window(&self) -> &ValidityWindow {
+        &self.validity_window
+    }
+
+    pub fn get_shielded_ptx_bundle(&self) -> &ShieldedPartialTxBundle {
+        &self.shielded_ptx_bundle
+    }
+
+    pub fn get_transparent_ptx_bundle(&self) -> &TransparentPartialTxBundle {
+        &self.transparent_ptx_bundle
+    }
+
     fn verify_binding_sig(&self) -> Result<(), TransactionError> {
         let binding_vk = self.get_binding_vk();
-        let sig_hash = Self::digest(&self.shielded_ptx_bundle, &self.transparent_ptx_bundle);
+        let sig_hash = Self::digest(
+            &self.shielded_ptx_bundle,
+            &self.transparent_ptx_bundle,
+            &self.validity_window,
+        );
         binding_vk
             .verify(&sig_hash, &self.signature)
             .map_err(|_| TransactionError::InvalidBindingSignature)
@@ -116,6 +203,7 @@ impl Transaction {
     fn digest(
         shielded_bundle: &ShieldedPartialTxBundle,
         transparent_bundle: &TransparentPartialTxBundle,
+        validity_window: &ValidityWindow,
     ) -> [u8; 32] {
         let mut h = Blake2bParams::new()
             .hash_length(32)
@@ -136,6 +224,13 @@ impl Transaction {
         shielded_bundle.get_anchors().iter().for_each(|anchor| {
             h.update(&anchor.to_bytes());
         });
+        shielded_bundle
+            .get_out_ciphertexts()
+            .iter()
+            .flat_map(|out_cipher| out_cipher.inner())
+            .for_each(|c| {
+                h.update(&c.to_repr());
+            });
 
         // TODO: the transparent digest may be not reasonable, fix it once the transparent execution is nailed down.
         transparent_bundle.get_nullifiers().iter().for_each(|nf| {
@@ -154,6 +249,9 @@ impl Transaction {
             h.update(&anchor.to_bytes());
         });
 
+        h.update(&validity_window.not_before.to_le_bytes());
+        h.update(&validity_window.expires_at.to_le_bytes());
+
         h.finalize().as_bytes().try_into().unwrap()
     }
 }
@@ -170,6 +268,7 @@ impl rustler::Encoder for Transaction {
             borsh::to_vec(&self.transparent_ptx_bundle)
                 .unwrap_or_default()
                 .encode(env),
+            self.validity_window.encode(env),
             borsh::to_vec(&self.signature)
                 .unwrap_or_default()
                 .encode(env),
@@ -181,10 +280,11 @@ impl rustler::Encoder for Transaction {
 #[cfg(feature = "nif")]
 impl<'a> Decoder<'a> for Transaction {
     fn decode(term: Term<'a>) -> NifResult<Self> {
-        let (term, shielded_ptx_bundle, transparent_bytes, sig_bytes): (
+        let (term, shielded_ptx_bundle, transparent_bytes, validity_window, sig_bytes): (
             atom::Atom,
             ShieldedPartialTxBundle,
             Vec<u8>,
+            ValidityWindow,
             Vec<u8>,
         ) = term.decode()?;
         if term == transaction() {
@@ -197,6 +297,7 @@ impl<'a> Decoder<'a> for Transaction {
                 shielded_ptx_bundle,
                 signature,
                 transparent_ptx_bundle,
+                validity_window,
             })
         } else {
             Err(rustler::Error::BadArg)
@@ -242,6 +343,10 @@ impl ShieldedPartialTxBundle {
         self.0.push(ptx);
     }
 
+    pub fn get_partial_txs(&self) -> &[ShieldedPartialTransaction] {
+        &self.0
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn execute(&self) -> Result<TransactionResult, TransactionError> {
         for partial_tx in self.0.iter() {
@@ -253,9 +358,17 @@ impl ShieldedPartialTxBundle {
             nullifiers: self.get_nullifiers(),
             output_cms: self.get_output_cms(),
             anchors: self.get_anchors(),
+            validity_window: ValidityWindow::default(),
         })
     }
 
+    pub fn check_validity_window(&self, window: &ValidityWindow) -> Result<(), TransactionError> {
+        for partial_tx in self.0.iter() {
+            partial_tx.check_validity_window(window)?;
+        }
+        Ok(())
+    }
+
     pub fn get_delta_commitments(&self) -> Vec<DeltaCommitment> {
         self.0
             .iter()
@@ -274,6 +387,13 @@ impl ShieldedPartialTxBundle {
     pub fn get_anchors(&self) -> Vec<Anchor> {
         self.0.iter().flat_map(|ptx| ptx.get_anchors()).collect()
     }
+
+    pub fn get_out_ciphertexts(&self) -> Vec<OutgoingCiphertext> {
+        self.0
+            .iter()
+            .flat_map(|ptx| ptx.get_out_ciphertexts().iter().cloned())
+            .collect()
+    }
 }
 
 impl TransparentPartialTxBundle {
@@ -289,15 +409,21 @@ impl TransparentPartialTxBundle {
         self.0.push(ptx);
     }
 
-    pub fn execute(&self) -> Result<TransactionResult, TransactionError> {
+    // The transparent resource logics are checked against the validity window when they are
+    // verified.
+    pub fn execute(
+        &self,
+        validity_window: &ValidityWindow,
+    ) -> Result<TransactionResult, TransactionError> {
         for partial_tx in self.0.iter() {
-            partial_tx.execute()?;
+            partial_tx.execute_in_validity_window(validity_window)?;
         }
 
         Ok(TransactionResult {
             nullifiers: self.get_nullifiers(),
             output_cms: self.get_output_cms(),
             anchors: self.get_anchors(),
+            validity_window: *validity_window,
         })
     }
 
@@ -372,4 +498,36 @@ pub mod testing {
             assert_eq!(_ret, de_ret);
         }
     }
+
+    #[test]
+    fn test_halo2_transaction_validity_window() {
+        use super::*;
+        use rand::rngs::OsRng;
+
+        let rng = OsRng;
+        let window = ValidityWindow::new(10, 100);
+
+        // The transparent resource logics are checked against the window as well
+        #[cfg(feature = "borsh")]
+        let transparent_ptx_bundle = create_transparent_ptx_bundle(1);
+        #[cfg(not(feature = "borsh"))]
+        let transparent_ptx_bundle = TransparentPartialTxBundle::default();
+
+        let tx = Transaction::build_with_validity_window(
+            rng,
+            create_shielded_ptx_bundle(1),
+            transparent_ptx_bundle,
+            window,
+        )
+        .unwrap();
+        let ret = tx.execute().unwrap();
+        assert_eq!(ret.validity_window, window);
+        assert!(ret.validity_window.contains_height(10));
+        assert!(!ret.validity_window.contains_height(101));
+
+        // The window is bound to the binding signature
+        let mut invalid_tx = tx.clone();
+        invalid_tx.validity_window = ValidityWindow::new(10, 200);
+        assert!(invalid_tx.execute().is_err());
+    }
 }
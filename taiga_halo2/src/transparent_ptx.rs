@@ -2,6 +2,7 @@ use crate::{
     circuit::resource_logic_bytecode::ApplicationByteCode, compliance::ComplianceInfo,
     delta_commitment::DeltaCommitment, error::TransactionError, executable::Executable,
     merkle_tree::Anchor, nullifier::Nullifier, resource::ResourceCommitment,
+    transaction::ValidityWindow,
 };
 
 use pasta_curves::pallas;
@@ -35,17 +36,20 @@ impl TransparentPartialTransaction {
             hints,
         }
     }
-}
 
-impl Executable for TransparentPartialTransaction {
-    fn execute(&self) -> Result<(), TransactionError> {
+    // Execute the partial transaction in the transaction validity window. The resource logics must
+    // be verified against a window that contains it.
+    pub fn execute_in_validity_window(
+        &self,
+        validity_window: &ValidityWindow,
+    ) -> Result<(), TransactionError> {
         // check resource logics, nullifiers, and resource commitments
         let compliance_nfs = self.get_nullifiers();
         let compliance_cms = self.get_output_cms();
         let compliance_resource_merkle_root = self.get_resource_merkle_root();
         for (resource_logic, nf) in self.input_resource_app.iter().zip(compliance_nfs.iter()) {
-            let self_resource_id =
-                resource_logic.verify_transparently(&compliance_resource_merkle_root)?;
+            let self_resource_id = resource_logic
+                .verify_transparently(&compliance_resource_merkle_root, validity_window)?;
             // Make sure all resource logics are checked
             if self_resource_id != nf.inner() {
                 return Err(TransactionError::InconsistentSelfResourceID);
@@ -53,8 +57,8 @@ impl Executable for TransparentPartialTransaction {
         }
 
         for (resource_logic, cm) in self.output_resource_app.iter().zip(compliance_cms.iter()) {
-            let self_resource_id =
-                resource_logic.verify_transparently(&compliance_resource_merkle_root)?;
+            let self_resource_id = resource_logic
+                .verify_transparently(&compliance_resource_merkle_root, validity_window)?;
             // Make sure all resource logics are checked
             if self_resource_id != cm.inner() {
                 return Err(TransactionError::InconsistentSelfResourceID);
@@ -63,6 +67,12 @@ impl Executable for TransparentPartialTransaction {
 
         Ok(())
     }
+}
+
+impl Executable for TransparentPartialTransaction {
+    fn execute(&self) -> Result<(), TransactionError> {
+        self.execute_in_validity_window(&ValidityWindow::default())
+    }
 
     // get nullifiers from compliances
     fn get_nullifiers(&self) -> Vec<Nullifier> {
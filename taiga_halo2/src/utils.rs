@@ -1,4 +1,6 @@
-use crate::constant::{POSEIDON_TO_FIELD_U_0_POSTFIX, POSEIDON_TO_FIELD_U_1_POSTFIX};
+use crate::constant::{
+    POSEIDON_RATE, POSEIDON_TO_FIELD_U_0_POSTFIX, POSEIDON_TO_FIELD_U_1_POSTFIX, POSEIDON_WIDTH,
+};
 use halo2_gadgets::poseidon::primitives as poseidon;
 use halo2_proofs::arithmetic::CurveAffine;
 use pasta_curves::{
@@ -47,6 +49,54 @@ pub(crate) fn poseidon_hash_n<const L: usize>(message: [pallas::Base; L]) -> pal
         .hash(message)
 }
 
+/// Poseidon hash over a message of any length. The capacity element is initialized to
+/// `length * 2^64 + domain_tag`, and the message is padded with zeros to a multiple of the rate.
+/// Different domain tags separate the hashes of different data, and the length in the capacity
+/// keeps the zero padding unambiguous. With the zero domain tag, it equals `poseidon_hash_n` on a
+/// message of the same length.
+pub fn poseidon_hash_var_len(domain_tag: u64, message: &[pallas::Base]) -> pallas::Base {
+    let mut poseidon_sponge: poseidon::Sponge<
+        pallas::Base,
+        poseidon::P128Pow5T3,
+        poseidon::Absorbing<pallas::Base, POSEIDON_RATE>,
+        POSEIDON_WIDTH,
+        POSEIDON_RATE,
+    > = poseidon::Sponge::init([
+        pallas::Base::zero(),
+        pallas::Base::zero(),
+        poseidon_var_len_capacity(domain_tag, message.len()),
+    ]);
+
+    // An empty message is absorbed as one chunk of padding
+    let padded_len = poseidon_var_len_padded_len(message.len());
+    let padded_message = message
+        .iter()
+        .copied()
+        .chain(std::iter::repeat(pallas::Base::zero()))
+        .take(padded_len)
+        .collect::<Vec<_>>();
+    for chunk in padded_message.chunks(POSEIDON_RATE) {
+        for (idx, msg_element) in chunk.iter().enumerate() {
+            poseidon_sponge.state[idx] += msg_element;
+        }
+        poseidon::permute::<_, poseidon::P128Pow5T3, POSEIDON_WIDTH, POSEIDON_RATE>(
+            &mut poseidon_sponge.state,
+            &poseidon_sponge.mds_matrix,
+            &poseidon_sponge.round_constants,
+        );
+    }
+
+    poseidon_sponge.state[0]
+}
+
+pub(crate) fn poseidon_var_len_capacity(domain_tag: u64, message_len: usize) -> pallas::Base {
+    pallas::Base::from_u128(((message_len as u128) << 64) + domain_tag as u128)
+}
+
+pub(crate) fn poseidon_var_len_padded_len(message_len: usize) -> usize {
+    (message_len.max(1) + POSEIDON_RATE - 1) / POSEIDON_RATE * POSEIDON_RATE
+}
+
 pub fn poseidon_to_curve<const L: usize>(message: &[pallas::Base]) -> pallas::Point {
     let us = poseidon_to_field::<L>(message);
     let q0 = hashtocurve::map_to_curve_simple_swu::<pallas::Base, pallas::Point, pallas::Iso>(
@@ -97,6 +147,14 @@ pub fn to_field_elements(bytes: &[u8]) -> Vec<pallas::Base> {
         .collect::<Vec<pallas::Base>>()
 }
 
+/// Encodes a 32-byte digest to two field elements, the little-endian low and high halves.
+/// It matches the encoding of the resource logic commitments in the public inputs.
+pub fn digest_to_fields(digest: &[u8; 32]) -> [pallas::Base; 2] {
+    let low = pallas::Base::from_u128(u128::from_le_bytes(digest[..16].try_into().unwrap()));
+    let high = pallas::Base::from_u128(u128::from_le_bytes(digest[16..].try_into().unwrap()));
+    [low, high]
+}
+
 pub fn read_base_field<R: std::io::Read>(reader: &mut R) -> std::io::Result<pallas::Base> {
     let mut bytes = [0u8; 32];
     reader.read_exact(&mut bytes)?;
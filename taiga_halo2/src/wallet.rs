@@ -0,0 +1,338 @@
+use crate::{
+    circuit::resource_logic_circuit::ResourceLogicPublicInputs,
+    constant::RESOURCE_LOGIC_CIRCUIT_SELF_RESOURCE_ID_IDX,
+    executable::Executable,
+    keys::FullViewingKey,
+    nullifier::{Nullifier, NullifierKeyContainer},
+    resource::{Resource, ResourceCommitment},
+    shielded_ptx::ShieldedPartialTransaction,
+    transaction::Transaction,
+};
+use pasta_curves::{group::ff::PrimeField, pallas};
+use std::collections::HashSet;
+
+#[cfg(feature = "serde")]
+use serde;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+// The encrypted resource message: logic, label, value, quantity, nonce, npk, is_ephemeral, rseed
+const RESOURCE_MESSAGE_LEN: usize = 8;
+
+/// A resource recovered by the wallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedResource {
+    /// The resource contains the nullifier key so that it can be consumed.
+    pub resource: Resource,
+    pub cm: ResourceCommitment,
+    pub nf: Nullifier,
+    /// The position of the resource commitment in the commitment tree
+    pub position: u64,
+    pub is_spent: bool,
+}
+
+/// Wallet scans the transactions in order and tracks the resources owned by a full viewing key.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wallet {
+    fvk: FullViewingKey,
+    resources: Vec<OwnedResource>,
+    // The position of the next output commitment in the commitment tree
+    next_position: u64,
+    // The output commitments scanned so far, so that a rescanned ptx doesn't advance the position
+    scanned_cms: HashSet<ResourceCommitment>,
+}
+
+impl Wallet {
+    pub fn new(fvk: FullViewingKey) -> Self {
+        Self::from_position(fvk, 0)
+    }
+
+    /// Create a wallet that starts scanning at the given commitment tree position.
+    pub fn from_position(fvk: FullViewingKey, next_position: u64) -> Self {
+        Self {
+            fvk,
+            resources: vec![],
+            next_position,
+            scanned_cms: HashSet::new(),
+        }
+    }
+
+    pub fn get_fvk(&self) -> &FullViewingKey {
+        &self.fvk
+    }
+
+    pub fn get_next_position(&self) -> u64 {
+        self.next_position
+    }
+
+    pub fn get_resources(&self) -> &[OwnedResource] {
+        &self.resources
+    }
+
+    pub fn get_unspent_resources(&self) -> Vec<OwnedResource> {
+        self.resources
+            .iter()
+            .filter(|owned| !owned.is_spent)
+            .copied()
+            .collect()
+    }
+
+    /// Scan a transaction and return the new resources. The commitment positions follow the
+    /// order of `TransactionResult::output_cms`: shielded outputs first, then transparent outputs.
+    pub fn scan_transaction(&mut self, tx: &Transaction) -> Vec<OwnedResource> {
+        let mut found = vec![];
+        for ptx in tx.get_shielded_ptx_bundle().get_partial_txs() {
+            found.extend(self.scan_shielded_ptx(ptx));
+        }
+
+        // TODO: recover the owned resources from transparent ptxs.
+        let transparent_ptx_bundle = tx.get_transparent_ptx_bundle();
+        self.mark_spent(&transparent_ptx_bundle.get_nullifiers());
+        let output_cms = transparent_ptx_bundle.get_output_cms();
+        if !self.is_scanned(&output_cms) {
+            self.advance(&output_cms);
+        }
+
+        found
+    }
+
+    /// Scan a shielded partial transaction and return the new resources.
+    pub fn scan_shielded_ptx(&mut self, ptx: &ShieldedPartialTransaction) -> Vec<OwnedResource> {
+        self.mark_spent(&ptx.get_nullifiers());
+
+        // The ptx has been scanned, its outputs already have positions
+        let output_cms = ptx.get_output_cms();
+        if self.is_scanned(&output_cms) {
+            return vec![];
+        }
+
+        let mut found: Vec<OwnedResource> = vec![];
+        for output in ptx.get_outputs() {
+            // The resource can be encrypted in any resource logic of the output, e.g. the receiver logic.
+            for public_inputs in output.get_public_inputs() {
+                if let Some(owned) = self.try_decrypt(public_inputs, &output_cms) {
+                    let is_known = self
+                        .resources
+                        .iter()
+                        .chain(found.iter())
+                        .any(|known| known.cm == owned.cm);
+                    if !is_known {
+                        found.push(owned);
+                    }
+                }
+            }
+        }
+        self.advance(&output_cms);
+        self.resources.extend(found.iter());
+
+        found
+    }
+
+    /// Recover the resources sent from this wallet in the ptx with the ovk. The recovered
+    /// resources only contain the npk of the receivers.
+    pub fn recover_sent_resources(&self, ptx: &ShieldedPartialTransaction) -> Vec<Resource> {
+        let ovk = self.fvk.get_ovk();
+        let output_cms = ptx.get_output_cms();
+        ptx.get_out_ciphertexts()
+            .iter()
+            .filter_map(|out_cipher| {
+                ptx.get_outputs()
+                    .iter()
+                    .flat_map(|output| output.get_public_inputs())
+                    .find_map(|public_inputs| {
+                        let message = public_inputs.decrypt_outgoing(&ovk, out_cipher)?;
+                        let resource = decode_resource(&message)?;
+                        let cm = resource.commitment();
+                        let is_valid = cm.inner()
+                            == public_inputs
+                                .get_from_index(RESOURCE_LOGIC_CIRCUIT_SELF_RESOURCE_ID_IDX)
+                            && output_cms.contains(&cm);
+                        is_valid.then_some(resource)
+                    })
+            })
+            .collect()
+    }
+
+    /// Mark the owned resources as spent if their nullifiers are revealed.
+    pub fn mark_spent(&mut self, nullifiers: &[Nullifier]) {
+        self.resources
+            .iter_mut()
+            .filter(|owned| nullifiers.contains(&owned.nf))
+            .for_each(|owned| owned.is_spent = true);
+    }
+
+    // The output commitments are unique, so any known one means the outputs were scanned.
+    fn is_scanned(&self, output_cms: &[ResourceCommitment]) -> bool {
+        output_cms.iter().any(|cm| self.scanned_cms.contains(cm))
+    }
+
+    fn advance(&mut self, output_cms: &[ResourceCommitment]) {
+        self.next_position += output_cms.len() as u64;
+        self.scanned_cms.extend(output_cms.iter().copied());
+    }
+
+    // Decrypt the resource and check it against the output commitments.
+    fn try_decrypt(
+        &self,
+        public_inputs: &ResourceLogicPublicInputs,
+        output_cms: &[ResourceCommitment],
+    ) -> Option<OwnedResource> {
+        let message = public_inputs.decrypt(self.fvk.get_ivk().inner())?;
+        let mut resource = decode_resource(&message)?;
+        // Only the resources with our npk are owned.
+        if resource.get_npk() != self.fvk.get_npk() {
+            return None;
+        }
+        resource.nk_container = NullifierKeyContainer::from_key(self.fvk.get_nk());
+        let cm = resource.commitment();
+
+        // The resource must be the self resource of the resource logic.
+        if cm.inner() != public_inputs.get_from_index(RESOURCE_LOGIC_CIRCUIT_SELF_RESOURCE_ID_IDX) {
+            return None;
+        }
+
+        let index = output_cms.iter().position(|output_cm| *output_cm == cm)?;
+        let nf = resource.get_nf()?;
+        Some(OwnedResource {
+            resource,
+            cm,
+            nf,
+            position: self.next_position + index as u64,
+            is_spent: false,
+        })
+    }
+}
+
+// Rebuild the resource from the decrypted message.
+fn decode_resource(message: &[pallas::Base]) -> Option<Resource> {
+    if message.len() < RESOURCE_MESSAGE_LEN {
+        return None;
+    }
+
+    let quantity = {
+        let bytes = message[3].to_repr();
+        if bytes[8..].iter().any(|b| *b != 0) {
+            return None;
+        }
+        u64::from_le_bytes(bytes[..8].try_into().unwrap())
+    };
+    let is_ephemeral = if message[6] == pallas::Base::one() {
+        true
+    } else if message[6] == pallas::Base::zero() {
+        false
+    } else {
+        return None;
+    };
+
+    Some(Resource::from_full(
+        message[0],
+        message[1],
+        message[2],
+        quantity,
+        NullifierKeyContainer::from_npk(message[5]),
+        Nullifier::from(message[4]),
+        is_ephemeral,
+        message[7],
+    ))
+}
+
+#[cfg(test)]
+#[cfg(feature = "examples")]
+pub mod tests {
+    use super::Wallet;
+    use crate::{
+        circuit::resource_logic_examples::{
+            signature_verification::COMPRESSED_TOKEN_AUTH_VK,
+            token::{Token, TokenAuthorization},
+        },
+        compliance::ComplianceInfo,
+        constant::TAIGA_COMMITMENT_TREE_DEPTH,
+        keys::SpendingKey,
+        merkle_tree::MerklePath,
+        resource_tree::ResourceMerkleTreeLeaves,
+        shielded_ptx::ShieldedPartialTransaction,
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_wallet_scan_shielded_ptx() {
+        let mut rng = OsRng;
+        let alice_sk = SpendingKey::random(&mut rng);
+        let alice_address = alice_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+        let bob_sk = SpendingKey::random(&mut rng);
+        let bob_address = bob_sk.get_address(*COMPRESSED_TOKEN_AUTH_VK);
+
+        // Alice sends 5 btc to Bob
+        let token = Token::new("btc".to_string(), 5);
+        let input_resource = token.create_random_input_token_resource_from_key(&mut rng, &alice_sk);
+        let mut output_resource =
+            token.create_random_output_token_resource_to_address(&mut rng, &bob_address);
+        let merkle_path = MerklePath::random(&mut rng, TAIGA_COMMITMENT_TREE_DEPTH);
+        let compliance = ComplianceInfo::new(
+            *input_resource.resource(),
+            merkle_path,
+            None,
+            &mut output_resource.resource,
+            &mut rng,
+        );
+
+        let input_nf = input_resource.get_nf().unwrap();
+        let output_cm = output_resource.commitment();
+        let resource_merkle_tree =
+            ResourceMerkleTreeLeaves::new(vec![input_nf.inner(), output_cm.inner()]);
+        let input_resource_logics = input_resource.generate_input_token_resource_logics(
+            &mut rng,
+            TokenAuthorization::from_address(&alice_address),
+            alice_sk.get_auth_sk(),
+            resource_merkle_tree
+                .generate_path(input_nf.inner())
+                .unwrap(),
+        );
+        let output_resource_logics = output_resource.generate_output_token_resource_logics(
+            &mut rng,
+            TokenAuthorization::from_address(&bob_address),
+            resource_merkle_tree
+                .generate_path(output_cm.inner())
+                .unwrap(),
+        );
+        let ptx = ShieldedPartialTransaction::build(
+            vec![compliance],
+            vec![input_resource_logics],
+            vec![output_resource_logics],
+            vec![],
+            &mut rng,
+        )
+        .unwrap();
+
+        // Alice can't recover the output resource
+        let mut alice_wallet = Wallet::new(alice_sk.get_full_viewing_key());
+        assert!(alice_wallet.scan_shielded_ptx(&ptx).is_empty());
+        assert_eq!(alice_wallet.get_next_position(), 1);
+
+        // Bob recovers the output resource
+        let mut bob_wallet = Wallet::new(bob_sk.get_full_viewing_key());
+        let found = bob_wallet.scan_shielded_ptx(&ptx);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].cm, output_cm);
+        assert_eq!(found[0].position, 0);
+        assert_eq!(found[0].resource.quantity, 5);
+        assert_eq!(found[0].resource.get_nk(), Some(bob_sk.get_nk()));
+
+        // Rescanning doesn't duplicate the resource or advance the position
+        assert!(bob_wallet.scan_shielded_ptx(&ptx).is_empty());
+        assert_eq!(bob_wallet.get_resources().len(), 1);
+        assert_eq!(bob_wallet.get_next_position(), 1);
+        assert!(alice_wallet.scan_shielded_ptx(&ptx).is_empty());
+        assert_eq!(alice_wallet.get_next_position(), 1);
+
+        // Spend detection
+        let nf = found[0].nf;
+        bob_wallet.mark_spent(&[nf]);
+        assert!(bob_wallet.get_unspent_resources().is_empty());
+    }
+}